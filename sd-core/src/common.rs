@@ -130,3 +130,31 @@ impl PrettyPrint for Unit {
         RcDoc::nil()
     }
 }
+
+/// Whether `name` matches `pattern`, in which `*` stands for any sequence of characters.
+#[must_use]
+pub fn glob(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, pattern)) => name.strip_prefix(prefix).is_some_and(|name| {
+            name.char_indices()
+                .map(|(i, _)| i)
+                .chain([name.len()])
+                .any(|i| glob(pattern, &name[i..]))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob;
+
+    #[test]
+    fn globs() {
+        assert!(glob("*", ""));
+        assert!(glob("a*c", "abbc"));
+        assert!(glob("*.λ*", "x.λ"));
+        assert!(!glob("a*c", "abcd"));
+        assert!(!glob("lambda", "lambdas"));
+    }
+}
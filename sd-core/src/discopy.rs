@@ -0,0 +1,573 @@
+//! Interchange with [DisCoPy](https://discopy.org), through its JSON serialisation of diagrams.
+//!
+//! A DisCoPy diagram is a list of boxes, each applied at an offset into the wires
+//! left over by the boxes before it. Factories are matched by their last component,
+//! so diagrams from the `monoidal`, `symmetric`, `compact` and `frobenius` modules are all accepted.
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    common::{InOut, InOutIter},
+    dot::Label,
+    hypergraph::{
+        builder::{Fragment, HypergraphBuilder, HypergraphError, OutPort},
+        generic::{Ctx, Edge, Operation, Thunk, Weight},
+        traits::WithWeight,
+        Hypergraph,
+    },
+    monoidal::graph::{MonoidalGraph, MonoidalOp},
+    term::TermWeight,
+};
+
+/// The module used for the factories of exported diagrams.
+const MODULE: &str = "discopy.frobenius";
+
+/// A basic type, which labels a single wire.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ob {
+    pub factory: String,
+    pub name: String,
+}
+
+/// A type, which labels a list of wires.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ty {
+    pub factory: String,
+    /// Called `objects` before DisCoPy 1.0.
+    #[serde(alias = "objects")]
+    pub inside: Vec<Ob>,
+}
+
+/// A box of a diagram.
+///
+/// Swaps, cups and caps are given by the types on their `left` and `right` rather than a domain and codomain,
+/// and bubbles carry the diagram they enclose as their `arg`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Generator {
+    pub factory: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dom: Option<Ty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cod: Option<Ty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left: Option<Ty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right: Option<Ty>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_dagger: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arg: Option<Box<Diagram>>,
+}
+
+/// A diagram, as serialised by DisCoPy's `Diagram.to_tree`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagram {
+    pub factory: String,
+    pub dom: Ty,
+    pub cod: Ty,
+    pub boxes: Vec<Generator>,
+    pub offsets: Vec<usize>,
+}
+
+#[derive(Error, Debug)]
+pub enum DiscopyError {
+    #[error("Invalid DisCoPy JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Diagram has {boxes} boxes but {offsets} offsets")]
+    Offsets { boxes: usize, offsets: usize },
+    #[error("Box `{name}` is missing its {field}")]
+    Missing { name: String, field: &'static str },
+    #[error("Box `{name}` at offset {offset} does not fit in {wires} wires")]
+    OutOfRange {
+        name: String,
+        offset: usize,
+        wires: usize,
+    },
+    #[error("Box `{name}` expects a wire of type `{expected}` but found `{found}`")]
+    TypeMismatch {
+        name: String,
+        expected: String,
+        found: String,
+    },
+    #[error("Diagram should end with type {expected} but ends with {found}")]
+    Codomain { expected: String, found: String },
+    #[error("Hypergraph error: {0:?}")]
+    HypergraphError(#[from] HypergraphError<TermWeight>),
+}
+
+impl Ty {
+    fn new(names: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            factory: format!("{MODULE}.Ty"),
+            inside: names
+                .into_iter()
+                .map(|name| Ob {
+                    factory: "discopy.cat.Ob".to_owned(),
+                    name,
+                })
+                .collect(),
+        }
+    }
+
+    fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.inside.iter().map(|ob| ob.name.as_str())
+    }
+
+    fn len(&self) -> usize {
+        self.inside.len()
+    }
+}
+
+impl Display for Ty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.inside.is_empty() {
+            return f.write_str("Ty()");
+        }
+        f.write_str(&self.names().collect::<Vec<_>>().join(" @ "))
+    }
+}
+
+/// The kind of a box, from the last component of its factory.
+fn kind(factory: &str) -> &str {
+    factory.rsplit('.').next().unwrap_or(factory)
+}
+
+impl Generator {
+    fn new(kind: &str, name: String, dom: Ty, cod: Ty) -> Self {
+        Self {
+            factory: format!("{MODULE}.{kind}"),
+            name,
+            dom: Some(dom),
+            cod: Some(cod),
+            left: None,
+            right: None,
+            is_dagger: false,
+            arg: None,
+        }
+    }
+
+    /// A swap, cup or cap between the wires `left` and `right`.
+    fn sided(kind: &str, left: String, right: String) -> Self {
+        Self {
+            factory: format!("{MODULE}.{kind}"),
+            name: String::new(),
+            dom: None,
+            cod: None,
+            left: Some(Ty::new([left])),
+            right: Some(Ty::new([right])),
+            is_dagger: false,
+            arg: None,
+        }
+    }
+
+    /// The name of the box, falling back to its kind for unnamed structural boxes.
+    fn label(&self) -> String {
+        let name = if self.name.is_empty() {
+            kind(&self.factory).to_lowercase()
+        } else {
+            self.name.clone()
+        };
+        if self.is_dagger {
+            format!("{name}†")
+        } else {
+            name
+        }
+    }
+
+    fn sides(&self) -> Result<(&Ty, &Ty), DiscopyError> {
+        let missing = |field| DiscopyError::Missing {
+            name: self.label(),
+            field,
+        };
+        Ok((
+            self.left.as_ref().ok_or_else(|| missing("left"))?,
+            self.right.as_ref().ok_or_else(|| missing("right"))?,
+        ))
+    }
+
+    /// The domain and codomain of the box.
+    fn boundary(&self) -> Result<(Ty, Ty), DiscopyError> {
+        let concat = |x: &Ty, y: &Ty| Ty {
+            factory: x.factory.clone(),
+            inside: x.inside.iter().chain(&y.inside).cloned().collect(),
+        };
+        let empty = |x: &Ty| Ty {
+            factory: x.factory.clone(),
+            inside: vec![],
+        };
+        match (kind(&self.factory), &self.dom, &self.cod) {
+            (_, Some(dom), Some(cod)) => Ok((dom.clone(), cod.clone())),
+            ("Swap", ..) => {
+                let (left, right) = self.sides()?;
+                Ok((concat(left, right), concat(right, left)))
+            }
+            ("Cup", ..) => {
+                let (left, right) = self.sides()?;
+                Ok((concat(left, right), empty(left)))
+            }
+            ("Cap", ..) => {
+                let (left, right) = self.sides()?;
+                Ok((empty(left), concat(left, right)))
+            }
+            (_, None, _) => Err(DiscopyError::Missing {
+                name: self.label(),
+                field: "dom",
+            }),
+            (_, _, None) => Err(DiscopyError::Missing {
+                name: self.label(),
+                field: "cod",
+            }),
+        }
+    }
+}
+
+impl Diagram {
+    /// Parse a diagram from its JSON serialisation.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the JSON is not a serialised diagram.
+    pub fn from_json(json: &str) -> Result<Self, DiscopyError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Serialise the diagram to JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("diagrams are serialisable")
+    }
+
+    /// Build the hypergraph of the diagram, with wires labelled by their types.
+    ///
+    /// Swaps are absorbed into the wiring, spiders with a single input become copies or deletions,
+    /// bubbles become thunks, and every other box becomes an operation.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a box does not fit the wires it is applied to.
+    pub fn to_graph(&self) -> Result<Hypergraph<TermWeight>, DiscopyError> {
+        let mut builder = HypergraphBuilder::new(
+            self.dom
+                .names()
+                .map(|name| Label(name.to_owned()))
+                .collect(),
+            self.cod.len(),
+        );
+        let inputs = builder
+            .graph_inputs()
+            .zip(self.dom.names().map(ToOwned::to_owned))
+            .collect();
+        let wires = self.build(&mut builder, inputs)?;
+        let found: Vec<_> = wires.iter().map(|(_, name)| name.as_str()).collect();
+        if found.iter().ne(self.cod.names().collect::<Vec<_>>().iter()) {
+            return Err(DiscopyError::Codomain {
+                expected: self.cod.to_string(),
+                found: Ty::new(found.into_iter().map(ToOwned::to_owned)).to_string(),
+            });
+        }
+        for ((wire, _), in_port) in wires
+            .into_iter()
+            .zip(builder.graph_outputs().collect::<Vec<_>>())
+        {
+            builder.link(wire, in_port)?;
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Add the boxes of the diagram to `fragment`, taking its input wires and returning its output wires.
+    fn build<F: Fragment<Weight = TermWeight>>(
+        &self,
+        fragment: &mut F,
+        mut wires: Vec<(OutPort<TermWeight>, String)>,
+    ) -> Result<Vec<(OutPort<TermWeight>, String)>, DiscopyError> {
+        if self.boxes.len() != self.offsets.len() {
+            return Err(DiscopyError::Offsets {
+                boxes: self.boxes.len(),
+                offsets: self.offsets.len(),
+            });
+        }
+        for (generator, &offset) in self.boxes.iter().zip(&self.offsets) {
+            let (dom, cod) = generator.boundary()?;
+            if offset + dom.len() > wires.len() {
+                return Err(DiscopyError::OutOfRange {
+                    name: generator.label(),
+                    offset,
+                    wires: wires.len(),
+                });
+            }
+            for ((_, found), expected) in wires[offset..].iter().zip(dom.names()) {
+                if found != expected {
+                    return Err(DiscopyError::TypeMismatch {
+                        name: generator.label(),
+                        expected: expected.to_owned(),
+                        found: found.clone(),
+                    });
+                }
+            }
+            let inputs: Vec<_> = wires.drain(offset..offset + dom.len()).collect();
+            let outputs = generator.build(fragment, inputs, &cod)?;
+            wires.splice(offset..offset, outputs);
+        }
+        Ok(wires)
+    }
+}
+
+impl Generator {
+    /// Add the box to `fragment`, taking its input wires and returning its output wires.
+    fn build<F: Fragment<Weight = TermWeight>>(
+        &self,
+        fragment: &mut F,
+        mut inputs: Vec<(OutPort<TermWeight>, String)>,
+        cod: &Ty,
+    ) -> Result<Vec<(OutPort<TermWeight>, String)>, DiscopyError> {
+        let is_copy = || inputs.len() == 1 && cod.names().all(|name| name == inputs[0].1);
+        match (kind(&self.factory), &self.arg) {
+            ("Swap", _) => {
+                let (left, _) = self.sides()?;
+                inputs.rotate_left(left.len());
+                Ok(inputs)
+            }
+            ("Spider", _) if is_copy() => Ok(vec![inputs[0].clone(); cod.len()]),
+            (_, Some(arg)) => {
+                let bound = arg.bound_inputs(inputs.len())?;
+                let thunk = fragment.add_thunk(
+                    0,
+                    bound.iter().cloned().map(Label),
+                    arg.cod.len(),
+                    cod.names().map(|name| Label(name.to_owned())),
+                    Label(self.label()),
+                );
+                fragment.in_thunk(thunk.clone(), |mut cursor| {
+                    let wires = inputs
+                        .into_iter()
+                        .chain(thunk.bound_inputs().zip(bound))
+                        .collect();
+                    let wires = arg.build(&mut cursor, wires)?;
+                    for ((wire, _), in_port) in wires.into_iter().zip(thunk.graph_outputs()) {
+                        cursor.link(wire, in_port)?;
+                    }
+                    Ok::<_, DiscopyError>(())
+                })?;
+                Ok(thunk
+                    .outputs()
+                    .zip(cod.names().map(ToOwned::to_owned))
+                    .collect())
+            }
+            _ => {
+                let operation = fragment.add_operation(
+                    inputs.len(),
+                    cod.names().map(|name| Label(name.to_owned())),
+                    Label(self.label()),
+                );
+                for ((wire, _), in_port) in inputs.into_iter().zip(operation.inputs()) {
+                    fragment.link(wire, in_port)?;
+                }
+                Ok(operation
+                    .outputs()
+                    .zip(cod.names().map(ToOwned::to_owned))
+                    .collect())
+            }
+        }
+    }
+}
+
+impl Diagram {
+    /// The wires of the domain past the first `captured`, which are bound by a bubble around the diagram.
+    fn bound_inputs(&self, captured: usize) -> Result<Vec<String>, DiscopyError> {
+        if captured > self.dom.len() {
+            return Err(DiscopyError::OutOfRange {
+                name: "bubble".to_owned(),
+                offset: captured,
+                wires: self.dom.len(),
+            });
+        }
+        Ok(self
+            .dom
+            .names()
+            .skip(captured)
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+}
+
+impl<T: Ctx> MonoidalGraph<T>
+where
+    Weight<Edge<T>>: Display,
+    Weight<Operation<T>>: Display,
+    Weight<Thunk<T>>: Display,
+{
+    /// Convert the decomposition of the graph into a DisCoPy diagram, with a layer of boxes for each slice.
+    ///
+    /// Copies become spiders, swaps are broken down into swaps of adjacent wires, and thunks become bubbles.
+    /// Free inputs and outputs are taken in the order chosen by the layout.
+    #[must_use]
+    pub fn to_discopy(&self) -> Diagram {
+        let dom = Ty::new(
+            self.free_inputs
+                .iter()
+                .chain(&self.bound_inputs)
+                .map(|edge| edge.weight().to_string()),
+        );
+        let cod = Ty::new(
+            self.free_outputs
+                .iter()
+                .chain(&self.bound_outputs)
+                .map(|edge| edge.weight().to_string()),
+        );
+        let mut boxes = Vec::new();
+        let mut offsets = Vec::new();
+        for slice in &self.slices {
+            let mut offset = 0;
+            for op in &slice.ops {
+                for (generator, relative) in op_generators(op) {
+                    boxes.push(generator);
+                    offsets.push(offset + relative);
+                }
+                offset += op.number_of_outputs();
+            }
+        }
+
+        Diagram {
+            factory: format!("{MODULE}.Diagram"),
+            dom,
+            cod,
+            boxes,
+            offsets,
+        }
+    }
+}
+
+/// The boxes making up an operation of a slice, with their offsets relative to the operation.
+fn op_generators<T: Ctx>(op: &MonoidalOp<T>) -> Vec<(Generator, usize)>
+where
+    Weight<Edge<T>>: Display,
+    Weight<Operation<T>>: Display,
+    Weight<Thunk<T>>: Display,
+{
+    let input_types = || op.input_links().map(|(edge, _)| edge.weight().to_string());
+    let output_types = || op.output_links().map(|(edge, _)| edge.weight().to_string());
+    let generator = |kind: &str, name: String| {
+        Generator::new(kind, name, Ty::new(input_types()), Ty::new(output_types()))
+    };
+    match op {
+        MonoidalOp::Copy { copies: 1, .. } | MonoidalOp::Backlink { .. } => vec![],
+        MonoidalOp::Copy { .. } => vec![(generator("Spider", String::new()), 0)],
+        MonoidalOp::Operation { addr } => vec![(generator("Box", addr.weight().to_string()), 0)],
+        MonoidalOp::Thunk { addr, body } => {
+            let mut bubble = generator("Bubble", addr.weight().to_string());
+            bubble.arg = Some(Box::new(body.to_discopy()));
+            vec![(bubble, 0)]
+        }
+        MonoidalOp::Swap { out_to_in, .. } => {
+            // Sort the inputs into place by swapping adjacent wires.
+            let types: Vec<String> = input_types().collect();
+            let mut current: Vec<usize> = (0..out_to_in.len()).collect();
+            let mut swaps = Vec::new();
+            for (i, input) in out_to_in.iter().enumerate() {
+                let j = current.iter().position(|x| x == input).unwrap();
+                for p in (i..j).rev() {
+                    let swap = Generator::sided(
+                        "Swap",
+                        types[current[p]].clone(),
+                        types[current[p + 1]].clone(),
+                    );
+                    swaps.push((swap, p));
+                    current.swap(p, p + 1);
+                }
+            }
+            swaps
+        }
+        MonoidalOp::Cup { intermediate, .. } if intermediate.is_empty() => {
+            vec![(cup_or_cap("Cup", input_types().collect()), 0)]
+        }
+        MonoidalOp::Cap { intermediate, .. } if intermediate.is_empty() => {
+            vec![(cup_or_cap("Cap", output_types().collect()), 0)]
+        }
+        MonoidalOp::Cup { .. } => vec![(generator("Box", "cup".to_owned()), 0)],
+        MonoidalOp::Cap { .. } => vec![(generator("Box", "cap".to_owned()), 0)],
+    }
+}
+
+fn cup_or_cap(kind: &str, types: Vec<String>) -> Generator {
+    let [left, right]: [String; 2] = types.try_into().expect("cups and caps join two wires");
+    Generator::sided(kind, left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use dir_test::{dir_test, Fixture};
+
+    use super::Diagram;
+    use crate::{
+        hypergraph::{isomorphism::is_isomorphic, petgraph::to_pet},
+        lp::Solver,
+        monoidal::{graph::MonoidalGraph, wired_graph::from_graph},
+    };
+
+    fn load(path: &str) -> (String, Diagram) {
+        let name = std::path::Path::new(path)
+            .file_stem()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned();
+        let json = std::fs::read_to_string(path).unwrap();
+        (name, Diagram::from_json(&json).unwrap())
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    #[dir_test(dir: "$CARGO_MANIFEST_DIR/src/tests/discopy", glob: "*.json", loader: load, postfix: "discopy")]
+    fn discopy_round_trip(fixture: Fixture<(String, Diagram)>) {
+        let (name, diagram) = fixture.content();
+        let graph = diagram.to_graph().unwrap();
+        insta::assert_ron_snapshot!(format!("discopy_{name}"), to_pet(&graph));
+
+        let exported =
+            MonoidalGraph::from(&from_graph(&graph, Solver::default(), false)).to_discopy();
+        let reimported = Diagram::from_json(&exported.to_json())
+            .and_then(|diagram| diagram.to_graph())
+            .unwrap();
+        assert!(is_isomorphic(&graph, &reimported), "{}", exported.to_json());
+    }
+
+    #[test]
+    fn discopy_errors() {
+        let ty = |names: &str| {
+            format!(
+                r#"{{"factory": "discopy.monoidal.Ty", "inside": [{}]}}"#,
+                names
+                    .split_whitespace()
+                    .map(|name| format!(r#"{{"factory": "discopy.cat.Ob", "name": "{name}"}}"#))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        let diagram = |dom: &str, cod: &str, offset: usize| {
+            format!(
+                r#"{{"factory": "discopy.monoidal.Diagram", "dom": {}, "cod": {}, "boxes": [
+                    {{"factory": "discopy.monoidal.Box", "name": "f", "dom": {}, "cod": {}}}
+                ], "offsets": [{offset}]}}"#,
+                ty(dom),
+                ty(cod),
+                ty("x"),
+                ty("y"),
+            )
+        };
+        let cases = [
+            (diagram("x", "y", 1), "does not fit"),
+            (diagram("y", "y", 0), "expects a wire of type `x`"),
+            (diagram("x", "x", 0), "should end with type x"),
+            ("{}".to_owned(), "Invalid DisCoPy JSON"),
+        ];
+        for (json, message) in cases {
+            let error = Diagram::from_json(&json)
+                .and_then(|diagram| diagram.to_graph())
+                .expect_err(message);
+            assert!(error.to_string().contains(message), "{error}");
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::{collections::HashMap, fmt::Display, mem};
 
 use dot_structures::{Id, Vertex};
 use pretty::RcDoc;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
@@ -9,6 +10,8 @@ use crate::{
     common::Matchable,
     hypergraph::{
         builder::{Fragment, HypergraphBuilder, HypergraphError, InPort, OutPort},
+        diff::Similarity,
+        isomorphism::Equivalence,
         traits::{WireType, WithType, WithWeight},
         Edge, Hypergraph, Operation, Thunk, Weight,
     },
@@ -16,6 +19,7 @@ use crate::{
 };
 
 #[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Serialize))]
 pub struct Label(pub String);
 
 impl WithType for Label {
@@ -76,6 +80,30 @@ impl Weight for DotWeight {
     type ThunkWeight = Label;
 }
 
+impl Equivalence for DotWeight {
+    fn equivalent_edges(x: &Label, y: &Label) -> bool {
+        x.0 == y.0
+    }
+
+    fn equivalent_operations(x: &Label, y: &Label) -> bool {
+        x.0 == y.0
+    }
+
+    fn equivalent_thunks(x: &Label, y: &Label) -> bool {
+        x.0 == y.0
+    }
+}
+
+impl Similarity for DotWeight {
+    fn operation_key(weight: &Label) -> String {
+        weight.0.clone()
+    }
+
+    fn thunk_key(weight: &Label) -> String {
+        weight.0.clone()
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum DotError {
     #[error("Unsupported graph")]
@@ -91,7 +119,7 @@ fn id_to_string(id: &Id) -> String {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Serialize, Deserialize)]
 pub struct DotSettings {
     pub invert: bool,
     pub collect: bool,
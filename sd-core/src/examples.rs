@@ -1,4 +1,5 @@
 use crate::{
+    common::Unit,
     hypergraph::{
         generic::{Ctx, Endpoint, Node},
         traits::{EdgeLike, Graph, Keyable, NodeLike, WithWeight},
@@ -42,9 +43,11 @@ impl Keyable for DummyEdge {
 }
 
 impl WithWeight for DummyEdge {
-    type Weight = ();
+    type Weight = Unit;
 
-    fn weight(&self) -> Self::Weight {}
+    fn weight(&self) -> Self::Weight {
+        Unit
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
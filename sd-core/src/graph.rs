@@ -5,6 +5,7 @@ use std::{
 
 use derivative::Derivative;
 use either::Either::{self, Left, Right};
+use indexmap::IndexSet;
 use itertools::Itertools;
 #[cfg(test)]
 use serde::Serialize;
@@ -18,6 +19,8 @@ use crate::{
             fragment::{Fragment, ThunkCursor},
             HypergraphBuilder, HypergraphError, InPort, OutPort, ThunkBuilder,
         },
+        diff::Similarity,
+        isomorphism::Equivalence,
         traits::{WireType, WithType},
         Hypergraph, Weight,
     },
@@ -100,6 +103,43 @@ impl<T: Language> Name<T> {
 
 pub type SyntaxHypergraph<T> = Hypergraph<Syntax<T>>;
 
+/// Programs are equivalent up to renaming of bound variables and thunk addresses.
+impl<T: Language> Equivalence for Syntax<T> {
+    fn equivalent_edges(x: &Name<T>, y: &Name<T>) -> bool {
+        match (x, y) {
+            (Name::CF(x), Name::CF(y)) => x.is_some() == y.is_some(),
+            (Name::FreeVar(x), Name::FreeVar(y)) => x == y,
+            (Name::Nil | Name::BoundVar(_), Name::Nil | Name::BoundVar(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn equivalent_operations(x: &T::Op, y: &T::Op) -> bool {
+        x == y
+    }
+
+    fn equivalent_thunks(
+        x: &Either<T::Addr, T::BlockAddr>,
+        y: &Either<T::Addr, T::BlockAddr>,
+    ) -> bool {
+        x.is_left() == y.is_left()
+    }
+}
+
+/// Operations are identified by their name and symbol name, if any.
+impl<T: Language> Similarity for Syntax<T> {
+    fn operation_key(weight: &T::Op) -> String {
+        match weight.sym_name() {
+            Some(sym_name) => format!("{weight} @{sym_name}"),
+            None => weight.to_string(),
+        }
+    }
+
+    fn thunk_key(weight: &Either<T::Addr, T::BlockAddr>) -> String {
+        if weight.is_left() { "thunk" } else { "block" }.to_owned()
+    }
+}
+
 #[derive(Derivative, Error)]
 #[derivative(Debug(bound = ""))]
 pub enum ConvertError<T: Language> {
@@ -111,6 +151,8 @@ pub enum ConvertError<T: Language> {
     Aliased(Vec<T::Var>, T::Var),
     #[error("Attempted to shadow `{}`", .0.to_pretty())]
     Shadowed(T::Var),
+    #[error("Region isolated from above uses `{}`", .0.to_pretty())]
+    Captured(T::Var),
     #[error("Fragment did not have output")]
     NoOutputError,
     #[error("Uninitialised Inports for variables: {0:?}")]
@@ -348,6 +390,21 @@ where
                     vec![]
                 };
 
+                if op.is_isolated_from_above() {
+                    let mut captured = IndexSet::new();
+                    for arg in args {
+                        if let Value::Thunk(thunk) = arg {
+                            thunk.free_vars(&mut captured, self.sym_name_link);
+                        }
+                    }
+                    if let Some(var) = captured
+                        .into_iter()
+                        .find(|var| var.get_type() != WireType::SymName)
+                    {
+                        return Err(ConvertError::Captured(var));
+                    }
+                }
+
                 let len = args.len() + symbol.len();
 
                 let operation_node = self.fragment.add_operation(len, output_weights, op.clone());
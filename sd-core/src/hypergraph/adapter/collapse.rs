@@ -50,6 +50,10 @@ impl<G: Graph> CollapseGraph<G> {
         self.expanded = ByThinAddress(Arc::new(expanded));
     }
 
+    pub fn set_expanded(&mut self, expanded: ThunkMap<G::Ctx, bool>) {
+        self.expanded = ByThinAddress(Arc::new(expanded));
+    }
+
     pub fn set_all(&mut self, value: bool) {
         let mut expanded = self.expanded().clone();
         expanded.values_mut().for_each(|x| *x = value);
@@ -45,6 +45,10 @@ impl<G: Graph> CutGraph<G> {
         &self.cut_edges
     }
 
+    pub fn set_cut_edges(&mut self, cut_edges: EdgeMap<G::Ctx, bool>) {
+        self.cut_edges = ByThinAddress(Arc::new(cut_edges));
+    }
+
     pub fn toggle(&mut self, edge: &Edge<G::Ctx>) {
         let mut cut_edges = self.cut_edges().clone();
         cut_edges[&edge.key()] ^= true;
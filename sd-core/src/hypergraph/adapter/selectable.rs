@@ -51,6 +51,9 @@ impl<G: Graph> SelectableGraph<G> {
             #[call(index_mut)]
             pub fn selected_mut(&mut self, node: &Node<G::Ctx>) -> &mut bool;
 
+            #[call(iter)]
+            pub fn selected_nodes(&self) -> impl DoubleEndedIterator<Item = Node<G::Ctx>> + Clone + '_;
+
             pub fn is_empty(&self) -> bool;
             pub fn clear_selection(&mut self);
             pub fn extend_selection(&mut self, direction: Option<(Direction, usize)>);
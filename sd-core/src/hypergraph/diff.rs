@@ -0,0 +1,403 @@
+use std::collections::HashMap;
+
+use derivative::Derivative;
+use indexmap::IndexMap;
+
+use super::{
+    generic::{Endpoint, Node},
+    isomorphism::Equivalence,
+    traits::{EdgeLike, Graph, NodeLike, WithWeight},
+    Edge, Hypergraph,
+};
+
+/// Weights which can be summarised by a key, used to decide which nodes of two graphs
+/// could correspond to each other.
+pub trait Similarity: Equivalence {
+    /// Only operations with the same key can be matched, e.g. operations with the same name.
+    fn operation_key(weight: &Self::OperationWeight) -> String;
+    /// Only thunks with the same key can be matched.
+    fn thunk_key(weight: &Self::ThunkWeight) -> String;
+}
+
+/// How a node differs between two versions of a graph.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Change {
+    /// The node only exists in the new graph.
+    Added,
+    /// The node only exists in the old graph.
+    Removed,
+    /// The node exists in both graphs, but its weight or inputs are different.
+    Changed,
+}
+
+type Nodes<W> = IndexMap<Node<Hypergraph<W>>, Node<Hypergraph<W>>>;
+type Changes<W> = IndexMap<Node<Hypergraph<W>>, Change>;
+type Pair<W> = (Node<Hypergraph<W>>, Node<Hypergraph<W>>);
+
+/// The result of comparing two versions of a graph.
+/// Nodes which are not mentioned are unchanged.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), Default(bound = ""))]
+pub struct Diff<W: Similarity> {
+    matching: Nodes<W>,
+    old: Changes<W>,
+    new: Changes<W>,
+}
+
+impl<W: Similarity> Diff<W> {
+    /// The node of the new graph matched with `node` of the old graph.
+    #[must_use]
+    pub fn matched(&self, node: &Node<Hypergraph<W>>) -> Option<&Node<Hypergraph<W>>> {
+        self.matching.get(node)
+    }
+
+    /// Changes to nodes of the old graph.
+    #[must_use]
+    pub const fn old_changes(&self) -> &Changes<W> {
+        &self.old
+    }
+
+    /// Changes to nodes of the new graph.
+    #[must_use]
+    pub const fn new_changes(&self) -> &Changes<W> {
+        &self.new
+    }
+}
+
+/// Greedily builds a matching between the nodes of two graphs.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+struct Matcher<W: Similarity> {
+    forward: Nodes<W>,
+    backward: Nodes<W>,
+    /// Newly matched pairs of nodes whose neighbours have not been visited yet
+    queue: Vec<Pair<W>>,
+    /// The key of every node of both graphs, computed once
+    keys: HashMap<Node<Hypergraph<W>>, String>,
+}
+
+fn key<W: Similarity>(node: &Node<Hypergraph<W>>) -> String {
+    match node {
+        Node::Operation(op) => format!("op {}", W::operation_key(&op.weight())),
+        Node::Thunk(thunk) => format!("thunk {}", W::thunk_key(&thunk.weight())),
+    }
+}
+
+/// The position of `edge` among the `edges`.
+fn position<W: Similarity>(
+    mut edges: impl Iterator<Item = Edge<W>>,
+    edge: &Edge<W>,
+) -> Option<usize> {
+    edges.position(|e| &e == edge)
+}
+
+impl<W: Similarity> Matcher<W> {
+    fn is_matched(&self, x: &Node<Hypergraph<W>>, y: &Node<Hypergraph<W>>) -> bool {
+        self.forward.contains_key(x) || self.backward.contains_key(y)
+    }
+
+    /// Match `x` with `y` if they are both unmatched, have the same key,
+    /// and are contained in thunks which are (or can be) matched.
+    fn try_match(&mut self, x: Node<Hypergraph<W>>, y: Node<Hypergraph<W>>) -> bool {
+        if self.is_matched(&x, &y) || self.keys.get(&x) != self.keys.get(&y) {
+            return false;
+        }
+        let same_level = match (x.backlink(), y.backlink()) {
+            (None, None) => true,
+            (Some(a), Some(b)) => {
+                let (a, b) = (Node::Thunk(a), Node::Thunk(b));
+                self.forward.get(&a) == Some(&b) || self.try_match(a, b)
+            }
+            _ => false,
+        };
+        if !same_level {
+            return false;
+        }
+        self.forward.insert(x.clone(), y.clone());
+        self.backward.insert(y.clone(), x.clone());
+        self.queue.push((x, y));
+        true
+    }
+
+    /// Match the sources of corresponding edges.
+    fn match_sources(
+        &mut self,
+        xs: impl Iterator<Item = Edge<W>>,
+        ys: impl Iterator<Item = Edge<W>>,
+    ) {
+        for (x, y) in xs.zip(ys) {
+            match (x.source(), y.source()) {
+                (Endpoint::Node(x), Endpoint::Node(y)) => {
+                    self.try_match(x, y);
+                }
+                (Endpoint::Boundary(Some(x)), Endpoint::Boundary(Some(y))) => {
+                    self.try_match(Node::Thunk(x), Node::Thunk(y));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Match the targets of corresponding edges, pairing up targets which use them in the same position.
+    fn match_targets(
+        &mut self,
+        xs: impl Iterator<Item = Edge<W>>,
+        ys: impl Iterator<Item = Edge<W>>,
+    ) {
+        for (x, y) in xs.zip(ys) {
+            let targets: Vec<_> = y
+                .targets()
+                .filter_map(|target| match target {
+                    Endpoint::Node(node) => Some(node),
+                    Endpoint::Boundary(_) => None,
+                })
+                .collect();
+            for target in x.targets() {
+                let Endpoint::Node(target) = target else {
+                    continue;
+                };
+                let index = position(target.inputs(), &x);
+                for candidate in &targets {
+                    if position(candidate.inputs(), &y) == index
+                        && self.try_match(target.clone(), candidate.clone())
+                    {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Extend the matching along the edges of every newly matched pair of nodes.
+    fn propagate(&mut self) {
+        while let Some((x, y)) = self.queue.pop() {
+            self.match_sources(explicit_inputs(&x), explicit_inputs(&y));
+            self.match_targets(explicit_outputs(&x), explicit_outputs(&y));
+            if let (Node::Thunk(x), Node::Thunk(y)) = (&x, &y) {
+                self.match_targets(x.bound_graph_inputs(), y.bound_graph_inputs());
+                self.match_sources(x.bound_graph_outputs(), y.bound_graph_outputs());
+            }
+        }
+    }
+
+    /// Whether `x` and `y` are produced by matched nodes (or boundaries) in the same position.
+    fn corresponding_edges(&self, x: &Edge<W>, y: &Edge<W>) -> bool {
+        if !W::equivalent_edges(&x.weight(), &y.weight()) {
+            return false;
+        }
+        match (x.source(), y.source()) {
+            (Endpoint::Node(a), Endpoint::Node(b)) => {
+                self.forward.get(&a) == Some(&b)
+                    && position(a.outputs(), x) == position(b.outputs(), y)
+            }
+            (Endpoint::Boundary(None), Endpoint::Boundary(None)) => true,
+            (Endpoint::Boundary(Some(a)), Endpoint::Boundary(Some(b))) => {
+                position(a.bound_graph_inputs(), x) == position(b.bound_graph_inputs(), y)
+                    && self.forward.get(&Node::Thunk(a)) == Some(&Node::Thunk(b))
+            }
+            _ => false,
+        }
+    }
+
+    fn is_changed(&self, x: &Node<Hypergraph<W>>, y: &Node<Hypergraph<W>>) -> bool {
+        let same_weight = match (x, y) {
+            (Node::Operation(x), Node::Operation(y)) => {
+                W::equivalent_operations(&x.weight(), &y.weight())
+            }
+            (Node::Thunk(x), Node::Thunk(y)) => W::equivalent_thunks(&x.weight(), &y.weight()),
+            _ => false,
+        };
+        let (xs, ys): (Vec<_>, Vec<_>) =
+            (explicit_inputs(x).collect(), explicit_inputs(y).collect());
+        !same_weight
+            || xs.len() != ys.len()
+            || x.number_of_outputs() != y.number_of_outputs()
+            || xs
+                .iter()
+                .zip(&ys)
+                .any(|(x, y)| !self.corresponding_edges(x, y))
+    }
+}
+
+/// Inputs of a node, ignoring the free inputs of thunks.
+fn explicit_inputs<W: Similarity>(
+    node: &Node<Hypergraph<W>>,
+) -> Box<dyn Iterator<Item = Edge<W>> + '_> {
+    match node {
+        Node::Operation(op) => op.inputs(),
+        Node::Thunk(thunk) => Box::new(thunk.inputs().skip(thunk.number_of_free_graph_inputs())),
+    }
+}
+
+/// Outputs of a node, ignoring the free outputs of thunks.
+fn explicit_outputs<W: Similarity>(
+    node: &Node<Hypergraph<W>>,
+) -> Box<dyn Iterator<Item = Edge<W>> + '_> {
+    match node {
+        Node::Operation(op) => op.outputs(),
+        Node::Thunk(thunk) => Box::new(thunk.outputs().skip(thunk.number_of_free_graph_outputs())),
+    }
+}
+
+/// All nodes of `graph`, with thunks before the nodes they contain.
+fn all_nodes<W: Similarity>(graph: &impl Graph<Ctx = Hypergraph<W>>) -> Vec<Node<Hypergraph<W>>> {
+    let mut nodes = Vec::new();
+    for node in graph.nodes().rev() {
+        nodes.push(node.clone());
+        if let Node::Thunk(thunk) = &node {
+            nodes.extend(all_nodes(thunk));
+        }
+    }
+    nodes
+}
+
+/// Compare two versions of a graph.
+///
+/// Nodes are matched greedily: first nodes whose key is unique in both graphs (such as named functions),
+/// then neighbours of matched nodes, and finally any remaining nodes with the same key, in order.
+#[must_use]
+pub fn diff<W: Similarity>(old: &Hypergraph<W>, new: &Hypergraph<W>) -> Diff<W> {
+    let xs = all_nodes(old);
+    let ys = all_nodes(new);
+
+    let mut matcher = Matcher::default();
+
+    let mut buckets: IndexMap<String, (Vec<_>, Vec<_>)> = IndexMap::new();
+    for x in &xs {
+        let key = key(x);
+        matcher.keys.insert(x.clone(), key.clone());
+        buckets.entry(key).or_default().0.push(x.clone());
+    }
+    for y in &ys {
+        let key = key(y);
+        matcher.keys.insert(y.clone(), key.clone());
+        buckets.entry(key).or_default().1.push(y.clone());
+    }
+    for (x, y) in buckets
+        .values()
+        .filter_map(|(xs, ys)| match (&xs[..], &ys[..]) {
+            ([x], [y]) => Some((x.clone(), y.clone())),
+            _ => None,
+        })
+    {
+        matcher.try_match(x, y);
+    }
+
+    // The boundaries of the graphs always correspond.
+    matcher.match_targets(old.graph_inputs(), new.graph_inputs());
+    matcher.match_sources(old.graph_outputs(), new.graph_outputs());
+    matcher.propagate();
+
+    // Only nodes with the same key can be matched, so each node is only tried against its own bucket.
+    for x in &xs {
+        let (_, candidates) = &buckets[&matcher.keys[x]];
+        for y in candidates {
+            if matcher.try_match(x.clone(), y.clone()) {
+                matcher.propagate();
+                break;
+            }
+        }
+    }
+
+    let mut diff = Diff::default();
+    for x in &xs {
+        match matcher.forward.get(x) {
+            Some(y) => {
+                if matcher.is_changed(x, y) {
+                    diff.old.insert(x.clone(), Change::Changed);
+                    diff.new.insert(y.clone(), Change::Changed);
+                }
+            }
+            None => {
+                diff.old.insert(x.clone(), Change::Removed);
+            }
+        }
+    }
+    for y in &ys {
+        if !matcher.backward.contains_key(y) {
+            diff.new.insert(y.clone(), Change::Added);
+        }
+    }
+    diff.matching = matcher.forward;
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use from_pest::FromPest;
+    use pest::Parser;
+    use rstest::rstest;
+
+    use super::{diff, Change};
+    use crate::{
+        graph::SyntaxHypergraph,
+        hypergraph::{generic::Node, traits::WithWeight},
+        language::spartan::{Expr, Rule, Spartan, SpartanParser},
+    };
+
+    fn graph(source: &str) -> Result<SyntaxHypergraph<Spartan>> {
+        let mut pairs = SpartanParser::parse(Rule::program, source)?;
+        Ok(Expr::from_pest(&mut pairs)?.to_graph(false)?)
+    }
+
+    fn summary<'a>(
+        changes: impl Iterator<Item = (&'a Node<SyntaxHypergraph<Spartan>>, &'a Change)>,
+    ) -> Vec<(String, Change)> {
+        let mut summary: Vec<_> = changes
+            .map(|(node, change)| {
+                let name = match node {
+                    Node::Operation(op) => op.weight().to_string(),
+                    Node::Thunk(_) => "thunk".to_owned(),
+                };
+                (name, *change)
+            })
+            .collect();
+        summary.sort_by(|x, y| x.0.cmp(&y.0));
+        summary
+    }
+
+    #[rstest]
+    #[case("bind a = plus(x, 1) in times(a, 2)", "bind b = plus(x, 1) in times(b, 2)", &[], &[])]
+    #[case(
+        "bind a = plus(x, 1) in times(a, 2)",
+        "bind a = plus(x, 1) in minus(a, 2)",
+        &[("×", Change::Removed)],
+        &[("-", Change::Added)]
+    )]
+    #[case(
+        "bind a = plus(x, 1) in times(a, 2)",
+        "bind a = plus(y, 1) in times(a, 2)",
+        &[("+", Change::Changed)],
+        &[("+", Change::Changed)]
+    )]
+    #[case(
+        "bind a = plus(x, 1) in times(a, 2)",
+        "bind a = plus(x, 1) in bind b = minus(a, 3) in times(b, 2)",
+        &[("×", Change::Changed)],
+        &[("-", Change::Added), ("3", Change::Added), ("×", Change::Changed)]
+    )]
+    #[case(
+        "bind f = lambda(x . plus(x, 1)) in app(f, 2)",
+        "bind f = lambda(x . times(x, 1)) in app(f, 2)",
+        &[("+", Change::Removed)],
+        &[("×", Change::Added)]
+    )]
+    fn diff_spartan(
+        #[case] old: &str,
+        #[case] new: &str,
+        #[case] old_changes: &[(&str, Change)],
+        #[case] new_changes: &[(&str, Change)],
+    ) -> Result<()> {
+        let diff = diff(&graph(old)?, &graph(new)?);
+        let expected = |changes: &[(&str, Change)]| {
+            changes
+                .iter()
+                .map(|(name, change)| ((*name).to_owned(), *change))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summary(diff.old_changes().iter()), expected(old_changes));
+        assert_eq!(summary(diff.new_changes().iter()), expected(new_changes));
+        Ok(())
+    }
+}
@@ -0,0 +1,442 @@
+use derivative::Derivative;
+use indexmap::IndexMap;
+
+use super::{
+    generic::{Endpoint, Node},
+    traits::{EdgeLike, Graph, NodeLike, WithWeight},
+    Edge, Hypergraph, Thunk, Weight,
+};
+
+/// Weights which can be compared up to some notion of equivalence,
+/// such as renaming of bound variables.
+pub trait Equivalence: Weight {
+    fn equivalent_edges(x: &Self::EdgeWeight, y: &Self::EdgeWeight) -> bool;
+    fn equivalent_operations(x: &Self::OperationWeight, y: &Self::OperationWeight) -> bool;
+    fn equivalent_thunks(x: &Self::ThunkWeight, y: &Self::ThunkWeight) -> bool;
+}
+
+/// A structure preserving bijection between the nodes and edges of two hypergraphs.
+///
+/// Operations are matched with operations and thunks with thunks,
+/// respecting the order of their ports, their nesting and their weights (up to [`Equivalence`]).
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), Default(bound = ""))]
+pub struct Isomorphism<W: Weight> {
+    nodes: IndexMap<Node<Hypergraph<W>>, Node<Hypergraph<W>>>,
+    nodes_inverse: IndexMap<Node<Hypergraph<W>>, Node<Hypergraph<W>>>,
+    edges: IndexMap<Edge<W>, Edge<W>>,
+    edges_inverse: IndexMap<Edge<W>, Edge<W>>,
+}
+
+impl<W: Weight> Isomorphism<W> {
+    /// The node of the second graph matched with `node` of the first graph.
+    #[must_use]
+    pub fn node(&self, node: &Node<Hypergraph<W>>) -> Option<&Node<Hypergraph<W>>> {
+        self.nodes.get(node)
+    }
+
+    /// The edge of the second graph matched with `edge` of the first graph.
+    #[must_use]
+    pub fn edge(&self, edge: &Edge<W>) -> Option<&Edge<W>> {
+        self.edges.get(edge)
+    }
+
+    /// All matched pairs of nodes.
+    pub fn nodes(&self) -> impl Iterator<Item = (&Node<Hypergraph<W>>, &Node<Hypergraph<W>>)> {
+        self.nodes.iter()
+    }
+
+    /// All matched pairs of edges.
+    pub fn edges(&self) -> impl Iterator<Item = (&Edge<W>, &Edge<W>)> {
+        self.edges.iter()
+    }
+}
+
+/// Edges which have been matched but whose sources have not yet been visited.
+type Queue<W> = Vec<(Edge<W>, Edge<W>)>;
+
+impl<W: Equivalence> Isomorphism<W> {
+    fn add_edge(&mut self, x: Edge<W>, y: Edge<W>, queue: &mut Queue<W>) -> bool {
+        match (self.edges.get(&x), self.edges_inverse.get(&y)) {
+            (Some(z), _) => return *z == y,
+            (None, Some(_)) => return false,
+            (None, None) => {}
+        }
+        if !W::equivalent_edges(&x.weight(), &y.weight())
+            || x.targets().count() != y.targets().count()
+        {
+            return false;
+        }
+        self.edges.insert(x.clone(), y.clone());
+        self.edges_inverse.insert(y.clone(), x.clone());
+        queue.push((x, y));
+        true
+    }
+
+    fn add_edges(
+        &mut self,
+        xs: impl Iterator<Item = Edge<W>>,
+        ys: impl Iterator<Item = Edge<W>>,
+        queue: &mut Queue<W>,
+    ) -> bool {
+        xs.zip(ys).all(|(x, y)| self.add_edge(x, y, queue))
+    }
+
+    fn add_backlinks(
+        &mut self,
+        x: Option<Thunk<W>>,
+        y: Option<Thunk<W>>,
+        queue: &mut Queue<W>,
+    ) -> bool {
+        match (x, y) {
+            (None, None) => true,
+            (Some(x), Some(y)) => self.add_node(Node::Thunk(x), Node::Thunk(y), queue),
+            _ => false,
+        }
+    }
+
+    fn add_node(
+        &mut self,
+        x: Node<Hypergraph<W>>,
+        y: Node<Hypergraph<W>>,
+        queue: &mut Queue<W>,
+    ) -> bool {
+        match (self.nodes.get(&x), self.nodes_inverse.get(&y)) {
+            (Some(z), _) => return *z == y,
+            (None, Some(_)) => return false,
+            (None, None) => {}
+        }
+
+        match (&x, &y) {
+            (Node::Operation(op_x), Node::Operation(op_y)) => {
+                if !W::equivalent_operations(&op_x.weight(), &op_y.weight())
+                    || op_x.number_of_inputs() != op_y.number_of_inputs()
+                    || op_x.number_of_outputs() != op_y.number_of_outputs()
+                {
+                    return false;
+                }
+            }
+            (Node::Thunk(thunk_x), Node::Thunk(thunk_y)) => {
+                if !W::equivalent_thunks(&thunk_x.weight(), &thunk_y.weight())
+                    || thunk_x.number_of_inputs() != thunk_y.number_of_inputs()
+                    || thunk_x.number_of_outputs() != thunk_y.number_of_outputs()
+                    || thunk_x.number_of_free_graph_inputs()
+                        != thunk_y.number_of_free_graph_inputs()
+                    || thunk_x.number_of_free_graph_outputs()
+                        != thunk_y.number_of_free_graph_outputs()
+                    || thunk_x.number_of_bound_graph_inputs()
+                        != thunk_y.number_of_bound_graph_inputs()
+                    || thunk_x.number_of_bound_graph_outputs()
+                        != thunk_y.number_of_bound_graph_outputs()
+                    || thunk_x.nodes().count() != thunk_y.nodes().count()
+                {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+
+        self.nodes.insert(x.clone(), y.clone());
+        self.nodes_inverse.insert(y.clone(), x.clone());
+
+        if !self.add_backlinks(x.backlink(), y.backlink(), queue) {
+            return false;
+        }
+
+        match (&x, &y) {
+            (Node::Operation(op_x), Node::Operation(op_y)) => {
+                self.add_edges(op_x.inputs(), op_y.inputs(), queue)
+                    && self.add_edges(op_x.outputs(), op_y.outputs(), queue)
+            }
+            (Node::Thunk(thunk_x), Node::Thunk(thunk_y)) => {
+                // Free inputs and outputs are ordered arbitrarily, so they are only
+                // matched through the nodes which use or define them.
+                let free_inputs = thunk_x.number_of_free_graph_inputs();
+                let free_outputs = thunk_x.number_of_free_graph_outputs();
+                self.add_edges(
+                    thunk_x.inputs().skip(free_inputs),
+                    thunk_y.inputs().skip(free_inputs),
+                    queue,
+                ) && self.add_edges(
+                    thunk_x.outputs().skip(free_outputs),
+                    thunk_y.outputs().skip(free_outputs),
+                    queue,
+                ) && self.add_edges(
+                    thunk_x.bound_graph_inputs(),
+                    thunk_y.bound_graph_inputs(),
+                    queue,
+                ) && self.add_edges(
+                    thunk_x.bound_graph_outputs(),
+                    thunk_y.bound_graph_outputs(),
+                    queue,
+                )
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Match the sources of every edge in the queue, until no more matches are forced.
+    fn propagate(&mut self, mut queue: Queue<W>) -> bool {
+        while let Some((x, y)) = queue.pop() {
+            let matched = match (x.source(), y.source()) {
+                (Endpoint::Node(x), Endpoint::Node(y)) => self.add_node(x, y, &mut queue),
+                (Endpoint::Boundary(x), Endpoint::Boundary(y)) => {
+                    self.add_backlinks(x, y, &mut queue)
+                }
+                _ => false,
+            };
+            if !matched {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Try matching `x` with `y`, extending the isomorphism with everything this forces.
+    fn try_match(&self, x: Node<Hypergraph<W>>, y: Node<Hypergraph<W>>) -> Option<Self> {
+        let mut next = self.clone();
+        let mut queue = Vec::new();
+        (next.add_node(x, y, &mut queue) && next.propagate(queue)).then_some(next)
+    }
+
+    /// Extend the isomorphism to all of `xs`, backtracking over the possible choices.
+    fn search(self, xs: &[Node<Hypergraph<W>>], ys: &[Node<Hypergraph<W>>]) -> Option<Self> {
+        let unmatched: Vec<_> = xs.iter().filter(|x| !self.nodes.contains_key(*x)).collect();
+        if unmatched.is_empty() {
+            return Some(self);
+        }
+
+        // Prefer a node with an input we already know the image of,
+        // as only nodes using that image in the same position are candidates.
+        let constrained = unmatched.iter().find_map(|x| {
+            x.inputs()
+                .enumerate()
+                .find_map(|(i, edge)| Some((*x, i, self.edges.get(&edge)?.clone())))
+        });
+
+        let (x, candidates): (_, Vec<_>) = if let Some((x, i, edge)) = constrained {
+            let candidates = edge
+                .targets()
+                .filter_map(|target| match target {
+                    Endpoint::Node(y) if y.inputs().nth(i).as_ref() == Some(&edge) => Some(y),
+                    _ => None,
+                })
+                .collect();
+            (x, candidates)
+        } else {
+            let x = unmatched[0];
+            let candidates = ys
+                .iter()
+                .filter(|y| !self.nodes_inverse.contains_key(*y))
+                .cloned()
+                .collect();
+            (x, candidates)
+        };
+
+        candidates
+            .into_iter()
+            .filter_map(|y| self.try_match(x.clone(), y))
+            .find_map(|next| next.search(xs, ys))
+    }
+}
+
+/// All nodes of `graph`, including those nested inside thunks.
+fn all_nodes<W: Weight>(graph: &impl Graph<Ctx = Hypergraph<W>>) -> Vec<Node<Hypergraph<W>>> {
+    let mut nodes = Vec::new();
+    for node in graph.nodes() {
+        if let Node::Thunk(thunk) = &node {
+            nodes.extend(all_nodes(thunk));
+        }
+        nodes.push(node);
+    }
+    nodes
+}
+
+/// Find an isomorphism between two hypergraphs, if one exists.
+///
+/// Graph inputs and outputs are matched in order.
+#[must_use]
+pub fn find_isomorphism<W: Equivalence>(
+    x: &Hypergraph<W>,
+    y: &Hypergraph<W>,
+) -> Option<Isomorphism<W>> {
+    if x.number_of_graph_inputs() != y.number_of_graph_inputs()
+        || x.number_of_graph_outputs() != y.number_of_graph_outputs()
+    {
+        return None;
+    }
+
+    let xs = all_nodes(x);
+    let ys = all_nodes(y);
+    if xs.len() != ys.len() {
+        return None;
+    }
+
+    let mut isomorphism = Isomorphism::default();
+    let mut queue = Vec::new();
+    if !(isomorphism.add_edges(x.graph_inputs(), y.graph_inputs(), &mut queue)
+        && isomorphism.add_edges(x.graph_outputs(), y.graph_outputs(), &mut queue)
+        && isomorphism.propagate(queue))
+    {
+        return None;
+    }
+
+    isomorphism.search(&xs, &ys)
+}
+
+/// Check whether two hypergraphs are isomorphic.
+#[must_use]
+pub fn is_isomorphic<W: Equivalence>(x: &Hypergraph<W>, y: &Hypergraph<W>) -> bool {
+    find_isomorphism(x, y).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use from_pest::FromPest;
+    use pest::Parser;
+    use rstest::rstest;
+
+    use super::is_isomorphic;
+    use crate::{
+        graph::SyntaxHypergraph,
+        language::{
+            chil::{self, Chil, ChilParser},
+            mlir::{
+                self,
+                internal::{MlirParser, TopLevelItem},
+                Mlir, Module,
+            },
+            spartan::{Expr, Rule, Spartan, SpartanParser},
+        },
+    };
+
+    fn graph(source: &str) -> Result<SyntaxHypergraph<Spartan>> {
+        let mut pairs = SpartanParser::parse(Rule::program, source)?;
+        Ok(Expr::from_pest(&mut pairs)?.to_graph(false)?)
+    }
+
+    fn chil_graph(source: &str) -> Result<SyntaxHypergraph<Chil>> {
+        let mut pairs = ChilParser::parse(chil::Rule::program, source)?;
+        Ok(chil::Expr::from_pest(&mut pairs)?.to_graph(false)?)
+    }
+
+    fn mlir_graph(source: &str) -> Result<SyntaxHypergraph<Mlir>> {
+        let mut pairs = MlirParser::parse(mlir::internal::Rule::toplevel, source)?;
+        let module = Module::from(Vec::<TopLevelItem>::from_pest(&mut pairs)?);
+        Ok(module.expr.to_graph(false)?)
+    }
+
+    #[rstest]
+    #[case("plus(x, 1)", "plus(x, 1)", true)]
+    #[case("bind a = plus(x, 1) in a", "bind b = plus(x, 1) in b", true)]
+    #[case("lambda(x . plus(x, y))", "lambda(z . plus(z, y))", true)]
+    #[case(
+        "bind a = plus(x, y) in bind b = times(x, y) in minus(a, b)",
+        "bind b = times(x, y) in bind a = plus(x, y) in minus(a, b)",
+        true
+    )]
+    #[case(
+        "bind f = lambda(x . plus(x, 1)) in app(f, app(f, 2))",
+        "bind g = lambda(y . plus(y, 1)) in app(g, app(g, 2))",
+        true
+    )]
+    #[case("plus(x, 1)", "plus(y, 1)", false)]
+    #[case("minus(x, y)", "minus(y, x)", false)]
+    #[case("plus(x, 1)", "times(x, 1)", false)]
+    #[case(
+        "bind a = plus(x, 1) in times(a, a)",
+        "times(plus(x, 1), plus(x, 1))",
+        false
+    )]
+    #[case("lambda(x . plus(x, y))", "lambda(x . plus(y, x))", false)]
+    fn isomorphic_spartan(
+        #[case] left: &str,
+        #[case] right: &str,
+        #[case] expected: bool,
+    ) -> Result<()> {
+        let (left, right) = (graph(left)?, graph(right)?);
+        assert_eq!(is_isomorphic(&left, &right), expected);
+        assert_eq!(is_isomorphic(&right, &left), expected);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(
+        "def %0 = +(%10, %11)\noutput %0",
+        "def %5 = +(%10, %11)\noutput %5",
+        true
+    )]
+    #[case(
+        "def %0 = func(thunk @1 = { %2 => def %3 = +(%2, %10)\noutput %3 })\noutput %0",
+        "def %4 = func(thunk @7 = { %8 => def %9 = +(%8, %10)\noutput %9 })\noutput %4",
+        true
+    )]
+    #[case(
+        "def %0 = +(%10, %11)\noutput %0",
+        "def %0 = +(%11, %10)\noutput %0",
+        false
+    )]
+    #[case(
+        "def %0 = +(%10, %11)\noutput %0",
+        "def %0 = -(%10, %11)\noutput %0",
+        false
+    )]
+    fn isomorphic_chil(
+        #[case] left: &str,
+        #[case] right: &str,
+        #[case] expected: bool,
+    ) -> Result<()> {
+        let (left, right) = (chil_graph(left)?, chil_graph(right)?);
+        assert_eq!(is_isomorphic(&left, &right), expected);
+        assert_eq!(is_isomorphic(&right, &left), expected);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(
+        r#"%0 = "arith.constant"() {value = 1 : i32} : () -> i32
+%1 = "arith.addi"(%0, %0) : (i32, i32) -> i32"#,
+        r#"%c = "arith.constant"() {value = 1 : i32} : () -> i32
+%sum = "arith.addi"(%c, %c) : (i32, i32) -> i32"#,
+        true
+    )]
+    #[case(
+        r#"%0 = "arith.constant"() {value = 1 : i32} : () -> i32 loc("a.mlir":1:1)"#,
+        r#"%0 = "arith.constant"() {value = 1 : i32} : () -> i32 loc("b.mlir":7:3)"#,
+        true
+    )]
+    #[case(
+        r#"%0 = "arith.constant"() {value = 1 : i32} : () -> i32
+"scf.while"() ({
+  %1 = "arith.addi"(%0, %0) : (i32, i32) -> i32
+  "scf.condition"(%1) : (i32) -> ()
+}) : () -> ()"#,
+        r#"%a = "arith.constant"() {value = 1 : i32} : () -> i32
+"scf.while"() ({
+  %b = "arith.addi"(%a, %a) : (i32, i32) -> i32
+  "scf.condition"(%b) : (i32) -> ()
+}) : () -> ()"#,
+        true
+    )]
+    #[case(
+        r#"%0 = "arith.constant"() {value = 1 : i32} : () -> i32"#,
+        r#"%0 = "arith.constant"() {value = 2 : i32} : () -> i32"#,
+        false
+    )]
+    #[case(
+        r#"%0 = "arith.constant"() {value = 1 : i32} : () -> i32"#,
+        r#"%0 = "arith.constant"() <{value = 1 : i32}> : () -> i32"#,
+        false
+    )]
+    fn isomorphic_mlir(
+        #[case] left: &str,
+        #[case] right: &str,
+        #[case] expected: bool,
+    ) -> Result<()> {
+        let (left, right) = (mlir_graph(left)?, mlir_graph(right)?);
+        assert_eq!(is_isomorphic(&left, &right), expected);
+        assert_eq!(is_isomorphic(&right, &left), expected);
+        Ok(())
+    }
+}
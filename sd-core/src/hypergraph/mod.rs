@@ -8,11 +8,15 @@ use derivative::Derivative;
 
 pub mod adapter;
 pub mod builder;
+pub mod diff;
 pub mod generic;
 mod internal;
+pub mod isomorphism;
 pub mod mapping;
+pub mod path;
 pub mod petgraph;
 pub mod reachability;
+pub mod rewrite;
 pub mod subgraph;
 pub mod traits;
 pub mod utils;
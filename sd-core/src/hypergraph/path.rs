@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    generic::{Ctx, Edge, Endpoint, Node, Thunk},
+    traits::{EdgeLike, Graph, NodeLike},
+};
+
+/// A stable identity for a node, which (unlike the node itself) survives recompiling the same program.
+///
+/// It consists of the index of each enclosing thunk among the nodes of its parent,
+/// outermost first, followed by the index of the node itself.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct NodePath(pub Vec<usize>);
+
+/// A stable identity for an edge, given by where it comes from.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum EdgePath {
+    /// The edge is an input of the graph.
+    GraphInput(usize),
+    /// The edge is a bound input of a thunk.
+    BoundInput(NodePath, usize),
+    /// The edge is an output of a node.
+    Output(NodePath, usize),
+}
+
+impl NodePath {
+    /// The path to `node` inside `graph`.
+    pub fn of<T: Ctx>(graph: &impl Graph<Ctx = T>, node: &Node<T>) -> Option<Self> {
+        let mut path = Vec::new();
+        let mut node = node.clone();
+        loop {
+            let backlink = node.backlink();
+            let index = match &backlink {
+                Some(thunk) => thunk.nodes().position(|x| x == node)?,
+                None => graph.nodes().position(|x| x == node)?,
+            };
+            path.push(index);
+            match backlink {
+                Some(thunk) => node = Node::Thunk(thunk),
+                None => break,
+            }
+        }
+        path.reverse();
+        Some(Self(path))
+    }
+
+    /// The node at this path inside `graph`, if there is one.
+    pub fn resolve<T: Ctx>(&self, graph: &impl Graph<Ctx = T>) -> Option<Node<T>> {
+        let (last, init) = self.0.split_last()?;
+        let mut thunk: Option<Thunk<T>> = None;
+        for &index in init {
+            let node = match &thunk {
+                Some(thunk) => thunk.nodes().nth(index)?,
+                None => graph.nodes().nth(index)?,
+            };
+            thunk = Some(node.into_thunk()?);
+        }
+        match &thunk {
+            Some(thunk) => thunk.nodes().nth(*last),
+            None => graph.nodes().nth(*last),
+        }
+    }
+}
+
+impl EdgePath {
+    /// The path to `edge` inside `graph`.
+    pub fn of<T: Ctx>(graph: &impl Graph<Ctx = T>, edge: &Edge<T>) -> Option<Self> {
+        match edge.source() {
+            Endpoint::Node(node) => {
+                let index = node.outputs().position(|x| &x == edge)?;
+                Some(Self::Output(NodePath::of(graph, &node)?, index))
+            }
+            Endpoint::Boundary(Some(thunk)) => {
+                let index = thunk.bound_graph_inputs().position(|x| &x == edge)?;
+                Some(Self::BoundInput(
+                    NodePath::of(graph, &Node::Thunk(thunk))?,
+                    index,
+                ))
+            }
+            Endpoint::Boundary(None) => {
+                let index = graph.graph_inputs().position(|x| &x == edge)?;
+                Some(Self::GraphInput(index))
+            }
+        }
+    }
+
+    /// The edge at this path inside `graph`, if there is one.
+    pub fn resolve<T: Ctx>(&self, graph: &impl Graph<Ctx = T>) -> Option<Edge<T>> {
+        match self {
+            Self::GraphInput(index) => graph.graph_inputs().nth(*index),
+            Self::BoundInput(path, index) => path
+                .resolve(graph)?
+                .into_thunk()?
+                .bound_graph_inputs()
+                .nth(*index),
+            Self::Output(path, index) => path.resolve(graph)?.outputs().nth(*index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use from_pest::FromPest;
+    use pest::Parser;
+
+    use super::{EdgePath, NodePath};
+    use crate::{
+        graph::SyntaxHypergraph,
+        hypergraph::{
+            generic::Node,
+            mapping::edge_map,
+            traits::{Graph, Keyable},
+        },
+        language::spartan::{Expr, Rule, Spartan, SpartanParser},
+    };
+
+    fn all_nodes(
+        graph: &impl Graph<Ctx = SyntaxHypergraph<Spartan>>,
+    ) -> Vec<Node<SyntaxHypergraph<Spartan>>> {
+        let mut nodes = Vec::new();
+        for node in graph.nodes() {
+            if let Node::Thunk(thunk) = &node {
+                nodes.extend(all_nodes(thunk));
+            }
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    fn graph(source: &str) -> Result<SyntaxHypergraph<Spartan>> {
+        let mut pairs = SpartanParser::parse(Rule::program, source)?;
+        Ok(Expr::from_pest(&mut pairs)?.to_graph(false)?)
+    }
+
+    #[test]
+    fn paths_survive_recompilation() -> Result<()> {
+        let source =
+            "bind f = lambda(x . bind y = plus(x, z) in lambda(w . times(y, w))) in app(f, 1)";
+        let (old, new) = (graph(source)?, graph(source)?);
+
+        for (x, y) in all_nodes(&old).iter().zip(&all_nodes(&new)) {
+            let path = NodePath::of(&old, x).unwrap();
+            assert_eq!(path.resolve(&old).as_ref(), Some(x));
+            assert_eq!(path.resolve(&new).as_ref(), Some(y));
+        }
+
+        let old_edges: Vec<_> = edge_map(&old, ()).keys().cloned().collect();
+        let new_edges: Vec<_> = edge_map(&new, ()).keys().cloned().collect();
+        for (x, y) in old_edges.iter().zip(&new_edges) {
+            let path = EdgePath::of(&old, x).unwrap();
+            assert_eq!(path.resolve(&old).map(|edge| edge.key()).as_ref(), Some(x));
+            assert_eq!(path.resolve(&new).map(|edge| edge.key()).as_ref(), Some(y));
+        }
+        Ok(())
+    }
+}
@@ -93,7 +93,10 @@ where
     pub fn data_successors(&self) -> impl Iterator<Item = Self> + '_ {
         self.outputs()
             .flat_map(|edge| {
-                if edge.weight().get_type() != WireType::Data {
+                if matches!(
+                    edge.weight().get_type(),
+                    WireType::ControlFlow | WireType::SymName
+                ) {
                     vec![]
                 } else {
                     edge.targets()
@@ -0,0 +1,261 @@
+use std::collections::{HashMap, HashSet};
+
+use derivative::Derivative;
+
+use super::{
+    builder::{
+        fragment::Fragment, HypergraphBuildError, HypergraphBuilder, HypergraphError, InPort,
+        OutPort, ThunkBuilder,
+    },
+    traits::{Graph, NodeLike, WithWeight},
+    Edge, Hypergraph, Node, Thunk, Weight,
+};
+
+pub type Result<T, W> = core::result::Result<T, HypergraphError<W>>;
+
+/// Mappings of edges replaced inside a scope, together with what they replaced.
+type Scope<W> = Vec<(Edge<W>, Option<OutPort<W>>)>;
+
+/// A rewriter copies the nodes of a hypergraph into a new hypergraph,
+/// giving the caller the chance to skip or substitute parts of it along the way.
+///
+/// Edges of the original graph are mapped to out ports of the new graph.
+/// Inputs of copied nodes are linked lazily, so nodes can be copied in any order.
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""), Default(bound = ""))]
+pub struct Rewriter<W: Weight> {
+    /// Out ports in the new graph for edges of the original graph
+    out_ports: HashMap<Edge<W>, OutPort<W>>,
+    /// In ports in the new graph waiting to be linked to an edge of the original graph
+    pending: Vec<(InPort<W>, Edge<W>)>,
+    /// Thunks in the new graph for thunks of the original graph
+    thunks: HashMap<Thunk<W>, ThunkBuilder<W>>,
+    /// Nodes of the original graph which should not be copied
+    skipped: HashSet<Node<W>>,
+    /// Weights to use in the new graph instead of those of the original graph
+    weights: HashMap<Edge<W>, W::EdgeWeight>,
+    /// Edges of the original graph to be used in place of other edges of the original graph
+    aliases: HashMap<Edge<W>, Edge<W>>,
+    /// For each enclosing scope, the mappings of edges which were replaced inside it
+    scopes: Vec<Scope<W>>,
+}
+
+impl<W: Weight> Rewriter<W> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Do not copy the given nodes (or anything they contain).
+    pub fn skip(&mut self, nodes: impl IntoIterator<Item = Node<W>>) {
+        self.skipped.extend(nodes);
+    }
+
+    /// Give the copy of `edge` in the new graph a different weight.
+    pub fn set_weight(&mut self, edge: Edge<W>, weight: W::EdgeWeight) {
+        self.weights.insert(edge, weight);
+    }
+
+    /// The weight of the copy of `edge` in the new graph.
+    fn weight(&self, edge: &Edge<W>) -> W::EdgeWeight {
+        self.weights
+            .get(edge)
+            .cloned()
+            .unwrap_or_else(|| edge.weight())
+    }
+
+    /// The thunk in the new graph corresponding to `thunk`, if it has been copied.
+    #[must_use]
+    pub fn thunk(&self, thunk: &Thunk<W>) -> Option<&ThunkBuilder<W>> {
+        self.thunks.get(thunk)
+    }
+
+    /// The out port in the new graph corresponding to `edge`, if it has been created.
+    #[must_use]
+    pub fn out_port(&self, edge: &Edge<W>) -> Option<&OutPort<W>> {
+        self.out_ports.get(self.resolve(edge))
+    }
+
+    /// Use whatever `target` is mapped to for every use of `edge` in the new graph.
+    pub fn alias(&mut self, edge: Edge<W>, target: Edge<W>) {
+        self.aliases.insert(edge, target);
+    }
+
+    /// Follow aliases starting from `edge`.
+    fn resolve<'a>(&'a self, mut edge: &'a Edge<W>) -> &'a Edge<W> {
+        while let Some(target) = self.aliases.get(edge) {
+            edge = target;
+        }
+        edge
+    }
+
+    /// Use `out_port` for every (current or future) use of `edge` in the new graph.
+    pub fn set_out_port(&mut self, edge: Edge<W>, out_port: OutPort<W>) {
+        let old = self.out_ports.insert(edge.clone(), out_port);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push((edge, old));
+        }
+    }
+
+    /// Link `in_port` to whatever `edge` ends up being mapped to.
+    pub fn link(&mut self, in_port: InPort<W>, edge: Edge<W>) {
+        self.pending.push((in_port, edge));
+    }
+
+    /// Create a builder with the same inputs and outputs as `graph` and copy all its nodes into it.
+    pub fn copy_graph(&mut self, graph: &Hypergraph<W>) -> HypergraphBuilder<W> {
+        let mut builder = HypergraphBuilder::new(
+            graph
+                .graph_inputs()
+                .map(|edge| self.weight(&edge))
+                .collect(),
+            graph.number_of_graph_outputs(),
+        );
+
+        for (edge, out_port) in graph.graph_inputs().zip(builder.graph_inputs()) {
+            self.set_out_port(edge, out_port);
+        }
+        for (edge, in_port) in graph.graph_outputs().zip(builder.graph_outputs()) {
+            self.pending.push((in_port, edge));
+        }
+
+        self.copy_nodes(&mut builder, graph.nodes());
+        builder
+    }
+
+    /// Copy the `nodes` which have not been skipped (and their contents) into `fragment`.
+    pub fn copy_nodes<F>(&mut self, fragment: &mut F, nodes: impl Iterator<Item = Node<W>>)
+    where
+        F: Fragment<Weight = W>,
+    {
+        for node in nodes {
+            if !self.skipped.contains(&node) {
+                self.copy_node(fragment, &node);
+            }
+        }
+    }
+
+    /// Copy `node` into `fragment`, even if it has been skipped.
+    /// Skipped nodes contained in `node` are not copied.
+    pub fn copy_node<F>(&mut self, fragment: &mut F, node: &Node<W>)
+    where
+        F: Fragment<Weight = W>,
+    {
+        match node {
+            Node::Operation(op) => {
+                let builder = fragment.add_operation(
+                    op.number_of_inputs(),
+                    op.outputs().map(|edge| self.weight(&edge)),
+                    op.weight(),
+                );
+                for (edge, in_port) in op.inputs().zip(builder.inputs()) {
+                    self.pending.push((in_port, edge));
+                }
+                for (edge, out_port) in op.outputs().zip(builder.outputs()) {
+                    self.set_out_port(edge, out_port);
+                }
+            }
+            Node::Thunk(thunk) => {
+                // Free inputs and outputs are recomputed when the new graph is built.
+                let inputs: Vec<_> = thunk
+                    .inputs()
+                    .skip(thunk.number_of_free_graph_inputs())
+                    .collect();
+                let outputs: Vec<_> = thunk
+                    .outputs()
+                    .skip(thunk.number_of_free_graph_outputs())
+                    .collect();
+
+                let builder = fragment.add_thunk(
+                    inputs.len(),
+                    thunk.bound_graph_inputs().map(|edge| self.weight(&edge)),
+                    thunk.number_of_bound_graph_outputs(),
+                    outputs.iter().map(|edge| self.weight(edge)),
+                    thunk.weight(),
+                );
+
+                for (edge, in_port) in inputs.into_iter().zip(builder.inputs()) {
+                    self.pending.push((in_port, edge));
+                }
+                for (edge, out_port) in outputs.into_iter().zip(builder.outputs()) {
+                    self.set_out_port(edge, out_port);
+                }
+                for (edge, out_port) in thunk.bound_graph_inputs().zip(builder.bound_inputs()) {
+                    self.set_out_port(edge, out_port);
+                }
+                for (edge, in_port) in thunk.bound_graph_outputs().zip(builder.graph_outputs()) {
+                    self.pending.push((in_port, edge));
+                }
+
+                self.thunks.insert(thunk.clone(), builder.clone());
+                fragment.in_thunk(builder, |mut cursor| {
+                    self.copy_nodes(&mut cursor, thunk.nodes());
+                });
+            }
+        }
+    }
+
+    /// Run `f` in a scope where the edges in `substitution` are mapped to the given out ports.
+    ///
+    /// Every link requested inside the scope is resolved when it ends,
+    /// after which the mapping of edges from before the scope is restored.
+    /// This allows the same part of the original graph to be copied more than once.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a link requested inside the scope cannot be made.
+    pub fn scoped<F, R>(
+        &mut self,
+        fragment: &mut F,
+        substitution: impl IntoIterator<Item = (Edge<W>, OutPort<W>)>,
+        f: impl FnOnce(&mut Self, &mut F) -> R,
+    ) -> Result<R, W>
+    where
+        F: Fragment<Weight = W>,
+    {
+        let pending = std::mem::take(&mut self.pending);
+        self.scopes.push(Vec::new());
+        for (edge, out_port) in substitution {
+            self.set_out_port(edge, out_port);
+        }
+
+        let ret = f(self, fragment);
+        let linked = self.flush(fragment);
+
+        for (edge, old) in self.scopes.pop().unwrap().into_iter().rev() {
+            match old {
+                Some(out_port) => self.out_ports.insert(edge, out_port),
+                None => self.out_ports.remove(&edge),
+            };
+        }
+        self.pending = pending;
+
+        linked.map(|()| ret)
+    }
+
+    /// Resolve all links requested so far.
+    fn flush<F>(&mut self, fragment: &mut F) -> Result<(), W>
+    where
+        F: Fragment<Weight = W>,
+    {
+        for (in_port, edge) in std::mem::take(&mut self.pending) {
+            let out_port = self.out_port(&edge).cloned().ok_or_else(|| {
+                HypergraphError::BuildError(HypergraphBuildError::UninitializedInPort(
+                    in_port.clone(),
+                ))
+            })?;
+            fragment.link(out_port, in_port)?;
+        }
+        Ok(())
+    }
+
+    /// Resolve all remaining links and build the new graph.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a link cannot be made or the new graph is malformed.
+    pub fn finish(mut self, mut builder: HypergraphBuilder<W>) -> Result<Hypergraph<W>, W> {
+        self.flush(&mut builder)?;
+        builder.build()
+    }
+}
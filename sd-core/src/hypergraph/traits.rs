@@ -17,6 +17,8 @@ pub enum WireType {
     Data,
     ControlFlow,
     SymName,
+    /// Classical bits, such as the results of measuring a quantum circuit.
+    Classical,
 }
 
 pub trait WithType {
@@ -2,23 +2,49 @@
 
 use delegate::delegate;
 use derivative::Derivative;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     codeable::{Code, Codeable},
     common::Direction,
     hypergraph::{
         adapter::{collapse::CollapseGraph, cut::CutGraph, selectable::SelectableGraph},
+        diff::Change,
         generic::{Ctx, Edge, Key, Node, Thunk},
         mapping::{edge_map, thunk_map},
+        path::{EdgePath, NodePath},
         subgraph::Subgraph,
         traits::{Graph, Keyable},
+        Hypergraph, Weight,
     },
 };
 
+/// The state of an interactive graph, with nodes and edges identified by their paths
+/// so that it can be restored after the graph is recompiled.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct InteractiveState {
+    pub collapsed: Vec<NodePath>,
+    pub cut: Vec<EdgePath>,
+    pub selected: Vec<NodePath>,
+}
+
+/// Decides whether a thunk can be inlined.
+pub type Inlinable<T> = fn(&Thunk<T>) -> bool;
+
 /// An interactive graph is a graph with cut edges, collapsible thunks, and selectable nodes.
+/// It also knows which thunks can be inlined, remembers which thunk the user last asked to inline,
+/// how its nodes differ from another version of the graph, and which node was last clicked.
 #[derive(Derivative)]
 #[derivative(Clone(bound = ""), Debug(bound = ""))]
-pub struct InteractiveGraph<G: Graph>(pub CutGraph<CollapseGraph<SelectableGraph<G>>>);
+pub struct InteractiveGraph<G: Graph> {
+    pub graph: CutGraph<CollapseGraph<SelectableGraph<G>>>,
+    /// Whether a thunk can be inlined, if the language supports inlining.
+    inlinable: Option<Inlinable<G::Ctx>>,
+    inline_request: Option<Thunk<G::Ctx>>,
+    changes: IndexMap<Node<G::Ctx>, Change>,
+    inspected: Option<Node<G::Ctx>>,
+}
 
 impl<G: Graph> InteractiveGraph<G> {
     pub fn new(graph: G) -> Self {
@@ -30,39 +56,163 @@ impl<G: Graph> InteractiveGraph<G> {
         let cut_edges = edge_map(&graph, false);
         let graph = CutGraph::new(graph, cut_edges);
 
-        Self(graph)
+        Self {
+            graph,
+            inlinable: None,
+            inline_request: None,
+            changes: IndexMap::new(),
+            inspected: None,
+        }
+    }
+
+    /// The underlying graph, without any interactive state.
+    pub fn inner(&self) -> &G {
+        self.graph.inner().inner().inner()
+    }
+
+    /// Allow inlining the thunks for which `inlinable` holds.
+    pub fn set_inlinable(&mut self, inlinable: Inlinable<G::Ctx>) {
+        self.inlinable = Some(inlinable);
+    }
+
+    #[must_use]
+    pub fn can_inline(&self, thunk: &Thunk<G::Ctx>) -> bool {
+        self.inlinable.is_some_and(|inlinable| inlinable(thunk))
+    }
+
+    pub fn request_inline(&mut self, thunk: Thunk<G::Ctx>) {
+        self.inline_request = Some(thunk);
+    }
+
+    pub fn take_inline_request(&mut self) -> Option<Thunk<G::Ctx>> {
+        self.inline_request.take()
+    }
+
+    /// Mark nodes as changed with respect to another version of the graph.
+    pub fn set_changes(&mut self, changes: IndexMap<Node<G::Ctx>, Change>) {
+        self.changes = changes;
+    }
+
+    pub fn clear_changes(&mut self) {
+        self.changes.clear();
+    }
+
+    pub fn change_of(&self, node: &Node<G::Ctx>) -> Option<Change> {
+        self.changes.get(node).copied()
+    }
+
+    /// The node last clicked, whose details are shown in the inspector.
+    pub fn inspected(&self) -> Option<&Node<G::Ctx>> {
+        self.inspected.as_ref()
+    }
+
+    pub fn inspect(&mut self, node: Node<G::Ctx>) {
+        self.inspected = Some(node);
     }
 
     delegate! {
-        to self.0.inner().inner() {
+        to self.graph.inner().inner() {
             pub fn is_empty(&self) -> bool;
         }
 
-        to self.0.inner_mut().inner_mut() {
+        to self.graph.inner_mut().inner_mut() {
             pub fn clear_selection(&mut self);
             pub fn extend_selection(&mut self, direction: Option<(Direction, usize)>);
         }
     }
 
     delegate! {
-        to self.0.inner_mut() {
+        to self.graph.inner_mut() {
             #[call(set_all)]
             pub fn set_expanded_all(&mut self, value: bool);
         }
     }
 
     pub fn to_subgraph(&self) -> InteractiveSubgraph<G::Ctx> {
-        let subgraph = self.0.inner().inner().to_subgraph();
-        let expanded = self.0.inner().expanded().clone();
+        let subgraph = self.graph.inner().inner().to_subgraph();
+        let expanded = self.graph.inner().expanded().clone();
         InteractiveSubgraph(CollapseGraph::new(subgraph, expanded))
     }
 }
 
+impl<W: Weight> InteractiveGraph<Hypergraph<W>> {
+    /// Paths of the selected nodes.
+    pub fn selection_paths(&self) -> Vec<NodePath> {
+        let graph = self.inner();
+        self.graph
+            .inner()
+            .inner()
+            .selected_nodes()
+            .filter_map(|node| NodePath::of(graph, &node))
+            .collect()
+    }
+
+    /// Select exactly the nodes at the given paths.
+    pub fn select(&mut self, paths: &[NodePath]) {
+        let nodes: Vec<_> = paths
+            .iter()
+            .filter_map(|path| path.resolve(self.inner()))
+            .collect();
+        let selectable = self.graph.inner_mut().inner_mut();
+        selectable.clear_selection();
+        for node in nodes {
+            *selectable.selected_mut(&node) = true;
+        }
+    }
+
+    pub fn state(&self) -> InteractiveState {
+        let graph = self.inner();
+        InteractiveState {
+            collapsed: self
+                .graph
+                .inner()
+                .expanded()
+                .iter()
+                .filter(|(_, expanded)| !**expanded)
+                .filter_map(|(thunk, _)| NodePath::of(graph, &Node::Thunk(thunk.clone())))
+                .collect(),
+            cut: self
+                .graph
+                .cut_edges()
+                .iter()
+                .filter(|(_, cut)| **cut)
+                .filter_map(|(edge, _)| EdgePath::of(graph, edge))
+                .collect(),
+            selected: self.selection_paths(),
+        }
+    }
+
+    /// Restore a saved state. Paths which no longer exist in the graph are ignored.
+    pub fn restore(&mut self, state: &InteractiveState) {
+        let graph = self.inner().clone();
+
+        let mut expanded = self.graph.inner().expanded().clone();
+        expanded.values_mut().for_each(|x| *x = true);
+        for path in &state.collapsed {
+            if let Some(Node::Thunk(thunk)) = path.resolve(&graph) {
+                expanded[&thunk] = false;
+            }
+        }
+        self.graph.inner_mut().set_expanded(expanded);
+
+        let mut cut_edges = self.graph.cut_edges().clone();
+        cut_edges.values_mut().for_each(|x| *x = false);
+        for path in &state.cut {
+            if let Some(edge) = path.resolve(&graph) {
+                cut_edges[&edge] = true;
+            }
+        }
+        self.graph.set_cut_edges(cut_edges);
+
+        self.select(&state.selected);
+    }
+}
+
 impl<G: Graph> Graph for InteractiveGraph<G> {
     type Ctx = CutGraph<CollapseGraph<SelectableGraph<G>>>;
 
     delegate! {
-        to self.0 {
+        to self.graph {
             fn free_graph_inputs(&self) -> Box<dyn DoubleEndedIterator<Item = Edge<Self::Ctx>> + '_>;
             fn bound_graph_inputs(&self) -> Box<dyn DoubleEndedIterator<Item = Edge<Self::Ctx>> + '_>;
             fn free_graph_outputs(&self) -> Box<dyn DoubleEndedIterator<Item = Edge<Self::Ctx>> + '_>;
@@ -81,7 +231,7 @@ impl<G: Graph + Codeable> Codeable for InteractiveGraph<G> {
     type Code = Code<CutGraph<CollapseGraph<SelectableGraph<G>>>>;
 
     fn code(&self) -> Self::Code {
-        self.0.code()
+        self.graph.code()
     }
 }
 
@@ -89,7 +239,7 @@ impl<G: Graph> Keyable for InteractiveGraph<G> {
     type Key = Key<CutGraph<CollapseGraph<SelectableGraph<G>>>>;
 
     fn key(&self) -> Self::Key {
-        self.0.key()
+        self.graph.key()
     }
 }
 
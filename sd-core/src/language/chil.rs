@@ -1,6 +1,7 @@
 #![allow(clippy::clone_on_copy)]
 
 use std::{
+    collections::HashSet,
     fmt::{Display, Write},
     hash::{Hash, Hasher},
 };
@@ -16,6 +17,7 @@ use super::{span_into_str, Fresh, GetVar, OpInfo};
 use crate::{
     common::{Empty, Matchable},
     hypergraph::traits::{WireType, WithType},
+    refactor::Abstraction,
 };
 
 pub struct Chil;
@@ -29,6 +31,34 @@ impl super::Language for Chil {
     type Symbol = Empty;
 }
 
+impl Abstraction for Chil {
+    fn lambda() -> Option<Op> {
+        Some(Op("func".to_owned()))
+    }
+
+    fn app(arity: usize) -> Op {
+        Op(format!("apply/{arity}"))
+    }
+
+    fn fresh_def(used: &HashSet<Variable>) -> VariableDef {
+        let number = used
+            .iter()
+            .map(|var| var.addr.1 + 1)
+            .max()
+            .unwrap_or_default();
+        Variable {
+            name: None,
+            addr: Addr('%', number),
+        }
+        .into()
+    }
+
+    fn fresh_addr(used: &HashSet<Addr>) -> Addr {
+        let number = used.iter().map(|addr| addr.1 + 1).max().unwrap_or_default();
+        Addr('%', number)
+    }
+}
+
 pub type Expr = super::Expr<Chil>;
 pub type Bind = super::Bind<Chil>;
 pub type Value = super::Value<Chil>;
@@ -120,7 +150,14 @@ impl<'pest> FromPest<'pest> for Op {
     }
 }
 
-impl OpInfo<Chil> for Op {}
+impl OpInfo<Chil> for Op {
+    fn is_pure(&self) -> bool {
+        self.0 == "func"
+            || ["field/", "tuple/", "class/"]
+                .iter()
+                .any(|prefix| self.0.starts_with(prefix))
+    }
+}
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug, FromPest)]
 #[pest_ast(rule(Rule::variable))]
@@ -248,6 +285,12 @@ impl Matchable for VariableDef {
     }
 }
 
+impl From<Variable> for VariableDef {
+    fn from(var: Variable) -> Self {
+        Self { var, r#type: None }
+    }
+}
+
 impl GetVar<Variable> for VariableDef {
     fn var(&self) -> &Variable {
         &self.var
@@ -0,0 +1,411 @@
+//! Languages defined at runtime by a pest grammar, together with a mapping
+//! saying which rules of the grammar correspond to which parts of an [`Expr`].
+//!
+//! Rules which are not mentioned in the mapping are transparent:
+//! their children are treated as if they appeared in place of the rule.
+
+use std::fmt::Display;
+
+use pest::{
+    error::LineColLocation,
+    iterators::{Pair, Pairs},
+};
+use pest_vm::Vm;
+use pretty::RcDoc;
+use serde::Deserialize;
+use thiserror::Error;
+
+pub use super::spartan::Variable;
+use super::OpInfo;
+use crate::{
+    common::{Empty, Matchable, Unit},
+    prettyprinter::PrettyPrint,
+};
+
+pub struct Dynamic;
+
+impl super::Language for Dynamic {
+    type Op = Op;
+    type Var = Variable;
+    type Addr = Unit;
+    type VarDef = Variable;
+    type BlockAddr = Empty;
+    type Symbol = Empty;
+}
+
+pub type Expr = super::Expr<Dynamic>;
+pub type Bind = super::Bind<Dynamic>;
+pub type Value = super::Value<Dynamic>;
+pub type Thunk = super::Thunk<Dynamic>;
+
+/// An operation, named by the text it was parsed from.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Op {
+    pub name: String,
+    pub pure: bool,
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl Matchable for Op {
+    fn is_match(&self, query: &str) -> bool {
+        self.name == query
+    }
+}
+
+impl PrettyPrint for Op {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::text(&self.name)
+    }
+}
+
+impl OpInfo<Dynamic> for Op {
+    fn is_pure(&self) -> bool {
+        self.pure
+    }
+}
+
+/// Which rules of a grammar correspond to which parts of an expression, read from a TOML file.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Mapping {
+    /// The rule matching a whole program.
+    pub program: String,
+    /// A sequence of binds followed by the outputs.
+    pub expr: String,
+    /// Variables followed by the value bound to them.
+    pub bind: String,
+    pub variable: String,
+    /// The name of an operation, whose arguments are the values following it.
+    pub op: String,
+    /// Arguments followed by a body.
+    pub thunk: String,
+    /// A rule grouping the outputs of an expression, if there is one.
+    #[serde(default)]
+    pub output: Option<String>,
+    /// Operations without side effects, which can be merged or removed by optimisations.
+    #[serde(default)]
+    pub pure_ops: Vec<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum DynamicError {
+    #[error("Invalid grammar:\n{0}")]
+    Grammar(String),
+    #[error("Invalid mapping: {0}")]
+    Mapping(#[from] toml::de::Error),
+    #[error("Rule {0} of the mapping is not in the grammar")]
+    UnknownRule(String),
+    #[error("Parsing error:\n{message}")]
+    Parse {
+        message: String,
+        line_col: LineColLocation,
+    },
+    #[error("Expected {expected} but found rule {found}")]
+    Unexpected {
+        expected: &'static str,
+        found: String,
+    },
+}
+
+/// A language defined by a pest grammar, which is interpreted by the pest VM.
+pub struct DynamicLanguage {
+    vm: Vm,
+    mapping: Mapping,
+}
+
+impl DynamicLanguage {
+    /// Load a language from a pest grammar and a TOML mapping of its rules.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the grammar or mapping is invalid,
+    /// or the mapping mentions a rule which is not in the grammar.
+    pub fn new(grammar: &str, mapping: &str) -> Result<Self, DynamicError> {
+        let mapping: Mapping = toml::from_str(mapping)?;
+        let (_, rules) = pest_meta::parse_and_optimize(grammar).map_err(|errors| {
+            DynamicError::Grammar(
+                errors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        })?;
+
+        for rule in [
+            &mapping.program,
+            &mapping.expr,
+            &mapping.bind,
+            &mapping.variable,
+            &mapping.op,
+            &mapping.thunk,
+        ]
+        .into_iter()
+        .chain(&mapping.output)
+        {
+            if !rules.iter().any(|r| &r.name == rule) {
+                return Err(DynamicError::UnknownRule(rule.clone()));
+            }
+        }
+
+        Ok(Self {
+            vm: Vm::new(rules),
+            mapping,
+        })
+    }
+
+    /// Parse a program of this language.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the program does not match the grammar,
+    /// or its parse tree does not have the shape described by the mapping.
+    pub fn parse(&self, source: &str) -> Result<Expr, DynamicError> {
+        let pairs = self
+            .vm
+            .parse(&self.mapping.program, source)
+            .map_err(|err| DynamicError::Parse {
+                message: err.to_string(),
+                line_col: err.line_col.clone(),
+            })?;
+        let expr = self
+            .find_expr(pairs)
+            .ok_or_else(|| DynamicError::Unexpected {
+                expected: "an expression",
+                found: self.mapping.program.clone(),
+            })?;
+        self.expr(expr)
+    }
+
+    fn is(&self, pair: &Pair<'_, &str>, rule: &str) -> bool {
+        pair.as_rule() == rule
+    }
+
+    fn find_expr<'i>(&self, pairs: Pairs<'i, &'i str>) -> Option<Pair<'i, &'i str>> {
+        pairs.into_iter().find_map(|pair| {
+            if self.is(&pair, &self.mapping.expr) {
+                Some(pair)
+            } else {
+                self.find_expr(pair.into_inner())
+            }
+        })
+    }
+
+    fn expr(&self, pair: Pair<'_, &str>) -> Result<Expr, DynamicError> {
+        let mut expr = Expr::default();
+        for child in pair.into_inner() {
+            if self.is(&child, &self.mapping.bind) {
+                expr.binds.push(self.bind(child)?);
+            } else if self.mapping.output.as_deref() == Some(child.as_rule()) {
+                for value in child.into_inner() {
+                    expr.values.push(self.value(value)?);
+                }
+            } else {
+                expr.values.push(self.value(child)?);
+            }
+        }
+        Ok(expr)
+    }
+
+    fn bind(&self, pair: Pair<'_, &str>) -> Result<Bind, DynamicError> {
+        let rule = pair.as_rule().to_owned();
+        let mut children: Vec<_> = pair.into_inner().collect();
+        let value = children.pop().ok_or(DynamicError::Unexpected {
+            expected: "a bound value",
+            found: rule,
+        })?;
+        let mut defs = Vec::new();
+        for child in children {
+            self.variables(child, &mut defs);
+        }
+        Ok(Bind {
+            defs,
+            value: self.value(value)?,
+        })
+    }
+
+    fn thunk(&self, pair: Pair<'_, &str>) -> Result<Thunk, DynamicError> {
+        let rule = pair.as_rule().to_owned();
+        let mut children: Vec<_> = pair.into_inner().collect();
+        let body = children.pop().ok_or(DynamicError::Unexpected {
+            expected: "a thunk body",
+            found: rule,
+        })?;
+        let mut args = Vec::new();
+        for child in children {
+            self.variables(child, &mut args);
+        }
+        let body = if self.is(&body, &self.mapping.expr) {
+            self.expr(body)?
+        } else {
+            Expr {
+                binds: vec![],
+                values: vec![self.value(body)?],
+            }
+        };
+        Ok(Thunk {
+            addr: Unit,
+            args,
+            body,
+            blocks: vec![],
+        })
+    }
+
+    fn value(&self, pair: Pair<'_, &str>) -> Result<Value, DynamicError> {
+        if self.is(&pair, &self.mapping.variable) {
+            return Ok(Value::Variable(self.variable(&pair)));
+        }
+        if self.is(&pair, &self.mapping.thunk) {
+            return Ok(Value::Thunk(self.thunk(pair)?));
+        }
+        if self.is(&pair, &self.mapping.op) {
+            return Ok(Value::Op {
+                op: self.op(&pair),
+                args: vec![],
+            });
+        }
+
+        // A transparent rule, which is either an operation followed by its arguments or wraps a single value.
+        let rule = pair.as_rule().to_owned();
+        let mut children = pair.into_inner().peekable();
+        match children.peek() {
+            Some(first) if self.is(first, &self.mapping.op) => {
+                let op = self.op(first);
+                let args = children
+                    .skip(1)
+                    .map(|arg| self.value(arg))
+                    .collect::<Result<_, _>>()?;
+                Ok(Value::Op { op, args })
+            }
+            Some(_) => {
+                let child = children.next().unwrap();
+                if children.next().is_some() {
+                    return Err(DynamicError::Unexpected {
+                        expected: "a single value",
+                        found: rule,
+                    });
+                }
+                self.value(child)
+            }
+            None => Err(DynamicError::Unexpected {
+                expected: "a value",
+                found: rule,
+            }),
+        }
+    }
+
+    fn op(&self, pair: &Pair<'_, &str>) -> Op {
+        let name = pair.as_str().trim().to_owned();
+        Op {
+            pure: self.mapping.pure_ops.contains(&name),
+            name,
+        }
+    }
+
+    fn variable(&self, pair: &Pair<'_, &str>) -> Variable {
+        Variable(pair.as_str().trim().to_owned())
+    }
+
+    /// Collect the variables in `pair`, which may be grouped by transparent rules.
+    fn variables(&self, pair: Pair<'_, &str>, vars: &mut Vec<Variable>) {
+        if self.is(&pair, &self.mapping.variable) {
+            vars.push(self.variable(&pair));
+        } else {
+            for child in pair.into_inner() {
+                self.variables(child, vars);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bind, DynamicError, DynamicLanguage, Expr, Op, Thunk, Value, Variable};
+    use crate::{common::Unit, hypergraph::traits::Graph};
+
+    const GRAMMAR: &str = r#"
+        program = _{ SOI ~ expr ~ EOI }
+        keyword = { "let" | "in" | "fun" }
+        expr = { bind* ~ value }
+        bind = { "let" ~ variable ~ "=" ~ value ~ "in" }
+        thunk = { "fun" ~ variable* ~ "->" ~ expr }
+        value = { thunk | variable | op ~ ("(" ~ value ~ ("," ~ value)* ~ ")")? }
+        op = @{ ASCII_DIGIT+ | "add" | "call" }
+        variable = @{ !(keyword | op) ~ ASCII_ALPHA+ }
+        WHITESPACE = _{ " " | "\n" }
+    "#;
+
+    const MAPPING: &str = r#"
+        program = "program"
+        expr = "expr"
+        bind = "bind"
+        variable = "variable"
+        op = "op"
+        thunk = "thunk"
+        pure_ops = ["add"]
+    "#;
+
+    fn var(name: &str) -> Variable {
+        Variable(name.to_owned())
+    }
+
+    fn op(name: &str, pure: bool, args: Vec<Value>) -> Value {
+        Value::Op {
+            op: Op {
+                name: name.to_owned(),
+                pure,
+            },
+            args,
+        }
+    }
+
+    #[test]
+    fn parse_with_mapping() -> anyhow::Result<()> {
+        let language = DynamicLanguage::new(GRAMMAR, MAPPING)?;
+        let expr = language.parse("let f = fun x -> add(x, 1) in call(f, y)")?;
+
+        let thunk = Thunk {
+            addr: Unit,
+            args: vec![var("x")],
+            body: Expr {
+                binds: vec![],
+                values: vec![op(
+                    "add",
+                    true,
+                    vec![Value::Variable(var("x")), op("1", false, vec![])],
+                )],
+            },
+            blocks: vec![],
+        };
+        let expected = Expr {
+            binds: vec![Bind {
+                defs: vec![var("f")],
+                value: Value::Thunk(thunk),
+            }],
+            values: vec![op(
+                "call",
+                false,
+                vec![Value::Variable(var("f")), Value::Variable(var("y"))],
+            )],
+        };
+        assert_eq!(expr, expected);
+
+        let graph = expr.to_graph(false)?;
+        assert_eq!(graph.graph_inputs().count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_rule() {
+        let mapping = MAPPING.replace("\"thunk\"", "\"lambda\"");
+        assert!(matches!(
+            DynamicLanguage::new(GRAMMAR, &mapping),
+            Err(DynamicError::UnknownRule(rule)) if rule == "lambda"
+        ));
+    }
+}
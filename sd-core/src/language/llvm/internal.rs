@@ -0,0 +1,251 @@
+use from_pest::{ConversionError, FromPest, Void};
+use pest::iterators::Pairs;
+use pest_ast::FromPest;
+use pest_derive::Parser;
+
+use crate::language::span_into_str;
+
+#[derive(Parser)]
+#[grammar = "language/llvm.pest"]
+pub struct LlvmParser;
+
+macro_rules! passthrough {
+    (outer, $ast:ident, $rule:ident) => {
+        #[derive(Debug, FromPest)]
+        #[pest_ast(rule(Rule::$rule))]
+        pub struct $ast(#[pest_ast(outer(with(span_into_str), with(str::to_string)))] pub String);
+    };
+    (inner, $ast:ident, $rule:ident) => {
+        #[derive(Debug, FromPest)]
+        #[pest_ast(rule(Rule::$rule))]
+        pub struct $ast(#[pest_ast(inner(with(span_into_str), with(str::to_string)))] pub String);
+    };
+    // The text of a rule whose inner structure is not needed.
+    (text, $ast:ident, $rule:ident) => {
+        #[derive(Debug)]
+        pub struct $ast(pub String);
+
+        impl<'pest> FromPest<'pest> for $ast {
+            type Rule = Rule;
+            type FatalError = Void;
+
+            fn from_pest(
+                pest: &mut Pairs<'pest, Self::Rule>,
+            ) -> Result<Self, ConversionError<Self::FatalError>> {
+                let mut clone = pest.clone();
+                let pair = clone.next().ok_or(ConversionError::NoMatch)?;
+                if pair.as_rule() != Rule::$rule {
+                    return Err(ConversionError::NoMatch);
+                }
+                *pest = clone;
+                Ok(Self(pair.as_str().trim().to_owned()))
+            }
+        }
+    };
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::toplevelitem))]
+pub enum TopLevelItem {
+    Function(Function),
+    Declaration(Declaration),
+    Global(GlobalVar),
+    TypeDef(TypeDef),
+    Other(Other),
+}
+
+passthrough!(outer, Other, other);
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::function))]
+pub struct Function {
+    pub prefix: FnPrefix,
+    pub name: GlobalId,
+    pub params: Params,
+    pub attrs: FnAttrs,
+    pub entry_block: EntryBlock,
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::declaration))]
+pub struct Declaration {
+    pub prefix: FnPrefix,
+    pub name: GlobalId,
+    pub params: Params,
+    pub attrs: FnAttrs,
+}
+
+// Linkage, visibility, return type and other keywords before the name of a function.
+passthrough!(text, FnPrefix, fn_prefix);
+// Function attributes, sections, personalities and metadata after the parameters.
+passthrough!(text, FnAttrs, fn_attrs);
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::params))]
+pub struct Params(pub Vec<Param>);
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::param))]
+pub struct Param {
+    pub r#type: Type,
+    pub attrs: ParamAttrs,
+    pub id: Option<LocalId>,
+}
+
+passthrough!(text, ParamAttrs, param_attrs);
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::global_var))]
+pub struct GlobalVar {
+    pub name: GlobalId,
+    pub tokens: Vec<Token>,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::type_def))]
+pub struct TypeDef {
+    pub name: LocalId,
+}
+
+passthrough!(outer, GlobalId, global_id);
+passthrough!(outer, LocalId, local_id);
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::entry_block))]
+pub struct EntryBlock {
+    pub label: Option<BlockLabel>,
+    pub instructions: Vec<Instruction>,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::block))]
+pub struct Block {
+    pub label: BlockLabel,
+    pub instructions: Vec<Instruction>,
+}
+
+passthrough!(inner, BlockLabel, block_label);
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::instruction))]
+pub struct Instruction {
+    pub result: Option<LocalId>,
+    pub tail: Option<Tail>,
+    pub opcode: Opcode,
+    pub tokens: Vec<Token>,
+}
+
+passthrough!(outer, Tail, tail);
+passthrough!(outer, Opcode, opcode);
+
+/// Anything which can follow the opcode of an instruction.
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::token))]
+pub enum Token {
+    Label(Label),
+    Type(InstType),
+    Constant(Constant),
+    Local(LocalRef),
+    Global(GlobalRef),
+    Group(Group),
+    Word(Word),
+}
+
+passthrough!(inner, Label, label);
+passthrough!(text, InstType, inst_type);
+passthrough!(outer, Constant, constant);
+passthrough!(inner, LocalRef, local_ref);
+passthrough!(inner, GlobalRef, global_ref);
+passthrough!(outer, Word, word);
+passthrough!(text, Type, r#type);
+
+/// Tokens in brackets, such as the incoming values of a phi or the cases of a switch.
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::group))]
+pub struct Group(pub Vec<Token>);
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use from_pest::FromPest;
+    use pest::Parser;
+
+    use super::{LlvmParser, Rule, TopLevelItem};
+
+    pub(crate) const FACTORIAL: &str = r#"
+%struct.pair = type { i32, i32 }
+
+@.str = private unnamed_addr constant [4 x i8] c"%d\0A\00", align 1
+
+; Function Attrs: noinline nounwind
+define dso_local i32 @fact(i32 noundef %n) #0 {
+entry:
+  %cmp = icmp slt i32 %n, 2
+  br i1 %cmp, label %done, label %loop
+
+loop:                                             ; preds = %entry, %loop
+  %i = phi i32 [ %n, %entry ], [ %dec, %loop ]
+  %acc = phi i32 [ 1, %entry ], [ %mul, %loop ]
+  %mul = mul nsw i32 %acc, %i
+  %dec = sub nsw i32 %i, 1
+  %again = icmp sgt i32 %dec, 1
+  br i1 %again, label %loop, label %done
+
+done:                                             ; preds = %loop, %entry
+  %res = phi i32 [ 1, %entry ], [ %mul, %loop ]
+  ret i32 %res
+}
+
+define i32 @main() {
+  %p = alloca %struct.pair, align 4
+  %r = tail call i32 @fact(i32 5), !dbg !12
+  %c = call i32 (ptr, ...) @printf(ptr noundef @.str, i32 noundef %r)
+  switch i32 %r, label %other [
+    i32 0, label %other
+    i32 120, label %ok
+  ]
+
+ok:
+  ret i32 0
+
+other:
+  ret i32 1
+}
+
+declare i32 @printf(ptr noundef, ...) #1
+
+attributes #0 = { noinline nounwind "frame-pointer"="all" }
+!12 = !DILocation(line: 3, column: 7, scope: !4)
+"#;
+
+    pub(crate) fn parse_llvm(source: &str) -> Vec<TopLevelItem> {
+        let mut pairs = LlvmParser::parse(Rule::toplevel, source)
+            .unwrap_or_else(|err| panic!("could not parse program\n{err}"));
+        let items = Vec::<TopLevelItem>::from_pest(&mut pairs).unwrap();
+        assert_eq!(pairs.next().map(|pair| pair.as_rule()), Some(Rule::EOI));
+        items
+    }
+
+    #[test]
+    fn parse_llvm_instruction() -> Result<(), Box<dyn std::error::Error>> {
+        let instructions = [
+            "%sum = add nsw i32 %a, 1",
+            "%x = getelementptr inbounds [4 x i32], ptr %arr, i64 0, i64 %i",
+            "store volatile i32 %x, ptr @g, align 4",
+            "%r = call { i32, i1 } @llvm.sadd.with.overflow.i32(i32 %a, i32 %b)",
+            "br i1 %c, label %then, label %else",
+        ];
+        for instruction in instructions {
+            let mut parse_tree = LlvmParser::parse(Rule::instruction, instruction)?;
+            let syntax_tree = super::Instruction::from_pest(&mut parse_tree)?;
+            insta::assert_debug_snapshot!(syntax_tree);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_llvm_module() {
+        let items = parse_llvm(FACTORIAL);
+        insta::assert_debug_snapshot!(items);
+    }
+}
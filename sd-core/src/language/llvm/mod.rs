@@ -0,0 +1,532 @@
+use std::{collections::HashSet, fmt::Display};
+
+use itertools::Itertools;
+
+pub mod internal;
+
+use pretty::RcDoc;
+use serde::{Deserialize, Serialize};
+
+use self::internal::{Token, TopLevelItem, Word};
+use super::{Fresh, Language, OpInfo, CF};
+use crate::{
+    common::{Matchable, Unit},
+    hypergraph::traits::{WireType, WithType},
+    prettyprinter::PrettyPrint,
+};
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Serialize, Deserialize)]
+pub struct LlvmSettings {
+    pub sym_name_linking: bool,
+}
+
+pub struct Llvm;
+
+impl Language for Llvm {
+    type Op = Op;
+    type Var = Var;
+    type Addr = Unit;
+    type VarDef = Var;
+    type BlockAddr = BlockAddr;
+    type Symbol = Symbol;
+}
+
+pub type Expr = super::Expr<Llvm>;
+pub type Bind = super::Bind<Llvm>;
+pub type Value = super::Value<Llvm>;
+pub type Thunk = super::Thunk<Llvm>;
+pub type Block = super::Block<Llvm>;
+
+/// Instructions which have no side effects.
+const PURE_OPS: &[&str] = &[
+    "fneg",
+    "add",
+    "fadd",
+    "sub",
+    "fsub",
+    "mul",
+    "fmul",
+    "udiv",
+    "sdiv",
+    "fdiv",
+    "urem",
+    "srem",
+    "frem",
+    "shl",
+    "lshr",
+    "ashr",
+    "and",
+    "or",
+    "xor",
+    "extractelement",
+    "insertelement",
+    "shufflevector",
+    "extractvalue",
+    "insertvalue",
+    "getelementptr",
+    "trunc",
+    "zext",
+    "sext",
+    "fptrunc",
+    "fpext",
+    "fptoui",
+    "fptosi",
+    "uitofp",
+    "sitofp",
+    "ptrtoint",
+    "inttoptr",
+    "bitcast",
+    "addrspacecast",
+    "select",
+    "freeze",
+];
+
+/// Instructions whose callee is given by a symbol.
+const CALL_OPS: &[&str] = &["call", "invoke", "callbr"];
+
+/// Instructions which leave the function, including `unreachable`, which ends a block without branching.
+const RETURN_OPS: &[&str] = &["ret", "resume", "unreachable"];
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct Op {
+    pub name: String,
+    pub successors: Vec<BlockAddr>,
+    /// The blocks which the arguments of a phi come from, in the same order.
+    pub incoming: Vec<BlockAddr>,
+    pub attributes: String,
+    pub sym_name: Option<String>,
+    pub symbols: Vec<String>,
+    pub pure: bool,
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl Matchable for Op {
+    fn is_match(&self, query: &str) -> bool {
+        self.name == query
+    }
+}
+
+impl OpInfo<Llvm> for Op {
+    fn get_cf(&self) -> Option<CF<Llvm>> {
+        if !self.successors.is_empty() {
+            Some(CF::Brs(self.successors.clone()))
+        } else if RETURN_OPS.contains(&self.name.as_str()) {
+            Some(CF::Return)
+        } else {
+            None
+        }
+    }
+
+    fn symbols_used(&self) -> impl Iterator<Item = Symbol> {
+        self.symbols.iter().map(|x| Symbol(x.clone()))
+    }
+
+    fn sym_name(&self) -> Option<Symbol> {
+        self.sym_name.as_ref().map(|x| Symbol(x.clone()))
+    }
+
+    fn is_pure(&self) -> bool {
+        self.pure
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub enum Var {
+    Var { id: String },
+    Symbol(Symbol),
+}
+
+impl WithType for Var {
+    fn get_type(&self) -> WireType {
+        match self {
+            Var::Symbol(_) => WireType::SymName,
+            Var::Var { .. } => WireType::Data,
+        }
+    }
+}
+
+impl From<Symbol> for Var {
+    fn from(value: Symbol) -> Self {
+        Var::Symbol(value)
+    }
+}
+
+impl Display for Var {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Var::Var { id } => f.write_str(id),
+            Var::Symbol(s) => s.fmt(f),
+        }
+    }
+}
+
+impl Matchable for Var {
+    fn is_match(&self, query: &str) -> bool {
+        match self {
+            Var::Var { id } => id == query || id.strip_prefix('%') == Some(query),
+            Var::Symbol(s) => s.is_match(query),
+        }
+    }
+}
+
+impl Fresh for Var {
+    fn fresh(number: usize) -> Self {
+        Var::Var {
+            id: format!("%?{number}"),
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct BlockAddr(pub String);
+
+impl Display for BlockAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Matchable for BlockAddr {
+    fn is_match(&self, query: &str) -> bool {
+        self.0 == query || self.0.strip_prefix('%') == Some(query)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct Symbol(pub String);
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Matchable for Symbol {
+    fn is_match(&self, query: &str) -> bool {
+        self.0 == query || Some(self.0.as_str()) == query.strip_prefix('@')
+    }
+}
+
+impl PrettyPrint for Symbol {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::text("@").append(&self.0)
+    }
+}
+
+// Conversion from internal AST.
+
+/// The operands of an instruction, together with everything else following its opcode.
+#[derive(Default)]
+struct Operands {
+    args: Vec<Value>,
+    attributes: Vec<String>,
+    successors: Vec<BlockAddr>,
+    incoming: Vec<BlockAddr>,
+    symbols: Vec<String>,
+}
+
+/// Converts a module, telling named types apart from values by the types it defines.
+struct Converter {
+    types: HashSet<String>,
+}
+
+fn symbol(global: &str) -> String {
+    global.trim_start_matches('@').to_owned()
+}
+
+impl Converter {
+    fn item(&self, item: TopLevelItem) -> Option<Bind> {
+        let value = match item {
+            TopLevelItem::Function(function) => Value::Op {
+                op: Op {
+                    name: "define".to_owned(),
+                    successors: vec![],
+                    incoming: vec![],
+                    attributes: [function.prefix.0, function.attrs.0]
+                        .iter()
+                        .filter(|s| !s.is_empty())
+                        .join(" "),
+                    sym_name: Some(symbol(&function.name.0)),
+                    symbols: vec![],
+                    pure: false,
+                },
+                args: vec![Value::Thunk(Thunk {
+                    addr: Unit,
+                    args: function
+                        .params
+                        .0
+                        .into_iter()
+                        .filter_map(|param| param.id)
+                        .map(|id| Var::Var { id: id.0 })
+                        .collect(),
+                    body: self.instructions(function.entry_block.instructions),
+                    blocks: function
+                        .blocks
+                        .into_iter()
+                        .map(|block| Block {
+                            addr: BlockAddr(format!("%{}", block.label.0)),
+                            args: vec![],
+                            expr: self.instructions(block.instructions),
+                        })
+                        .collect(),
+                })],
+            },
+            TopLevelItem::Declaration(declaration) => Value::Op {
+                op: Op {
+                    name: "declare".to_owned(),
+                    successors: vec![],
+                    incoming: vec![],
+                    attributes: declaration.prefix.0,
+                    sym_name: Some(symbol(&declaration.name.0)),
+                    symbols: vec![],
+                    pure: false,
+                },
+                args: vec![],
+            },
+            TopLevelItem::Global(global) => {
+                let name = global
+                    .tokens
+                    .iter()
+                    .find_map(|token| match token {
+                        Token::Word(word)
+                            if ["global", "constant", "alias", "ifunc"]
+                                .contains(&word.0.as_str()) =>
+                        {
+                            Some(word.0.clone())
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| "global".to_owned());
+                let mut operands = Operands::default();
+                self.operands(global.tokens, &mut operands);
+                operands.attributes.retain(|attribute| attribute != &name);
+                Value::Op {
+                    op: Op {
+                        name,
+                        successors: vec![],
+                        incoming: vec![],
+                        attributes: operands.attributes.join(" "),
+                        sym_name: Some(symbol(&global.name.0)),
+                        symbols: operands.symbols,
+                        pure: false,
+                    },
+                    args: operands.args,
+                }
+            }
+            TopLevelItem::TypeDef(_) | TopLevelItem::Other(_) => return None,
+        };
+        Some(Bind {
+            defs: vec![],
+            value,
+        })
+    }
+
+    fn instructions(&self, instructions: Vec<internal::Instruction>) -> Expr {
+        Expr {
+            binds: instructions
+                .into_iter()
+                .map(|instruction| self.instruction(instruction))
+                .collect(),
+            values: vec![],
+        }
+    }
+
+    fn instruction(&self, instruction: internal::Instruction) -> Bind {
+        let name = instruction.opcode.0.split_whitespace().join(" ");
+        let mut operands = Operands::default();
+        operands
+            .attributes
+            .extend(instruction.tail.map(|tail| tail.0));
+        let mut tokens = instruction.tokens;
+
+        if CALL_OPS.contains(&name.as_str()) {
+            // The callee of a direct call is the global immediately followed by the arguments.
+            if let Some(index) = tokens
+                .iter()
+                .tuple_windows()
+                .position(|pair| matches!(pair, (Token::Global(_), Token::Group(_))))
+            {
+                if let Token::Global(callee) = &tokens[index] {
+                    operands.symbols.push(symbol(&callee.0));
+                    tokens[index] = Token::Word(Word(callee.0.clone()));
+                }
+            }
+        }
+
+        if name == "phi" {
+            // Each incoming value is paired with the block it comes from.
+            for token in tokens {
+                match token {
+                    Token::Group(mut group) => {
+                        if let Some(Token::Local(block)) = group.0.pop() {
+                            operands.incoming.push(BlockAddr(block.0));
+                        }
+                        self.operands(group.0, &mut operands);
+                    }
+                    token => self.operands(vec![token], &mut operands),
+                }
+            }
+        } else {
+            self.operands(tokens, &mut operands);
+        }
+
+        Bind {
+            defs: instruction
+                .result
+                .map(|id| Var::Var { id: id.0 })
+                .into_iter()
+                .collect(),
+            value: Value::Op {
+                op: Op {
+                    pure: PURE_OPS.contains(&name.as_str())
+                        || name.starts_with("icmp ")
+                        || name.starts_with("fcmp "),
+                    name,
+                    successors: operands.successors,
+                    incoming: operands.incoming,
+                    attributes: operands.attributes.join(" "),
+                    sym_name: None,
+                    symbols: operands.symbols,
+                },
+                args: operands.args,
+            },
+        }
+    }
+
+    fn operands(&self, tokens: Vec<Token>, operands: &mut Operands) {
+        for token in tokens {
+            match token {
+                Token::Label(label) => operands.successors.push(BlockAddr(label.0)),
+                Token::Type(ty) => operands.attributes.push(ty.0),
+                Token::Word(word) => operands.attributes.push(word.0),
+                Token::Constant(constant) => operands.args.push(Value::Op {
+                    op: Op {
+                        name: constant.0,
+                        successors: vec![],
+                        incoming: vec![],
+                        attributes: String::new(),
+                        sym_name: None,
+                        symbols: vec![],
+                        pure: true,
+                    },
+                    args: vec![],
+                }),
+                Token::Local(local) => {
+                    if self.types.contains(&local.0) {
+                        operands.attributes.push(local.0);
+                    } else {
+                        operands
+                            .args
+                            .push(Value::Variable(Var::Var { id: local.0 }));
+                    }
+                }
+                // The address of a global, which is linked to its definition by symbol.
+                Token::Global(global) => operands.args.push(Value::Op {
+                    op: Op {
+                        symbols: vec![symbol(&global.0)],
+                        name: global.0,
+                        successors: vec![],
+                        incoming: vec![],
+                        attributes: String::new(),
+                        sym_name: None,
+                        pure: true,
+                    },
+                    args: vec![],
+                }),
+                Token::Group(group) => self.operands(group.0, operands),
+            }
+        }
+    }
+}
+
+impl From<Vec<TopLevelItem>> for Expr {
+    fn from(items: Vec<TopLevelItem>) -> Self {
+        let converter = Converter {
+            types: items
+                .iter()
+                .filter_map(|item| match item {
+                    TopLevelItem::TypeDef(def) => Some(def.name.0.clone()),
+                    _ => None,
+                })
+                .collect(),
+        };
+        Expr {
+            binds: items
+                .into_iter()
+                .filter_map(|item| converter.item(item))
+                .collect(),
+            values: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{internal::tests::parse_llvm, BlockAddr, Expr, Op, Value};
+    use crate::{
+        language::{tests::ExprTest, OpInfo, CF},
+        prettyprinter::PrettyPrint,
+    };
+
+    #[test]
+    fn convert_llvm() -> anyhow::Result<()> {
+        let expr = Expr::from(parse_llvm(super::internal::tests::FACTORIAL));
+        insta::assert_debug_snapshot!(expr);
+        expr.graph_test("factorial", "ll", false)?;
+        expr.graph_test("factorial", "ll", true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn terminators_and_phis() -> anyhow::Result<()> {
+        let expr = Expr::from(parse_llvm(
+            r"
+define i32 @f(i1 %c) {
+entry:
+  br i1 %c, label %ok, label %bad
+
+ok:
+  %x = phi i32 [ 1, %entry ]
+  ret i32 %x
+
+bad:
+  call void @abort()
+  unreachable
+}
+",
+        ));
+        let Value::Op { args, .. } = &expr.binds[0].value else {
+            unreachable!()
+        };
+        let Value::Thunk(thunk) = &args[0] else {
+            unreachable!()
+        };
+        let op = |expr: &Expr, index: usize| -> Op {
+            match &expr.binds[index].value {
+                Value::Op { op, .. } => op.clone(),
+                Value::Variable(_) | Value::Thunk(_) => unreachable!(),
+            }
+        };
+
+        let phi = op(&thunk.blocks[0].expr, 0);
+        assert_eq!(phi.incoming, vec![BlockAddr("%entry".to_owned())]);
+        assert_eq!(phi.to_pretty(), "phi i32 [%entry]");
+        assert!(phi.get_cf().is_none());
+
+        let unreachable = op(&thunk.blocks[1].expr, 1);
+        assert_eq!(unreachable.name, "unreachable");
+        assert!(matches!(unreachable.get_cf(), Some(CF::Return)));
+
+        expr.to_graph(false)?;
+        Ok(())
+    }
+}
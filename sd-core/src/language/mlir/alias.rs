@@ -0,0 +1,230 @@
+//! Attribute and type aliases, see <https://mlir.llvm.org/docs/LangRef/#attribute-value-aliases>
+//! and <https://mlir.llvm.org/docs/LangRef/#type-aliases>.
+
+use std::collections::HashMap;
+
+use super::{
+    attribute::{AttributeValue, Elements},
+    for_each_op,
+    location::Location,
+    Expr,
+};
+
+/// How deeply aliases defined in terms of other aliases are expanded, which stops cyclic definitions.
+const MAX_DEPTH: usize = 16;
+
+/// The aliases defined at the top level of an MLIR file, such as `#map = ...` and `!type = ...`.
+#[derive(Clone, Default, Debug)]
+pub struct Aliases {
+    attributes: HashMap<String, AttributeValue>,
+    types: HashMap<String, String>,
+}
+
+impl Aliases {
+    pub fn define_attribute(&mut self, name: String, value: AttributeValue) {
+        self.attributes.insert(name, value);
+    }
+
+    pub fn define_type(&mut self, name: String, r#type: String) {
+        self.types.insert(name, r#type);
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.types.is_empty()
+    }
+
+    /// The value of an attribute alias, with any aliases it refers to expanded.
+    #[must_use]
+    pub fn attribute(&self, name: &str) -> Option<AttributeValue> {
+        self.attribute_at(name, 0)
+    }
+
+    /// A type alias, with any aliases it refers to expanded.
+    #[must_use]
+    pub fn r#type(&self, name: &str) -> Option<String> {
+        self.type_at(name, 0)
+    }
+
+    fn attribute_at(&self, name: &str, depth: usize) -> Option<AttributeValue> {
+        let mut value = self.attributes.get(name)?.clone();
+        if depth < MAX_DEPTH {
+            self.expand_value(&mut value, depth + 1);
+        }
+        Some(value)
+    }
+
+    fn type_at(&self, name: &str, depth: usize) -> Option<String> {
+        let mut r#type = self.types.get(name)?.clone();
+        if depth < MAX_DEPTH {
+            self.expand_text(&mut r#type, depth + 1);
+        }
+        Some(r#type)
+    }
+
+    /// Expand the attribute and type aliases in the attributes of every operation of `expr`.
+    pub fn expand(&self, expr: &mut Expr) {
+        for_each_op(expr, &mut |op| {
+            for attribute in op.properties.iter_mut().chain(&mut op.attributes) {
+                if let Some(value) = &mut attribute.value {
+                    self.expand_value(value, 0);
+                }
+            }
+        });
+    }
+
+    /// Expand the location aliases of every operation of `expr`, such as `loc(#loc3)`.
+    pub fn expand_locations(&self, expr: &mut Expr) {
+        for_each_op(expr, &mut |op| {
+            if let Some(location) = &mut op.location {
+                self.expand_location(location, 0);
+            }
+        });
+    }
+
+    fn expand_value(&self, value: &mut AttributeValue, depth: usize) {
+        match value {
+            AttributeValue::Alias(name) => {
+                if let Some(expanded) = self.attribute_at(name, depth) {
+                    *value = expanded;
+                }
+            }
+            AttributeValue::Integer {
+                r#type: Some(text), ..
+            }
+            | AttributeValue::Float {
+                r#type: Some(text), ..
+            }
+            | AttributeValue::String {
+                r#type: Some(text), ..
+            }
+            | AttributeValue::DenseArray { r#type: text, .. }
+            | AttributeValue::Type(text)
+            | AttributeValue::Dialect {
+                body: Some(text), ..
+            }
+            | AttributeValue::Other(text) => self.expand_text(text, depth),
+            AttributeValue::Array(values) => {
+                for value in values {
+                    self.expand_value(value, depth);
+                }
+            }
+            AttributeValue::Dictionary(attributes) => {
+                for value in attributes.iter_mut().filter_map(|x| x.value.as_mut()) {
+                    self.expand_value(value, depth);
+                }
+            }
+            AttributeValue::DenseElements { elements, r#type } => {
+                if let Elements::Attribute(value) = elements {
+                    self.expand_value(value, depth);
+                }
+                self.expand_text(r#type, depth);
+            }
+            AttributeValue::Location(location) => self.expand_location(location, depth),
+            _ => (),
+        }
+    }
+
+    fn expand_location(&self, location: &mut Location, depth: usize) {
+        match location {
+            Location::Alias(name) => {
+                if let Some(AttributeValue::Location(expanded)) = self.attribute_at(name, depth) {
+                    *location = expanded;
+                }
+            }
+            Location::Name {
+                child: Some(child), ..
+            } => self.expand_location(child, depth),
+            Location::CallSite { callee, caller } => {
+                self.expand_location(callee, depth);
+                self.expand_location(caller, depth);
+            }
+            Location::Fused { locations, .. } => {
+                for location in locations {
+                    self.expand_location(location, depth);
+                }
+            }
+            Location::Opaque(location) => self.expand_location(location, depth),
+            _ => (),
+        }
+    }
+
+    /// Expand the aliases written in text which has not been parsed any further,
+    /// such as types and the bodies of dialect attributes.
+    fn expand_text(&self, text: &mut String, depth: usize) {
+        if self.is_empty() || !text.contains(['#', '!']) {
+            return;
+        }
+        let mut expanded = String::with_capacity(text.len());
+        let mut rest = text.as_str();
+        while let Some(start) = rest.find(['#', '!']) {
+            expanded.push_str(&rest[..start]);
+            let sigil = &rest[start..=start];
+            let name = &rest[start + 1..];
+            let end = name
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '.')))
+                .unwrap_or(name.len());
+            let (name, after) = name.split_at(end);
+            let replacement = match sigil {
+                "#" => self.attribute_at(name, depth).map(|x| x.to_string()),
+                _ => self.type_at(name, depth),
+            };
+            match replacement {
+                Some(replacement) => expanded.push_str(&replacement),
+                None => {
+                    expanded.push_str(sigil);
+                    expanded.push_str(name);
+                }
+            }
+            rest = after;
+        }
+        expanded.push_str(rest);
+        *text = expanded;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use from_pest::FromPest;
+    use pest::Parser;
+
+    use crate::{
+        language::mlir::{
+            internal::{MlirParser, Rule, TopLevelItem},
+            Expr, Module, Op, Value,
+        },
+        prettyprinter::PrettyPrint,
+    };
+
+    fn first_op(expr: &Expr) -> &Op {
+        match &expr.binds[0].value {
+            Value::Op { op, .. } => op,
+            Value::Variable(_) | Value::Thunk(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn aliases() {
+        let source = r#"
+#tbaa_root = #llvm.tbaa_root<id = "root">
+#tbaa_tag = #llvm.tbaa_tag<base_type = #tbaa_root, offset = 0>
+!pair = !llvm.struct<(i32, i32)>
+!pairs = tensor<2x!pair>
+%0 = "test.op"() {tag = [#tbaa_tag], ty = !pairs, unknown = #other} : () -> i32 loc(#loc1)
+#loc1 = loc("file.cpp":12:3)
+"#;
+        let mut pairs = MlirParser::parse(Rule::toplevel, source).unwrap();
+        let module = Module::from(Vec::<TopLevelItem>::from_pest(&mut pairs).unwrap());
+
+        let op = first_op(&module.expr);
+        assert_eq!(
+            op.to_pretty(),
+            r#"test.op {tag = [#tbaa_tag], ty = !pairs, unknown = #other} loc("file.cpp":12:3)"#
+        );
+        let expanded = module.expanded();
+        assert_eq!(
+            first_op(&expanded).to_pretty(),
+            r#"test.op {tag = [#llvm.tbaa_tag<base_type = #llvm.tbaa_root<id = "root">, offset = 0>], ty = tensor<2x!llvm.struct<(i32, i32)>>, unknown = #other} loc("file.cpp":12:3)"#
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Typed values of MLIR attributes, see <https://mlir.llvm.org/docs/LangRef/#attributes>.
+
+use std::fmt::Display;
+
+use itertools::Itertools;
+#[cfg(test)]
+use serde::Serialize;
+
+use super::location::Location;
+use crate::common::Matchable;
+
+/// A named attribute of an operation, such as `value = 42 : i64`.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct Attribute {
+    /// The name, which is quoted if it is not a bare identifier.
+    pub name: String,
+    /// The value, which is absent for unit attributes written as just their name.
+    pub value: Option<AttributeValue>,
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub enum AttributeValue {
+    Unit,
+    Bool(bool),
+    /// An integer, with its type if given (e.g. `42 : i64`).
+    Integer {
+        value: i128,
+        r#type: Option<String>,
+    },
+    /// A float, kept as written so that it prints back exactly.
+    Float {
+        literal: String,
+        r#type: Option<String>,
+    },
+    /// A string, without its quotes but with any escape sequences as written.
+    String {
+        value: String,
+        r#type: Option<String>,
+    },
+    Array(Vec<AttributeValue>),
+    Dictionary(Vec<Attribute>),
+    /// A reference to a symbol, nested in the symbol tables of the ones before it
+    /// (e.g. `@module::@function`), without the `@`s.
+    SymbolRef(Vec<String>),
+    /// A dense array of integers or floats, such as `array<i32: 1, 0, 0>`.
+    DenseArray {
+        r#type: String,
+        elements: Vec<String>,
+    },
+    /// The elements of a tensor or vector, such as `dense<[1, 2]> : tensor<2xi32>`.
+    DenseElements {
+        elements: Elements,
+        r#type: String,
+    },
+    Type(String),
+    /// An attribute of a dialect, such as `#llvm.linkage<internal>`,
+    /// whose body is kept as written including its angle brackets.
+    Dialect {
+        dialect: String,
+        name: Option<String>,
+        body: Option<String>,
+    },
+    /// A reference to an attribute alias, such as `#map`.
+    Alias(String),
+    /// A location, such as `loc("file.cpp":12:3)`.
+    Location(Location),
+    /// Any other builtin attribute (e.g. a strided layout), as written.
+    Other(String),
+}
+
+/// The elements of a dense elements attribute.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub enum Elements {
+    /// An integer, float or boolean literal, as written.
+    Literal(String),
+    List(Vec<Elements>),
+    /// Elements given by another attribute, such as a string.
+    Attribute(Box<AttributeValue>),
+}
+
+impl Attribute {
+    /// The name of the symbol defined by this attribute, if it is a `sym_name`.
+    #[must_use]
+    pub fn sym_name(&self) -> Option<&str> {
+        match &self.value {
+            Some(AttributeValue::String { value, .. }) if self.name == "sym_name" => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The symbol referred to by the value of this attribute, if any.
+    #[must_use]
+    pub fn symbol(&self) -> Option<String> {
+        self.value.as_ref().and_then(AttributeValue::symbol)
+    }
+}
+
+impl AttributeValue {
+    /// The symbol referred to by this value, if it is a symbol reference,
+    /// with nested references separated by `::`.
+    #[must_use]
+    pub fn symbol(&self) -> Option<String> {
+        match self {
+            AttributeValue::SymbolRef(names) => {
+                Some(names.iter().map(|name| name.trim_matches('"')).join("::"))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Write the type of a value after it, if it has one.
+fn with_type(
+    f: &mut std::fmt::Formatter<'_>,
+    value: impl Display,
+    r#type: Option<&String>,
+) -> std::fmt::Result {
+    match r#type {
+        Some(r#type) => write!(f, "{value} : {type}"),
+        None => write!(f, "{value}"),
+    }
+}
+
+impl Display for Attribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{} = {value}", self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+impl Display for AttributeValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttributeValue::Unit => f.write_str("unit"),
+            AttributeValue::Bool(value) => write!(f, "{value}"),
+            AttributeValue::Integer { value, r#type } => with_type(f, value, r#type.as_ref()),
+            AttributeValue::Float { literal, r#type } => with_type(f, literal, r#type.as_ref()),
+            AttributeValue::String { value, r#type } => {
+                with_type(f, format_args!("\"{value}\""), r#type.as_ref())
+            }
+            AttributeValue::Array(values) => write!(f, "[{}]", values.iter().join(", ")),
+            AttributeValue::Dictionary(attributes) => {
+                write!(f, "{{{}}}", attributes.iter().join(", "))
+            }
+            AttributeValue::SymbolRef(names) => {
+                write!(
+                    f,
+                    "{}",
+                    names.iter().map(|name| format!("@{name}")).join("::")
+                )
+            }
+            AttributeValue::DenseArray { r#type, elements } if elements.is_empty() => {
+                write!(f, "array<{type}>")
+            }
+            AttributeValue::DenseArray { r#type, elements } => {
+                write!(f, "array<{type}: {}>", elements.iter().join(", "))
+            }
+            AttributeValue::DenseElements { elements, r#type } => {
+                write!(f, "dense<{elements}> : {type}")
+            }
+            AttributeValue::Type(r#type) => f.write_str(r#type),
+            AttributeValue::Dialect {
+                dialect,
+                name,
+                body,
+            } => {
+                write!(f, "#{dialect}")?;
+                if let Some(name) = name {
+                    write!(f, ".{name}")?;
+                }
+                if let Some(body) = body {
+                    f.write_str(body)?;
+                }
+                Ok(())
+            }
+            AttributeValue::Alias(alias) => write!(f, "#{alias}"),
+            AttributeValue::Location(location) => write!(f, "loc({location})"),
+            AttributeValue::Other(value) => f.write_str(value),
+        }
+    }
+}
+
+impl Display for Elements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Elements::Literal(literal) => f.write_str(literal),
+            Elements::List(elements) => write!(f, "[{}]", elements.iter().join(", ")),
+            Elements::Attribute(value) => value.fmt(f),
+        }
+    }
+}
+
+impl Matchable for Attribute {
+    /// Matches queries of the form `name=value`, where the value is matched as by [`AttributeValue`].
+    fn is_match(&self, query: &str) -> bool {
+        query.split_once('=').is_some_and(|(name, value)| {
+            self.name.trim_matches('"') == name.trim()
+                && self
+                    .value
+                    .as_ref()
+                    .is_some_and(|x| x.is_match(value.trim()))
+        })
+    }
+}
+
+impl Matchable for AttributeValue {
+    /// Matches the value as written, or numbers, strings and symbols regardless of their types and quotes.
+    fn is_match(&self, query: &str) -> bool {
+        self.to_string() == query
+            || match self {
+                AttributeValue::Integer { value, .. } => query.parse() == Ok(*value),
+                AttributeValue::Float { literal, .. } => literal
+                    .parse::<f64>()
+                    .is_ok_and(|value| query.parse() == Ok(value)),
+                AttributeValue::String { value, .. } => value == query,
+                AttributeValue::SymbolRef(_) => self
+                    .symbol()
+                    .is_some_and(|symbol| symbol == query.trim_start_matches('@')),
+                _ => false,
+            }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use from_pest::FromPest;
+    use pest::Parser;
+
+    use super::AttributeValue;
+    use crate::{
+        common::Matchable,
+        language::mlir::{
+            internal::{MlirParser, Operation, Rule},
+            Op, Value,
+        },
+    };
+
+    fn parse_op(op: &str) -> Op {
+        let mut pairs = MlirParser::parse(Rule::operation, op).unwrap();
+        match Operation::from_pest(&mut pairs).unwrap().into() {
+            Value::Op { op, .. } => op,
+            Value::Variable(_) | Value::Thunk(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn print_attributes() {
+        let attributes = [
+            "value = 42 : i64",
+            "flag = true",
+            "ratio = 1.500000e+00 : f32",
+            r#"name = "hello\n" : i8"#,
+            r#"callee = @outer::@"inner""#,
+            "sizes = array<i32: 1, 0, 0>",
+            "weights = dense<[[1, 2], [3, 4]]> : tensor<2x2xi32>",
+            "ty = (i32) -> i64",
+            "linkage = #llvm.linkage<internal>",
+            "map = #map",
+            "nested = {inner = [unit, -3 : index], empty = {}}",
+            "fastmath",
+        ];
+        let op = parse_op(&format!(
+            r#""test.op"() {{{}}} : () -> ()"#,
+            attributes.join(", ")
+        ));
+        insta::assert_debug_snapshot!(op.attributes);
+        for (attribute, expected) in op.attributes.iter().zip(attributes) {
+            assert_eq!(attribute.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn match_attributes() {
+        let op = parse_op(
+            r#""func.call"() <{callee = @f}> {value = 42 : i64, ratio = 1.5 : f32, s = "x"} : () -> ()"#,
+        );
+        assert_eq!(op.properties.len(), 1);
+        assert_eq!(op.symbols, vec!["f".to_owned()]);
+        assert!(op.is_match("func.call"));
+        assert!(op.is_match("value=42"));
+        assert!(op.is_match("value = 42 : i64"));
+        assert!(op.is_match("ratio=1.50"));
+        assert!(op.is_match("s=x"));
+        assert!(op.is_match("callee=@f"));
+        assert!(!op.is_match("value=43"));
+        assert!(!op.is_match("42"));
+        assert_eq!(
+            op.attributes[0].value,
+            Some(AttributeValue::Integer {
+                value: 42,
+                r#type: Some("i64".to_owned())
+            })
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! Which operations of each dialect are terminators, branch to successor blocks,
+//! or have regions isolated from above, see <https://mlir.llvm.org/docs/Traits/>.
+//!
+//! A dialect file is written in TOML with a table per dialect, for example:
+//!
+//! ```toml
+//! [dialect.scf]
+//! terminators = ["yield", "condition"]
+//!
+//! [dialect.cf]
+//! terminators = ["br", "cond_br"]
+//! successors = ["br", "cond_br"]
+//!
+//! [dialect.func]
+//! isolated_from_above = ["func"]
+//! ```
+//!
+//! Operations are named without their dialect prefix, and `*` in a name matches any text.
+//! Terminators return control to the operation enclosing them, unless they are listed under
+//! `successors`, in which case they only branch to their successor blocks (so `llvm.unreachable`
+//! is listed there with none). Operations which their dialect does not list, or whose dialect
+//! is not declared, are looked up in the dialect `"*"` instead.
+
+use std::{collections::HashMap, str::FromStr};
+
+use serde::Deserialize;
+#[cfg(test)]
+use serde::Serialize;
+
+use super::{for_each_op, Expr};
+use crate::common::glob;
+
+/// The dialects known without loading a dialect file.
+const BUILTIN: &str = include_str!("dialects.toml");
+
+/// The dialect whose declaration is used for the operations not listed by their own dialect.
+const FALLBACK: &str = "*";
+
+/// The traits of an operation which change how it is drawn.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct OpTraits {
+    pub terminator: bool,
+    /// Whether the operation branches to successor blocks rather than returning to its parent.
+    pub successors: bool,
+    /// Whether the regions of the operation cannot use values defined outside them.
+    pub isolated_from_above: bool,
+}
+
+#[derive(Clone, Default, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Dialect {
+    #[serde(default)]
+    pub terminators: Vec<String>,
+    #[serde(default)]
+    pub successors: Vec<String>,
+    #[serde(default)]
+    pub isolated_from_above: Vec<String>,
+}
+
+impl Dialect {
+    /// The traits of `op`, if the dialect lists it.
+    fn traits(&self, op: &str) -> Option<OpTraits> {
+        let contains = |ops: &[String]| ops.iter().any(|pattern| glob(pattern, op));
+        let successors = contains(&self.successors);
+        let traits = OpTraits {
+            terminator: successors || contains(&self.terminators),
+            successors,
+            isolated_from_above: contains(&self.isolated_from_above),
+        };
+        (traits != OpTraits::default()).then_some(traits)
+    }
+}
+
+/// The dialects declared by a dialect file on top of the built-in ones.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DialectRegistry {
+    #[serde(default, rename = "dialect")]
+    dialects: HashMap<String, Dialect>,
+}
+
+impl Default for DialectRegistry {
+    fn default() -> Self {
+        toml::from_str(BUILTIN).expect("built-in dialects are invalid")
+    }
+}
+
+impl FromStr for DialectRegistry {
+    type Err = toml::de::Error;
+
+    /// Read a dialect file, whose dialects replace the built-in declarations of the same name.
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut registry = Self::default();
+        registry.extend(toml::from_str(source)?);
+        Ok(registry)
+    }
+}
+
+impl DialectRegistry {
+    /// Declare the dialects of `other`, replacing any existing declarations of them.
+    pub fn extend(&mut self, other: DialectRegistry) {
+        self.dialects.extend(other.dialects);
+    }
+
+    /// The traits of the operation named `name`, such as `scf.yield`.
+    #[must_use]
+    pub fn traits(&self, name: &str) -> OpTraits {
+        let (dialect, op) = name.split_once('.').unwrap_or(("builtin", name));
+        [dialect, FALLBACK]
+            .into_iter()
+            .filter_map(|dialect| self.dialects.get(dialect))
+            .find_map(|dialect| dialect.traits(op))
+            .unwrap_or_default()
+    }
+
+    /// Set the traits of every operation of `expr`.
+    pub fn apply(&self, expr: &mut Expr) {
+        for_each_op(expr, &mut |op| op.traits = self.traits(&op.name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use from_pest::FromPest;
+    use pest::Parser;
+
+    use super::{DialectRegistry, OpTraits};
+    use crate::{
+        graph::ConvertError,
+        language::{
+            mlir::{
+                internal::{MlirParser, Rule, TopLevelItem},
+                Module, Value,
+            },
+            OpInfo, CF,
+        },
+    };
+
+    fn parse_module(source: &str) -> Module {
+        let mut pairs = MlirParser::parse(Rule::toplevel, source).unwrap();
+        Module::from(Vec::<TopLevelItem>::from_pest(&mut pairs).unwrap())
+    }
+
+    #[test]
+    fn dialects() {
+        let registry = DialectRegistry::default();
+        let terminator = OpTraits {
+            terminator: true,
+            ..Default::default()
+        };
+        assert_eq!(registry.traits("func.return"), terminator);
+        assert_eq!(registry.traits("scf.condition"), terminator);
+        assert_eq!(registry.traits("foo.yield"), terminator);
+        assert_eq!(registry.traits("cf.assert"), OpTraits::default());
+        assert_eq!(registry.traits("arith.addi"), OpTraits::default());
+        assert_eq!(
+            registry.traits("llvm.unreachable"),
+            OpTraits {
+                terminator: true,
+                successors: true,
+                isolated_from_above: false,
+            }
+        );
+        assert!(registry.traits("module").isolated_from_above);
+        assert!(registry.traits("func.func").isolated_from_above);
+
+        let registry: DialectRegistry = r#"
+[dialect.test]
+terminators = ["done"]
+isolated_from_above = ["kernel"]
+
+[dialect.scf]
+terminators = ["yield"]
+"#
+        .parse()
+        .unwrap();
+        assert_eq!(registry.traits("test.done"), terminator);
+        assert!(registry.traits("test.kernel").isolated_from_above);
+        assert_eq!(registry.traits("test.return"), terminator);
+        assert_eq!(registry.traits("test.kernel_end"), OpTraits::default());
+        assert_eq!(registry.traits("scf.condition"), OpTraits::default());
+        assert_eq!(registry.traits("func.return"), terminator);
+
+        assert!("[dialect.test]\nterminator = [\"done\"]"
+            .parse::<DialectRegistry>()
+            .is_err());
+    }
+
+    /// Ops named `yield` or with `return` in their name end their region,
+    /// as they did before dialects were declared.
+    #[test]
+    fn returns() {
+        let cf = |name: &str| {
+            let module = parse_module(&format!(r#""{name}"() : () -> ()"#));
+            let Value::Op { op, .. } = &module.expr.binds[0].value else {
+                unreachable!()
+            };
+            op.get_cf()
+        };
+        for name in [
+            "return",
+            "yield",
+            "func.return",
+            "gpu.return",
+            "scf.reduce.return",
+            "foo.return",
+            "foo.return_value",
+            "spirv.Return",
+            "spirv.ReturnValue",
+            "arc.output",
+            "hw.output",
+        ] {
+            assert!(matches!(cf(name), Some(CF::Return)), "{name}");
+        }
+        for name in ["arith.addi", "cf.assert", "yielded", "module"] {
+            assert!(cf(name).is_none(), "{name}");
+        }
+    }
+
+    #[test]
+    fn isolated_from_above() {
+        let module = parse_module(
+            r#"
+%0 = "arith.constant"() {value = 1 : i32} : () -> i32
+"scf.while"() ({
+  %1 = "arith.addi"(%0, %0) : (i32, i32) -> i32
+  "scf.condition"(%1) : (i32) -> ()
+}) : () -> ()
+"#,
+        );
+        let Value::Op { args, .. } = &module.expr.binds[1].value else {
+            unreachable!()
+        };
+        let Value::Thunk(thunk) = &args[0] else {
+            unreachable!()
+        };
+        let Value::Op { op, .. } = &thunk.body.binds[1].value else {
+            unreachable!()
+        };
+        assert!(matches!(op.get_cf(), Some(CF::Return)));
+        assert!(module.expr.to_graph(false).is_ok());
+
+        let module = parse_module(
+            r#"
+%0 = "arith.constant"() {value = 1 : i32} : () -> i32
+"func.func"() <{function_type = () -> i32, sym_name = "f"}> ({
+  "func.return"(%0) : (i32) -> ()
+}) : () -> ()
+"#,
+        );
+        assert!(matches!(
+            module.expr.to_graph(false),
+            Err(ConvertError::Captured(_))
+        ));
+    }
+}
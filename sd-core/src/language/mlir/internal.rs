@@ -1,17 +1,23 @@
-use std::fmt::Display;
-
+use pest::iterators::{Pair, Pairs};
 use pest_ast::FromPest;
 use pest_derive::Parser;
 
+use super::{
+    attribute::{Attribute, AttributeValue, Elements},
+    location::Location,
+};
 use crate::language::span_into_str;
 
 #[derive(Parser)]
 #[grammar = "language/mlir.pest"]
 pub struct MlirParser;
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum TopLevelItem {
     Operation(Operation),
+    AttributeAlias { name: String, value: AttributeValue },
+    TypeAlias { name: String, r#type: String },
     Other(String),
 }
 
@@ -27,10 +33,26 @@ impl from_pest::FromPest<'_> for TopLevelItem {
             let span = pair.as_span();
             let mut inner = pair.clone().into_inner();
             let inner = &mut inner;
-            let this = Operation::from_pest(inner).map_or_else(
-                |_| TopLevelItem::Other(span.as_str().to_owned()),
-                TopLevelItem::Operation,
-            );
+            let this = match inner.peek().map(|pair| pair.as_rule()) {
+                Some(Rule::attribute_alias_def) => {
+                    let mut def = inner.next().unwrap().into_inner();
+                    TopLevelItem::AttributeAlias {
+                        name: def.next().unwrap().as_str().to_owned(),
+                        value: attribute_value(def.next().unwrap()),
+                    }
+                }
+                Some(Rule::type_alias_def) => {
+                    let mut def = inner.next().unwrap().into_inner();
+                    TopLevelItem::TypeAlias {
+                        name: def.next().unwrap().as_str().to_owned(),
+                        r#type: def.next().unwrap().as_str().to_owned(),
+                    }
+                }
+                _ => Operation::from_pest(inner).map_or_else(
+                    |_| TopLevelItem::Other(span.as_str().to_owned()),
+                    TopLevelItem::Operation,
+                ),
+            };
             *pest = clone;
             Ok(this)
         } else {
@@ -70,47 +92,217 @@ pub struct ValueIndex(
     #[pest_ast(outer(with(span_into_str), with(str::parse), with(Result::unwrap)))] pub usize,
 );
 
-#[allow(dead_code)]
 #[derive(Debug, FromPest)]
-#[pest_ast(rule(Rule::attribute_entry))]
-pub struct Attribute(
-    #[pest_ast(inner(with(span_into_str), with(str::to_string)))] String,
-    Option<AttributeValue>,
-);
-
-impl Attribute {
-    pub(crate) fn is_sym_name(&self) -> Option<String> {
-        if self.0 == "sym_name" {
-            self.1
-                .as_ref()
-                .map(|x| x.0.trim_matches(|c| c == '\"').to_owned())
-        } else {
-            None
+#[pest_ast(rule(Rule::dictionary_properties))]
+pub struct Properties(pub Vec<Attribute>);
+
+/// Implement `FromPest` for a type by converting a single pair of the given rule with a function.
+macro_rules! from_pair {
+    ($ast:ident, $rule:ident, $convert:ident) => {
+        impl from_pest::FromPest<'_> for $ast {
+            type Rule = Rule;
+            type FatalError = ::from_pest::Void;
+            fn from_pest(
+                pest: &mut ::from_pest::pest::iterators::Pairs<Rule>,
+            ) -> ::std::result::Result<Self, ::from_pest::ConversionError<::from_pest::Void>> {
+                let mut clone = pest.clone();
+                let pair = clone.next().ok_or(::from_pest::ConversionError::NoMatch)?;
+                if pair.as_rule() == Rule::$rule {
+                    *pest = clone;
+                    Ok($convert(pair))
+                } else {
+                    Err(::from_pest::ConversionError::NoMatch)
+                }
+            }
         }
+    };
+}
+
+from_pair!(Attribute, attribute_entry, attribute);
+from_pair!(AttributeValue, attribute_value, attribute_value);
+from_pair!(Location, location, location);
+
+fn attribute(pair: Pair<'_, Rule>) -> Attribute {
+    let mut inner = pair.into_inner();
+    Attribute {
+        name: inner.next().unwrap().as_str().to_owned(),
+        value: inner.next().map(attribute_value),
     }
-    pub(crate) fn get_symbol(&self) -> Option<String> {
-        self.1.as_ref().and_then(|x| {
-            let mut chars = x.0.chars();
-            if let Some('@') = chars.next() {
-                Some(chars.filter(|c| *c != '@' && *c != '\"').collect())
+}
+
+/// Convert a parsed attribute value into a typed tree.
+fn attribute_value(pair: Pair<'_, Rule>) -> AttributeValue {
+    let with_type = |mut inner: Pairs<'_, Rule>| {
+        let value = inner.next().unwrap().as_str().to_owned();
+        (value, inner.next().map(|pair| pair.as_str().to_owned()))
+    };
+    match pair.as_rule() {
+        Rule::attribute_value | Rule::builtin_attribute => {
+            let mut inner = pair.into_inner().peekable();
+            match inner.peek().map(Pair::as_rule) {
+                // Dictionaries are silent, so their entries are the inner pairs
+                None | Some(Rule::attribute_entry) => {
+                    AttributeValue::Dictionary(inner.map(attribute).collect())
+                }
+                Some(_) => attribute_value(inner.next().unwrap()),
+            }
+        }
+        Rule::attribute_alias => AttributeValue::Alias(pair.into_inner().as_str().to_owned()),
+        Rule::dialect_attribute => {
+            let mut inner = pair.into_inner().next().unwrap().into_inner();
+            let namespace = inner.next().unwrap().as_str();
+            let mut inner = inner.map(|pair| (pair.as_rule(), pair.as_str().to_owned()));
+            let (mut name, body) = match inner.next() {
+                Some((Rule::pretty_dialect_attribute_lead_ident, name)) => {
+                    (Some(name), inner.next().map(|(_, body)| body))
+                }
+                Some((_, body)) => (None, Some(body)),
+                None => (None, None),
+            };
+            // Namespaces are bare identifiers, which may swallow the name of a pretty attribute
+            let dialect = match namespace.split_once('.') {
+                Some((dialect, prefix)) if name.is_none() => {
+                    name = Some(prefix.to_owned());
+                    dialect.to_owned()
+                }
+                _ => namespace.to_owned(),
+            };
+            AttributeValue::Dialect {
+                dialect,
+                name,
+                body,
+            }
+        }
+        Rule::array_attribute => {
+            AttributeValue::Array(pair.into_inner().map(attribute_value).collect())
+        }
+        Rule::dense_array_attribute => {
+            let mut inner = pair.into_inner();
+            AttributeValue::DenseArray {
+                r#type: inner.next().unwrap().as_str().to_owned(),
+                elements: inner.map(|pair| pair.as_str().to_owned()).collect(),
+            }
+        }
+        Rule::dense_intorfloat_elements_attribute | Rule::dense_string_elements_attribute => {
+            let mut inner = pair.into_inner();
+            let elements = inner.next().unwrap();
+            let elements = if elements.as_rule() == Rule::tensor_literal {
+                tensor_literal(elements)
             } else {
-                None
+                Elements::Attribute(Box::new(attribute_value(elements)))
+            };
+            AttributeValue::DenseElements {
+                elements,
+                r#type: inner.next().unwrap().as_str().to_owned(),
+            }
+        }
+        Rule::float_attribute => {
+            let (literal, r#type) = with_type(pair.into_inner());
+            AttributeValue::Float { literal, r#type }
+        }
+        Rule::integer_attribute => match pair.as_str() {
+            "true" => AttributeValue::Bool(true),
+            "false" => AttributeValue::Bool(false),
+            text => {
+                let text = text.to_owned();
+                let (literal, r#type) = with_type(pair.into_inner());
+                parse_integer(&literal).map_or(AttributeValue::Other(text), |value| {
+                    AttributeValue::Integer { value, r#type }
+                })
+            }
+        },
+        Rule::string_attribute => {
+            let (literal, r#type) = with_type(pair.into_inner());
+            AttributeValue::String {
+                value: remove_quotes(&literal).to_owned(),
+                r#type,
             }
-        })
+        }
+        Rule::symbol_ref_attribute => AttributeValue::SymbolRef(symbol_refs(pair.as_str())),
+        Rule::type_attribute => AttributeValue::Type(pair.as_str().to_owned()),
+        Rule::unit_attribute => AttributeValue::Unit,
+        Rule::location => AttributeValue::Location(location(pair)),
+        _ => AttributeValue::Other(pair.as_str().to_owned()),
     }
 }
 
-impl Display for Attribute {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(x) = &self.1 {
-            write!(f, "{} = {}", self.0, x.0)
-        } else {
-            f.write_str(&self.0)
+/// Convert a parsed location into a typed tree.
+fn location(pair: Pair<'_, Rule>) -> Location {
+    let pair = pair.into_inner().next().unwrap();
+    let rule = pair.as_rule();
+    let mut inner = pair.into_inner();
+    match rule {
+        Rule::filelinecol_location => {
+            let mut next = || inner.next().unwrap().as_str();
+            Location::FileLineCol {
+                file: remove_quotes(next()).to_owned(),
+                line: parse_dec_or_hex(next()).unwrap_or_default(),
+                column: parse_dec_or_hex(next()).unwrap_or_default(),
+            }
         }
+        Rule::name_location => Location::Name {
+            name: remove_quotes(inner.next().unwrap().as_str()).to_owned(),
+            child: inner.next().map(|pair| Box::new(location(pair))),
+        },
+        Rule::callsite_location => Location::CallSite {
+            callee: Box::new(location(inner.next().unwrap())),
+            caller: Box::new(location(inner.next().unwrap())),
+        },
+        Rule::fused_location => {
+            let mut inner = inner.peekable();
+            let metadata = inner
+                .next_if(|pair| pair.as_rule() == Rule::fusion_metadata)
+                .map(|pair| pair.into_inner().as_str().to_owned());
+            Location::Fused {
+                metadata,
+                locations: inner.map(location).collect(),
+            }
+        }
+        Rule::opaque_location => Location::Opaque(Box::new(location(inner.next().unwrap()))),
+        Rule::attribute_alias => Location::Alias(inner.as_str().to_owned()),
+        _ => Location::Unknown,
     }
 }
 
-passthrough!(inner, AttributeValue, attribute_value);
+fn tensor_literal(pair: Pair<'_, Rule>) -> Elements {
+    if pair.as_str().starts_with('[') {
+        Elements::List(pair.into_inner().map(tensor_literal).collect())
+    } else {
+        Elements::Literal(pair.as_str().to_owned())
+    }
+}
+
+/// Parse a decimal or hexadecimal integer literal, which may be negative.
+fn parse_integer(literal: &str) -> Option<i128> {
+    let (sign, literal) = match literal.strip_prefix('-') {
+        Some(literal) => (-1, literal),
+        None => (1, literal),
+    };
+    let value = match literal.strip_prefix("0x") {
+        Some(hex) => i128::from_str_radix(hex, 16),
+        None => literal.parse(),
+    };
+    value.ok().map(|value| sign * value)
+}
+
+/// Split nested symbol references such as `@module::@"function"` into their names.
+fn symbol_refs(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(name) = rest.strip_prefix('@') {
+        let end = if let Some(quoted) = name.strip_prefix('"') {
+            quoted.find('"').map_or(name.len(), |i| i + 2)
+        } else {
+            name.find("::").unwrap_or(name.len())
+        };
+        names.push(name[..end].to_owned());
+        rest = name[end..]
+            .trim_start()
+            .trim_start_matches("::")
+            .trim_start();
+    }
+    names
+}
 
 #[derive(Debug, FromPest)]
 #[pest_ast(rule(Rule::generic_operation))]
@@ -125,7 +317,7 @@ pub struct GenericOperation {
     pub operands: Vec<Value>,
     pub successors: Vec<Successor>,
     //  data attached to the operation
-    pub properties: Vec<Attribute>,
+    pub properties: Option<Properties>,
     // enclosed regions
     pub regions: Vec<Region>,
     // attributes are *constant* data, no variables allowed
@@ -173,8 +365,6 @@ pub struct Operation {
     pub location: Option<Location>,
 }
 
-passthrough!(inner, Location, location);
-
 #[derive(Debug, FromPest)]
 #[pest_ast(rule(Rule::value_id_and_type))]
 pub struct TypedArg {
@@ -0,0 +1,130 @@
+//! Source locations of MLIR operations, see <https://mlir.llvm.org/docs/Dialects/Builtin/#location-attributes>.
+
+use std::fmt::Display;
+
+use itertools::Itertools;
+#[cfg(test)]
+use serde::Serialize;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub enum Location {
+    /// A position in a source file, such as `"file.cpp":12:3`.
+    FileLineCol {
+        file: String,
+        line: usize,
+        column: usize,
+    },
+    /// A named location, such as `"x"("file.cpp":12:3)`.
+    Name {
+        name: String,
+        child: Option<Box<Location>>,
+    },
+    /// A call of `callee` from `caller`.
+    CallSite {
+        callee: Box<Location>,
+        caller: Box<Location>,
+    },
+    /// Several locations fused together, with metadata kept as written.
+    Fused {
+        metadata: Option<String>,
+        locations: Vec<Location>,
+    },
+    Opaque(Box<Location>),
+    /// A reference to a location alias, such as `#loc3`.
+    Alias(String),
+    Unknown,
+}
+
+impl Location {
+    /// The file and line this location refers to, if any.
+    /// Call sites give the location of their callee, and fused locations their first location in a file.
+    #[must_use]
+    pub fn file_line(&self) -> Option<(&str, usize)> {
+        match self {
+            Location::FileLineCol { file, line, .. } => Some((file, *line)),
+            Location::Name { child, .. } => child.as_ref()?.file_line(),
+            Location::CallSite { callee, .. } => callee.file_line(),
+            Location::Fused { locations, .. } => locations.iter().find_map(Location::file_line),
+            Location::Opaque(location) => location.file_line(),
+            Location::Alias(_) | Location::Unknown => None,
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::FileLineCol { file, line, column } => write!(f, "\"{file}\":{line}:{column}"),
+            Location::Name { name, child: None } => write!(f, "\"{name}\""),
+            Location::Name {
+                name,
+                child: Some(child),
+            } => write!(f, "\"{name}\"({child})"),
+            Location::CallSite { callee, caller } => write!(f, "callsite({callee} at {caller})"),
+            Location::Fused {
+                metadata,
+                locations,
+            } => {
+                f.write_str("fused")?;
+                if let Some(metadata) = metadata {
+                    write!(f, "<{metadata}>")?;
+                }
+                write!(f, "[{}]", locations.iter().join(", "))
+            }
+            Location::Opaque(location) => write!(f, "opaque<{location}>"),
+            Location::Alias(alias) => write!(f, "#{alias}"),
+            Location::Unknown => f.write_str("?"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use from_pest::FromPest;
+    use pest::Parser;
+
+    use crate::{
+        language::mlir::{
+            internal::{MlirParser, Operation, Rule},
+            Op, Value,
+        },
+        prettyprinter::PrettyPrint,
+    };
+
+    #[test]
+    fn locations() {
+        let locations = [
+            (r#""file.cpp":12:3"#, Some(("file.cpp", 12))),
+            (r#""x"("a.py":4:1)"#, Some(("a.py", 4))),
+            (
+                r#"callsite("inner.h":7:2 at "main.cpp":20:5)"#,
+                Some(("inner.h", 7)),
+            ),
+            (r#"fused<"cse">["x", "b.cpp":9:1]"#, Some(("b.cpp", 9))),
+            ("#loc3", None),
+            ("?", None),
+        ];
+        for (location, file_line) in locations {
+            let source = format!(r#""test.op"() {{value = 1 : i32}} : () -> () loc({location})"#);
+            let mut pairs = MlirParser::parse(Rule::operation, &source).unwrap();
+            let Value::Op { op, .. } = Operation::from_pest(&mut pairs).unwrap().into() else {
+                unreachable!()
+            };
+            let parsed = op.location.as_ref().unwrap();
+            assert_eq!(parsed.to_string(), location);
+            assert_eq!(parsed.file_line(), file_line);
+            assert_eq!(
+                op.to_pretty(),
+                format!("test.op {{value = 1 : i32}} loc({location})")
+            );
+            assert_eq!(
+                op,
+                Op {
+                    location: None,
+                    ..op.clone()
+                }
+            );
+        }
+    }
+}
@@ -1,16 +1,27 @@
 #![allow(clippy::clone_on_copy)]
 
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
 
 use itertools::Itertools;
 
+pub mod alias;
+pub mod attribute;
+pub mod dialect;
 pub mod internal;
+pub mod location;
 
 use pretty::RcDoc;
-#[cfg(test)]
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use self::internal::Attribute;
+use self::{
+    alias::Aliases,
+    attribute::Attribute,
+    dialect::{DialectRegistry, OpTraits},
+    location::Location,
+};
 use super::{Fresh, Language, OpInfo, CF};
 use crate::{
     common::{Matchable, Unit},
@@ -18,9 +29,12 @@ use crate::{
     prettyprinter::PrettyPrint,
 };
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Serialize, Deserialize)]
 pub struct MlirSettings {
     pub sym_name_linking: bool,
+    /// Show attributes and types with their aliases expanded.
+    #[serde(default)]
+    pub expand_aliases: bool,
 }
 
 pub struct Mlir;
@@ -40,14 +54,83 @@ pub type Value = super::Value<Mlir>;
 pub type Thunk = super::Thunk<Mlir>;
 pub type Block = super::Block<Mlir>;
 
-#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+/// The operations of an MLIR file, along with the aliases they may refer to.
+#[derive(Clone, Debug)]
+pub struct Module {
+    /// The operations, whose locations have their aliases expanded.
+    pub expr: Expr,
+    pub aliases: Aliases,
+}
+
+impl Module {
+    /// The operations with the aliases in their attributes expanded.
+    #[must_use]
+    pub fn expanded(&self) -> Expr {
+        let mut expr = self.expr.clone();
+        self.aliases.expand(&mut expr);
+        expr
+    }
+}
+
+#[derive(Clone, Debug)]
 #[cfg_attr(test, derive(Serialize))]
 pub struct Op {
     pub name: String,
     pub successors: Vec<BlockAddr>,
-    pub attributes: String,
+    /// Attributes stored inline in the operation, written as `<{...}>`.
+    pub properties: Vec<Attribute>,
+    /// Attributes written as `{...}`.
+    pub attributes: Vec<Attribute>,
     pub sym_name: Option<String>,
     pub symbols: Vec<String>,
+    /// Where the operation came from, which is not taken into account when comparing operations.
+    pub location: Option<Location>,
+    /// The traits declared for the operation by its dialect.
+    pub traits: OpTraits,
+}
+
+impl Op {
+    #[allow(clippy::type_complexity)]
+    fn key(
+        &self,
+    ) -> (
+        &String,
+        &Vec<BlockAddr>,
+        &Vec<Attribute>,
+        &Vec<Attribute>,
+        &Option<String>,
+        &Vec<String>,
+        &OpTraits,
+    ) {
+        (
+            &self.name,
+            &self.successors,
+            &self.properties,
+            &self.attributes,
+            &self.sym_name,
+            &self.symbols,
+            &self.traits,
+        )
+    }
+
+    /// The properties and then the attributes of the operation.
+    pub fn all_attributes(&self) -> impl Iterator<Item = &Attribute> {
+        self.properties.iter().chain(&self.attributes)
+    }
+}
+
+impl PartialEq for Op {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Op {}
+
+impl Hash for Op {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
 }
 
 impl Display for Op {
@@ -57,20 +140,20 @@ impl Display for Op {
 }
 
 impl Matchable for Op {
+    /// Matches the name of the operation, or a query `name=value` matching one of its attributes.
     fn is_match(&self, query: &str) -> bool {
         self.name == query
+            || self
+                .all_attributes()
+                .any(|attribute| attribute.is_match(query))
     }
 }
 
 impl OpInfo<Mlir> for Op {
     fn get_cf(&self) -> Option<CF<Mlir>> {
-        if !self.successors.is_empty() {
+        if self.traits.successors || !self.successors.is_empty() {
             Some(CF::Brs(self.successors.clone()))
-        } else if self.name.contains("return")
-            || self.name == "arc.output"
-            || self.name == "yield"
-            || self.name == "hw.output"
-        {
+        } else if self.traits.terminator {
             Some(CF::Return)
         } else {
             None
@@ -84,6 +167,18 @@ impl OpInfo<Mlir> for Op {
     fn sym_name(&self) -> Option<Symbol> {
         self.sym_name.as_ref().map(|x| Symbol(x.clone()))
     }
+
+    fn is_pure(&self) -> bool {
+        self.sym_name.is_none()
+            && (self.name == "hw.constant"
+                || ["arith.", "math.", "comb."]
+                    .iter()
+                    .any(|dialect| self.name.starts_with(dialect)))
+    }
+
+    fn is_isolated_from_above(&self) -> bool {
+        self.traits.isolated_from_above
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
@@ -223,6 +318,66 @@ impl From<internal::Successor> for BlockAddr {
     }
 }
 
+impl Module {
+    /// Build a module from the items of an MLIR file, whose operations are given the traits
+    /// declared by `dialects`.
+    #[must_use]
+    pub fn new(items: Vec<internal::TopLevelItem>, dialects: &DialectRegistry) -> Self {
+        let mut ops = Vec::new();
+        let mut aliases = Aliases::default();
+        for item in items {
+            match item {
+                internal::TopLevelItem::Operation(op) => ops.push(op),
+                internal::TopLevelItem::AttributeAlias { name, value } => {
+                    aliases.define_attribute(name, value);
+                }
+                internal::TopLevelItem::TypeAlias { name, r#type } => {
+                    aliases.define_type(name, r#type);
+                }
+                internal::TopLevelItem::Other(_) => (),
+            }
+        }
+        let mut expr = Expr::from(ops);
+        aliases.expand_locations(&mut expr);
+        dialects.apply(&mut expr);
+        Module { expr, aliases }
+    }
+}
+
+impl From<Vec<internal::TopLevelItem>> for Module {
+    fn from(items: Vec<internal::TopLevelItem>) -> Self {
+        Module::new(items, &DialectRegistry::default())
+    }
+}
+
+/// Call `f` on every operation of `expr`, including those in regions.
+fn for_each_op(expr: &mut Expr, f: &mut impl FnMut(&mut Op)) {
+    fn visit(value: &mut Value, f: &mut impl FnMut(&mut Op)) {
+        match value {
+            Value::Variable(_) => (),
+            Value::Thunk(thunk) => {
+                for_each_op(&mut thunk.body, f);
+                for block in &mut thunk.blocks {
+                    for_each_op(&mut block.expr, f);
+                }
+            }
+            Value::Op { op, args } => {
+                f(op);
+                for arg in args {
+                    visit(arg, f);
+                }
+            }
+        }
+    }
+
+    for bind in &mut expr.binds {
+        visit(&mut bind.value, f);
+    }
+    for value in &mut expr.values {
+        visit(value, f);
+    }
+}
+
 impl From<Vec<internal::Operation>> for Expr {
     fn from(ops: Vec<internal::Operation>) -> Self {
         Expr {
@@ -233,10 +388,13 @@ impl From<Vec<internal::Operation>> for Expr {
 }
 
 impl From<internal::Operation> for Bind {
-    fn from(op: internal::Operation) -> Self {
+    fn from(mut op: internal::Operation) -> Self {
         Bind {
-            defs: op.result.into_iter().map_into::<Vec<Var>>().concat(),
-            value: op.operation.into(),
+            defs: std::mem::take(&mut op.result)
+                .into_iter()
+                .map_into::<Vec<Var>>()
+                .concat(),
+            value: op.into(),
         }
     }
 }
@@ -265,37 +423,27 @@ impl From<internal::Block> for Block {
     }
 }
 
-impl From<internal::GenericOperation> for Value {
-    fn from(generic_op: internal::GenericOperation) -> Self {
+impl From<internal::Operation> for Value {
+    fn from(op: internal::Operation) -> Self {
+        let generic_op = op.operation;
+        let properties = generic_op
+            .properties
+            .map(|properties| properties.0)
+            .unwrap_or_default();
+        let attributes = generic_op.attributes;
+        let all_attributes = || properties.iter().chain(&attributes);
         Value::Op {
             op: Op {
                 name: generic_op.op,
                 successors: generic_op.successors.into_iter().map_into().collect(),
-                attributes: if generic_op.attributes.len() + generic_op.properties.len() == 0 {
-                    String::new()
-                } else {
-                    format!(
-                        "{{{}}}",
-                        generic_op
-                            .attributes
-                            .iter()
-                            .chain(generic_op.properties.iter())
-                            .join(", ")
-                    )
-                },
-                sym_name: generic_op
-                    .attributes
-                    .iter()
-                    .chain(generic_op.properties.iter())
-                    .map(Attribute::is_sym_name)
-                    .find(Option::is_some)
-                    .flatten(),
-                symbols: generic_op
-                    .attributes
-                    .iter()
-                    .chain(generic_op.properties.iter())
-                    .filter_map(|attr| attr.get_symbol())
-                    .collect(),
+                sym_name: all_attributes()
+                    .find_map(Attribute::sym_name)
+                    .map(str::to_owned),
+                symbols: all_attributes().filter_map(Attribute::symbol).collect(),
+                properties,
+                attributes,
+                location: op.location,
+                traits: OpTraits::default(),
             },
             args: generic_op
                 .operands
@@ -8,7 +8,10 @@ use derivative::Derivative;
 use crate::{common::Matchable, hypergraph::traits::WithType, prettyprinter::PrettyPrint};
 
 pub mod chil;
+pub mod dynamic;
+pub mod llvm;
 pub mod mlir;
+pub mod qasm;
 pub mod spartan;
 
 pub(crate) fn span_into_str(span: pest::Span) -> &str {
@@ -57,6 +60,15 @@ pub trait OpInfo<T: Language + ?Sized> {
     fn sym_name(&self) -> Option<T::Symbol> {
         None
     }
+    /// Pure operations have no side effects, so they can be merged or removed by optimisations.
+    fn is_pure(&self) -> bool {
+        false
+    }
+    /// The regions of operations isolated from above cannot use variables defined outside them,
+    /// apart from symbols.
+    fn is_isolated_from_above(&self) -> bool {
+        false
+    }
 }
 
 pub trait Language {
@@ -235,10 +247,7 @@ pub(crate) mod tests {
 
     use super::{
         chil::tests::parse_chil,
-        mlir::{
-            self,
-            internal::{tests::parse_mlir, TopLevelItem},
-        },
+        mlir::{self, internal::tests::parse_mlir},
         spartan::tests::parse_sd,
         Expr, Language,
     };
@@ -285,14 +294,7 @@ pub(crate) mod tests {
             }
             Some(ext) if ext == OsStr::new("mlir") => {
                 let (name, items) = parse_mlir(raw_path);
-                let ops: Vec<mlir::internal::Operation> = items
-                    .into_iter()
-                    .filter_map(|x| match x {
-                        TopLevelItem::Operation(y) => Some(y),
-                        TopLevelItem::Other(_) => None,
-                    })
-                    .collect();
-                let expr = mlir::Expr::from(ops);
+                let expr = mlir::Module::from(items).expr;
                 ("mlir", name, Box::new(expr))
             }
             _ => unreachable!(),
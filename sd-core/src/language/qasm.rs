@@ -0,0 +1,847 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+};
+
+use from_pest::{ConversionError, FromPest, Void};
+use indexmap::IndexMap;
+use itertools::Itertools;
+use pest::iterators::Pairs;
+use pest_ast::FromPest;
+use pest_derive::Parser;
+#[cfg(test)]
+use serde::Serialize;
+use thiserror::Error;
+
+use super::{span_into_str, Fresh, Language, OpInfo};
+use crate::{
+    common::{Empty, Matchable},
+    hypergraph::traits::{WireType, WithType},
+};
+
+pub struct Qasm;
+
+impl Language for Qasm {
+    type Op = Op;
+    type Var = Var;
+    type Addr = Addr;
+    type VarDef = Var;
+    type BlockAddr = Empty;
+    type Symbol = Empty;
+}
+
+pub type Expr = super::Expr<Qasm>;
+pub type Bind = super::Bind<Qasm>;
+pub type Value = super::Value<Qasm>;
+pub type Thunk = super::Thunk<Qasm>;
+
+#[derive(Parser)]
+#[grammar = "language/qasm.pest"]
+pub struct QasmParser;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub enum OpKind {
+    /// The declaration of a register, whose wires start in the zero state.
+    Register {
+        name: String,
+        size: Option<usize>,
+        classical: bool,
+    },
+    /// An application of a built in or user defined gate.
+    Gate {
+        name: String,
+        params: Option<String>,
+    },
+    Measure,
+    Reset,
+    Barrier,
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct Op {
+    pub kind: OpKind,
+    /// The condition under which the operation is applied, whose bits are inputs of the operation.
+    pub condition: Option<String>,
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            OpKind::Register { name, .. } => f.write_str(name),
+            OpKind::Gate { name, params } => {
+                f.write_str(name)?;
+                if let Some(params) = params {
+                    write!(f, "({params})")?;
+                }
+                Ok(())
+            }
+            OpKind::Measure => f.write_str("measure"),
+            OpKind::Reset => f.write_str("reset"),
+            OpKind::Barrier => f.write_str("barrier"),
+        }
+    }
+}
+
+impl Matchable for Op {
+    fn is_match(&self, query: &str) -> bool {
+        match &self.kind {
+            OpKind::Register { name, .. } | OpKind::Gate { name, .. } => name == query,
+            _ => self.to_string() == query,
+        }
+    }
+}
+
+impl OpInfo<Qasm> for Op {}
+
+/// A single qubit or bit, which is given a new version every time it is acted on.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct Wire {
+    pub register: String,
+    pub index: Option<usize>,
+    pub version: usize,
+}
+
+impl Display for Wire {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.register)?;
+        if let Some(index) = self.index {
+            write!(f, "[{index}]")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub enum Var {
+    Qubit(Wire),
+    Bit(Wire),
+    /// A user defined gate, bound to its definition.
+    Gate(String),
+}
+
+impl WithType for Var {
+    fn get_type(&self) -> WireType {
+        match self {
+            Var::Qubit(_) => WireType::Data,
+            Var::Bit(_) => WireType::Classical,
+            Var::Gate(_) => WireType::SymName,
+        }
+    }
+}
+
+impl From<Empty> for Var {
+    fn from(value: Empty) -> Self {
+        match value {}
+    }
+}
+
+impl Display for Var {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Var::Qubit(wire) | Var::Bit(wire) => wire.fmt(f),
+            Var::Gate(name) => f.write_str(name),
+        }
+    }
+}
+
+impl Matchable for Var {
+    fn is_match(&self, query: &str) -> bool {
+        match self {
+            Var::Qubit(wire) | Var::Bit(wire) => {
+                wire.register == query || wire.to_string() == query
+            }
+            Var::Gate(name) => name == query,
+        }
+    }
+}
+
+impl Fresh for Var {
+    fn fresh(number: usize) -> Self {
+        Var::Qubit(Wire {
+            register: format!("?{number}"),
+            index: None,
+            version: 0,
+        })
+    }
+}
+
+/// The name and parameters of a gate definition.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct Addr {
+    pub name: String,
+    pub params: Vec<String>,
+}
+
+impl Display for Addr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)?;
+        if !self.params.is_empty() {
+            f.write_char('(')?;
+            f.write_str(&self.params.join(", "))?;
+            f.write_char(')')?;
+        }
+        Ok(())
+    }
+}
+
+impl Matchable for Addr {
+    fn is_match(&self, query: &str) -> bool {
+        self.name == query
+    }
+}
+
+// Conversion from pest parse tree to internal AST.
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::statement))]
+pub enum Statement {
+    Qreg(Qreg),
+    Creg(Creg),
+    QubitDecl(QubitDecl),
+    BitDecl(BitDecl),
+    GateDef(GateDef),
+    Measure(Measure),
+    MeasureAssign(MeasureAssign),
+    Reset(Reset),
+    Barrier(Barrier),
+    Conditional(Conditional),
+    GateCall(GateCall),
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::qreg))]
+pub struct Qreg {
+    pub name: Identifier,
+    pub size: Integer,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::creg))]
+pub struct Creg {
+    pub name: Identifier,
+    pub size: Integer,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::qubit_decl))]
+pub struct QubitDecl {
+    pub size: Option<Integer>,
+    pub name: Identifier,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::bit_decl))]
+pub struct BitDecl {
+    pub size: Option<Integer>,
+    pub name: Identifier,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::gate_def))]
+pub struct GateDef {
+    pub name: Identifier,
+    pub params: Option<GateParams>,
+    pub qubits: GateQubits,
+    pub body: Vec<Statement>,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::gate_params))]
+pub struct GateParams(pub Vec<Identifier>);
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::gate_qubits))]
+pub struct GateQubits(pub Vec<Identifier>);
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::measure))]
+pub struct Measure {
+    pub qubit: Argument,
+    pub bit: Argument,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::measure_assign))]
+pub struct MeasureAssign {
+    pub bit: Argument,
+    pub qubit: Argument,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::reset))]
+pub struct Reset {
+    pub qubit: Argument,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::barrier))]
+pub struct Barrier {
+    pub args: Vec<Argument>,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::conditional))]
+pub struct Conditional {
+    pub condition: Condition,
+    pub body: Vec<Statement>,
+}
+
+/// The text of a condition, along with the bits it reads.
+#[derive(Debug)]
+pub struct Condition {
+    pub text: String,
+    pub args: Vec<Argument>,
+}
+
+impl<'pest> FromPest<'pest> for Condition {
+    type Rule = Rule;
+    type FatalError = Void;
+
+    fn from_pest(
+        pest: &mut Pairs<'pest, Self::Rule>,
+    ) -> Result<Self, ConversionError<Self::FatalError>> {
+        let mut clone = pest.clone();
+        let pair = clone.next().ok_or(ConversionError::NoMatch)?;
+        if pair.as_rule() != Rule::condition {
+            return Err(ConversionError::NoMatch);
+        }
+        let text = pair
+            .as_str()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut inner = pair.into_inner();
+        let condition = Condition {
+            text,
+            args: FromPest::from_pest(&mut inner)?,
+        };
+        if inner.next().is_some() {
+            return Err(ConversionError::Extraneous {
+                current_node: stringify!(Condition),
+            });
+        }
+        *pest = clone;
+        Ok(condition)
+    }
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::gate_call))]
+pub struct GateCall {
+    pub name: Identifier,
+    pub params: Option<Params>,
+    pub args: Vec<Argument>,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::params))]
+pub struct Params(#[pest_ast(outer(with(span_into_str), with(str::to_string)))] pub String);
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::argument))]
+pub struct Argument {
+    pub register: Identifier,
+    pub index: Option<Integer>,
+}
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::identifier))]
+pub struct Identifier(#[pest_ast(outer(with(span_into_str), with(str::to_string)))] pub String);
+
+#[derive(Debug, FromPest)]
+#[pest_ast(rule(Rule::integer))]
+pub struct Integer(
+    #[pest_ast(outer(with(span_into_str), with(str::parse), with(Result::unwrap)))] pub usize,
+);
+
+// Conversion from internal AST to expressions.
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum QasmError {
+    #[error("Register `{0}` is not declared")]
+    UnknownRegister(String),
+    #[error("Register `{0}` is declared more than once")]
+    DuplicateRegister(String),
+    #[error("Gate `{0}` is defined more than once")]
+    DuplicateGate(String),
+    #[error("Index {index} is out of range for register `{register}`")]
+    IndexOutOfRange { register: String, index: usize },
+    #[error("Expected `{0}` to be a qubit")]
+    ExpectedQubit(String),
+    #[error("Expected `{0}` to be a bit")]
+    ExpectedBit(String),
+    #[error("Registers of sizes {0} and {1} cannot be broadcast together")]
+    Broadcast(usize, usize),
+    #[error("Gate `{gate}` acts on {expected} qubits but was given {found}")]
+    Arity {
+        gate: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Qubit `{0}` is used more than once by the same operation")]
+    RepeatedQubit(String),
+}
+
+struct Register {
+    size: Option<usize>,
+    classical: bool,
+}
+
+/// A wire of a register, before it is given a version.
+type WireId = (String, Option<usize>);
+
+/// Converts a circuit, giving every wire a new version each time it is acted on.
+#[derive(Default)]
+struct Converter {
+    registers: IndexMap<String, Register>,
+    versions: HashMap<WireId, usize>,
+    /// User defined gates and the number of qubits they act on.
+    gates: HashMap<String, usize>,
+    /// The bits read by the enclosing conditionals, and their combined text.
+    condition: Option<(String, Vec<WireId>)>,
+    binds: Vec<Bind>,
+}
+
+impl Converter {
+    fn var(&self, (register, index): &WireId, version: usize) -> Var {
+        let wire = Wire {
+            register: register.clone(),
+            index: *index,
+            version,
+        };
+        if self.registers[register].classical {
+            Var::Bit(wire)
+        } else {
+            Var::Qubit(wire)
+        }
+    }
+
+    fn current(&self, id: &WireId) -> Var {
+        self.var(id, self.versions.get(id).copied().unwrap_or_default())
+    }
+
+    fn next(&mut self, id: &WireId) -> Var {
+        let version = self.versions.entry(id.clone()).or_default();
+        *version += 1;
+        let version = *version;
+        self.var(id, version)
+    }
+
+    fn declare(
+        &mut self,
+        name: String,
+        size: Option<usize>,
+        classical: bool,
+    ) -> Result<(), QasmError> {
+        if self.registers.contains_key(&name) {
+            return Err(QasmError::DuplicateRegister(name));
+        }
+        self.registers
+            .insert(name.clone(), Register { size, classical });
+        let defs = self
+            .wires_of(&name)
+            .iter()
+            .map(|id| self.current(id))
+            .collect();
+        self.binds.push(Bind {
+            defs,
+            value: Value::Op {
+                op: Op {
+                    kind: OpKind::Register {
+                        name,
+                        size,
+                        classical,
+                    },
+                    condition: None,
+                },
+                args: vec![],
+            },
+        });
+        Ok(())
+    }
+
+    fn wires_of(&self, register: &str) -> Vec<WireId> {
+        match self.registers[register].size {
+            Some(size) => (0..size)
+                .map(|index| (register.to_owned(), Some(index)))
+                .collect(),
+            None => vec![(register.to_owned(), None)],
+        }
+    }
+
+    /// The wires referred to by an argument, which must be qubits or bits as given by `classical`.
+    fn wires(&self, arg: &Argument, classical: bool) -> Result<Vec<WireId>, QasmError> {
+        let name = &arg.register.0;
+        let register = self
+            .registers
+            .get(name)
+            .ok_or_else(|| QasmError::UnknownRegister(name.clone()))?;
+        if register.classical != classical {
+            return Err(if classical {
+                QasmError::ExpectedBit(name.clone())
+            } else {
+                QasmError::ExpectedQubit(name.clone())
+            });
+        }
+        match (&arg.index, register.size) {
+            (None, _) => Ok(self.wires_of(name)),
+            (Some(Integer(index)), Some(size)) if *index < size => {
+                Ok(vec![(name.clone(), Some(*index))])
+            }
+            (Some(Integer(index)), _) => Err(QasmError::IndexOutOfRange {
+                register: name.clone(),
+                index: *index,
+            }),
+        }
+    }
+
+    /// Apply an operation to each position of the given registers, where single wires are shared.
+    fn broadcast(&self, args: Vec<Vec<WireId>>) -> Result<Vec<Vec<WireId>>, QasmError> {
+        let size = args.iter().map(Vec::len).max().unwrap_or_default();
+        if let Some(arg) = args.iter().find(|arg| arg.len() != 1 && arg.len() != size) {
+            return Err(QasmError::Broadcast(arg.len(), size));
+        }
+        Ok((0..size)
+            .map(|i| {
+                args.iter()
+                    .map(|arg| arg[if arg.len() == 1 { 0 } else { i }].clone())
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Add an operation which acts on `qubits`, preceded by `extra` inputs, and produces
+    /// new versions of them followed by new versions of `bits`.
+    fn apply(
+        &mut self,
+        kind: OpKind,
+        extra: Vec<Value>,
+        qubits: &[WireId],
+        bits: &[WireId],
+    ) -> Result<(), QasmError> {
+        for (i, qubit) in qubits.iter().enumerate() {
+            if qubits[..i].contains(qubit) {
+                return Err(QasmError::RepeatedQubit(self.current(qubit).to_string()));
+            }
+        }
+        let mut args = extra;
+        args.extend(qubits.iter().map(|id| Value::Variable(self.current(id))));
+        let condition = self.condition.as_ref().map(|(text, condition_bits)| {
+            args.extend(
+                condition_bits
+                    .iter()
+                    .map(|id| Value::Variable(self.current(id))),
+            );
+            text.clone()
+        });
+        let defs = qubits.iter().chain(bits).map(|id| self.next(id)).collect();
+        self.binds.push(Bind {
+            defs,
+            value: Value::Op {
+                op: Op { kind, condition },
+                args,
+            },
+        });
+        Ok(())
+    }
+
+    fn statements(&mut self, statements: Vec<Statement>) -> Result<(), QasmError> {
+        statements
+            .into_iter()
+            .try_for_each(|statement| self.statement(statement))
+    }
+
+    fn statement(&mut self, statement: Statement) -> Result<(), QasmError> {
+        match statement {
+            Statement::Qreg(Qreg { name, size }) => self.declare(name.0, Some(size.0), false),
+            Statement::Creg(Creg { name, size }) => self.declare(name.0, Some(size.0), true),
+            Statement::QubitDecl(QubitDecl { size, name }) => {
+                self.declare(name.0, size.map(|size| size.0), false)
+            }
+            Statement::BitDecl(BitDecl { size, name }) => {
+                self.declare(name.0, size.map(|size| size.0), true)
+            }
+            Statement::GateDef(def) => self.gate_def(def),
+            Statement::Measure(Measure { qubit, bit })
+            | Statement::MeasureAssign(MeasureAssign { bit, qubit }) => {
+                let args = vec![self.wires(&qubit, false)?, self.wires(&bit, true)?];
+                for row in self.broadcast(args)? {
+                    self.apply(OpKind::Measure, vec![], &row[..1], &row[1..])?;
+                }
+                Ok(())
+            }
+            Statement::Reset(Reset { qubit }) => {
+                for id in self.wires(&qubit, false)? {
+                    self.apply(OpKind::Reset, vec![], &[id], &[])?;
+                }
+                Ok(())
+            }
+            Statement::Barrier(Barrier { args }) => {
+                let qubits = if args.is_empty() {
+                    self.registers
+                        .iter()
+                        .filter(|(_, register)| !register.classical)
+                        .flat_map(|(name, _)| self.wires_of(name))
+                        .collect()
+                } else {
+                    args.iter()
+                        .map(|arg| self.wires(arg, false))
+                        .flatten_ok()
+                        .collect::<Result<Vec<_>, _>>()?
+                };
+                self.apply(OpKind::Barrier, vec![], &qubits, &[])
+            }
+            Statement::Conditional(Conditional { condition, body }) => {
+                let mut bits = condition
+                    .args
+                    .iter()
+                    .map(|arg| self.wires(arg, true))
+                    .flatten_ok()
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut text = condition.text;
+                let outer = self.condition.take();
+                if let Some((outer_text, outer_bits)) = &outer {
+                    text = format!("{outer_text} && {text}");
+                    bits.splice(0..0, outer_bits.iter().cloned());
+                }
+                self.condition = Some((text, bits.into_iter().unique().collect()));
+                let result = self.statements(body);
+                self.condition = outer;
+                result
+            }
+            Statement::GateCall(call) => self.gate_call(call),
+        }
+    }
+
+    fn gate_call(&mut self, call: GateCall) -> Result<(), QasmError> {
+        let name = call.name.0;
+        let args = call
+            .args
+            .iter()
+            .map(|arg| self.wires(arg, false))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Gates which are not defined in the circuit are taken to be built in.
+        let extra = match self.gates.get(&name) {
+            Some(&expected) if expected != args.len() => {
+                return Err(QasmError::Arity {
+                    gate: name,
+                    expected,
+                    found: args.len(),
+                })
+            }
+            Some(_) => vec![Value::Variable(Var::Gate(name.clone()))],
+            None => vec![],
+        };
+        let params = call.params.map(|params| {
+            params.0[1..params.0.len() - 1]
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        for row in self.broadcast(args)? {
+            let kind = OpKind::Gate {
+                name: name.clone(),
+                params: params.clone(),
+            };
+            self.apply(kind, extra.clone(), &row, &[])?;
+        }
+        Ok(())
+    }
+
+    fn gate_def(&mut self, def: GateDef) -> Result<(), QasmError> {
+        let name = def.name.0;
+        if self.gates.contains_key(&name) {
+            return Err(QasmError::DuplicateGate(name));
+        }
+
+        // The body of a gate can only refer to its own qubits and previously defined gates.
+        let mut inner = Converter {
+            gates: self.gates.clone(),
+            ..Default::default()
+        };
+        for qubit in &def.qubits.0 {
+            if inner.registers.contains_key(&qubit.0) {
+                return Err(QasmError::DuplicateRegister(qubit.0.clone()));
+            }
+            inner.registers.insert(
+                qubit.0.clone(),
+                Register {
+                    size: None,
+                    classical: false,
+                },
+            );
+        }
+        let ids: Vec<WireId> = def
+            .qubits
+            .0
+            .into_iter()
+            .map(|qubit| (qubit.0, None))
+            .collect();
+        let args = ids.iter().map(|id| inner.current(id)).collect();
+        inner.statements(def.body)?;
+        let values = ids
+            .iter()
+            .map(|id| Value::Variable(inner.current(id)))
+            .collect();
+
+        self.gates.insert(name.clone(), ids.len());
+        self.binds.push(Bind {
+            defs: vec![Var::Gate(name.clone())],
+            value: Value::Thunk(Thunk {
+                addr: Addr {
+                    name,
+                    params: def
+                        .params
+                        .map(|params| params.0.into_iter().map(|param| param.0).collect())
+                        .unwrap_or_default(),
+                },
+                args,
+                body: Expr {
+                    binds: inner.binds,
+                    values,
+                },
+                blocks: vec![],
+            }),
+        });
+        Ok(())
+    }
+}
+
+impl TryFrom<Vec<Statement>> for Expr {
+    type Error = QasmError;
+
+    fn try_from(statements: Vec<Statement>) -> Result<Self, Self::Error> {
+        let mut converter = Converter::default();
+        converter.statements(statements)?;
+        // The final state of every wire is an output of the circuit.
+        let values = converter
+            .registers
+            .keys()
+            .flat_map(|name| converter.wires_of(name))
+            .map(|id| Value::Variable(converter.current(&id)))
+            .collect();
+        Ok(Expr {
+            binds: converter.binds,
+            values,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use from_pest::FromPest;
+    use pest::Parser;
+
+    use super::{Expr, QasmError, QasmParser, Rule, Statement};
+    use crate::language::tests::ExprTest;
+
+    pub(crate) const TELEPORT: &str = r#"
+OPENQASM 2.0;
+include "qelib1.inc";
+
+// Prepare a Bell pair and teleport the first qubit across it.
+gate bell a, b {
+  h a;
+  cx a, b;
+}
+
+gate correct(theta) q, m {
+  rz(theta / 2) q;
+  cx m, q;
+}
+
+qreg q[3];
+creg c0[1];
+creg c1[1];
+
+u3(0.3, 0.2, 0.1) q[0];
+bell q[1], q[2];
+barrier q;
+cx q[0], q[1];
+h q[0];
+measure q[0] -> c0[0];
+measure q[1] -> c1[0];
+if (c0 == 1) z q[2];
+if (c1 == 1) correct(pi) q[2], q[1];
+reset q[0];
+"#;
+
+    pub(crate) fn parse_qasm(source: &str) -> Vec<Statement> {
+        let mut pairs = QasmParser::parse(Rule::program, source)
+            .unwrap_or_else(|err| panic!("could not parse program\n{err}"));
+        let statements = Vec::<Statement>::from_pest(&mut pairs).unwrap();
+        assert_eq!(pairs.next().map(|pair| pair.as_rule()), Some(Rule::EOI));
+        statements
+    }
+
+    #[test]
+    fn parse_qasm_program() {
+        let statements = parse_qasm(TELEPORT);
+        insta::assert_debug_snapshot!(statements);
+    }
+
+    #[test]
+    fn convert_qasm() -> anyhow::Result<()> {
+        let expr = Expr::try_from(parse_qasm(TELEPORT))?;
+        insta::assert_debug_snapshot!(expr);
+        expr.graph_test("teleport", "qasm", false)?;
+        Ok(())
+    }
+
+    #[test]
+    fn convert_qasm_broadcast() -> anyhow::Result<()> {
+        let source = "
+OPENQASM 3.0;
+qubit[2] q;
+qubit r;
+bit[2] c;
+cx r, q;
+c = measure q;
+";
+        let expr = Expr::try_from(parse_qasm(source))?;
+        expr.graph_test("broadcast", "qasm", false)?;
+        Ok(())
+    }
+
+    #[test]
+    fn convert_qasm_errors() {
+        let cases = [
+            (
+                "qreg q[2]; h p;",
+                QasmError::UnknownRegister("p".to_owned()),
+            ),
+            (
+                "qreg q[2]; h q[2];",
+                QasmError::IndexOutOfRange {
+                    register: "q".to_owned(),
+                    index: 2,
+                },
+            ),
+            (
+                "qreg q[2]; creg c[2]; h c;",
+                QasmError::ExpectedQubit("c".to_owned()),
+            ),
+            ("qreg q[2]; qreg r[3]; cx q, r;", QasmError::Broadcast(2, 3)),
+            (
+                "gate g a, b { cx a, b; } qreg q[3]; g q[0], q[1], q[2];",
+                QasmError::Arity {
+                    gate: "g".to_owned(),
+                    expected: 2,
+                    found: 3,
+                },
+            ),
+            (
+                "qreg q[2]; cx q[0], q[0];",
+                QasmError::RepeatedQubit("q[0]".to_owned()),
+            ),
+        ];
+        for (source, error) in cases {
+            assert_eq!(Expr::try_from(parse_qasm(source)), Err(error));
+        }
+    }
+}
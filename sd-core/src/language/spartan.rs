@@ -1,6 +1,7 @@
 #![allow(clippy::clone_on_copy)]
 
 use std::{
+    collections::HashSet,
     fmt::{Display, Write},
     str::FromStr,
 };
@@ -16,6 +17,7 @@ use super::{span_into_str, Fresh, OpInfo};
 use crate::{
     common::{Empty, Matchable, Unit},
     hypergraph::traits::{WireType, WithType},
+    refactor::Abstraction,
 };
 
 pub struct Spartan;
@@ -29,6 +31,27 @@ impl super::Language for Spartan {
     type Symbol = Empty;
 }
 
+impl Abstraction for Spartan {
+    fn lambda() -> Option<Op> {
+        Some(Op::Lambda)
+    }
+
+    fn app(_arity: usize) -> Op {
+        Op::App
+    }
+
+    fn fresh_def(used: &HashSet<Variable>) -> Variable {
+        (0..)
+            .map(|number| Variable(format!("x{number}")))
+            .find(|var| !used.contains(var))
+            .unwrap()
+    }
+
+    fn fresh_addr(_used: &HashSet<Unit>) -> Unit {
+        Unit
+    }
+}
+
 pub type Expr = super::Expr<Spartan>;
 pub type Bind = super::Bind<Spartan>;
 pub type Value = super::Value<Spartan>;
@@ -184,7 +207,16 @@ impl<'pest> FromPest<'pest> for Op {
     }
 }
 
-impl OpInfo<Spartan> for Op {}
+impl OpInfo<Spartan> for Op {
+    /// Operations forcing their thunk arguments, such as the branches of `if`,
+    /// have the side effects of those thunks.
+    fn is_pure(&self) -> bool {
+        !matches!(
+            self,
+            Self::App | Self::If | Self::Atom | Self::Deref | Self::Assign
+        )
+    }
+}
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, FromPest)]
 #[cfg_attr(test, derive(Serialize))]
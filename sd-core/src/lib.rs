@@ -2,6 +2,7 @@
 pub mod codeable;
 pub mod common;
 pub mod decompile;
+pub mod discopy;
 pub mod dot;
 pub mod examples;
 pub mod free_vars;
@@ -11,6 +12,9 @@ pub mod interactive;
 pub mod language;
 pub mod lp;
 pub mod monoidal;
+pub mod optimise;
 pub mod prettyprinter;
+pub mod refactor;
 pub mod selection;
+pub mod term;
 pub mod weak_map;
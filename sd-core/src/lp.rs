@@ -26,6 +26,14 @@ pub enum Solver {
     Microlp,
 }
 
+impl Solver {
+    /// Whether the solver can handle integer variables
+    #[must_use]
+    pub const fn supports_integers(self) -> bool {
+        !matches!(self, Self::Clarabel)
+    }
+}
+
 impl LpProblem {
     pub fn add_variable(&mut self, var_def: VariableDefinition) -> Variable {
         self.problem.add(var_def)
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use good_lp::{variable, Expression, Variable};
+use itertools::Itertools;
+
+use super::{permutation::generate_permutation, MonoidalTerm, Slice};
+use crate::{
+    common::{Direction, InOutIter, Link},
+    lp::{LpProblem, Solver},
+};
+
+/// The largest number of variables for which swaps are minimised exactly.
+const EXACT_VARIABLE_LIMIT: usize = 80;
+
+/// The position of an edge in the layer between two slices.
+#[derive(Clone, Copy, Debug)]
+enum Place {
+    /// A free input, which can be reordered but comes before the bound inputs.
+    Free,
+    /// An input or output of the term at a fixed index.
+    Fixed(usize),
+    /// A port of the operation at index `op` in a slice.
+    Port { op: usize, port: usize },
+}
+
+/// Whether one place comes before another.
+enum Order {
+    /// The places can be put in either order.
+    Any,
+    Known(bool),
+    /// Determined by the value of a 0-1 expression.
+    Variable(Expression),
+}
+
+/// Variables `x` where `x[(a, b)]` is 1 when operation `a` comes before operation `b`, for `a < b`.
+type Ordering = HashMap<(usize, usize), Variable>;
+
+fn before(ordering: &Ordering, x: Place, y: Place) -> Order {
+    match (x, y) {
+        (Place::Free, Place::Free) => Order::Any,
+        (Place::Free, _) => Order::Known(true),
+        (_, Place::Free) => Order::Known(false),
+        (Place::Fixed(i), Place::Fixed(j)) => Order::Known(i < j),
+        (Place::Port { op: a, port: i }, Place::Port { op: b, port: j }) => match a.cmp(&b) {
+            std::cmp::Ordering::Equal => Order::Known(i < j),
+            std::cmp::Ordering::Less => Order::Variable(ordering[&(a, b)].into()),
+            std::cmp::Ordering::Greater => Order::Variable(1.0 - ordering[&(b, a)]),
+        },
+        _ => unreachable!("Fixed places and ports never share a layer"),
+    }
+}
+
+/// The inputs or outputs of a slice, with their places.
+fn ports<O: InOutIter>(slice: &Slice<O>, inputs: bool) -> Vec<(Link<O::T>, Place)> {
+    slice
+        .ops
+        .iter()
+        .enumerate()
+        .flat_map(|(op, x)| {
+            let links = if inputs {
+                x.input_links()
+            } else {
+                x.output_links()
+            };
+            links
+                .enumerate()
+                .map(|(port, link)| (link, Place::Port { op, port }))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+impl<O: InOutIter> MonoidalTerm<O::T, O> {
+    /// Reorder the operations on each slice of a monoidal term to minimise the total number of swaps,
+    /// by solving an integer program for the order of each pair of operations in a slice.
+    ///
+    /// Returns `false`, leaving the term unchanged, if `solver` does not support integer variables
+    /// or the term is too large to be solved exactly.
+    pub fn minimise_swaps_exact(&mut self, solver: Solver) -> bool {
+        if !solver.supports_integers() {
+            return false;
+        }
+
+        let mut problem = LpProblem::default();
+        let orderings: Vec<Ordering> = self
+            .slices
+            .iter()
+            .map(|slice| {
+                (0..slice.ops.len())
+                    .tuple_combinations()
+                    .map(|pair| (pair, problem.add_variable(variable().binary())))
+                    .collect()
+            })
+            .collect();
+        let mut variables: usize = orderings.iter().map(HashMap::len).sum();
+        if variables > EXACT_VARIABLE_LIMIT {
+            return false;
+        }
+
+        // The orders must be transitive
+        for (slice, ordering) in self.slices.iter().zip(&orderings) {
+            for (a, b, c) in (0..slice.ops.len()).tuple_combinations() {
+                let cycle = ordering[&(a, b)] + ordering[&(b, c)] - ordering[&(a, c)];
+                problem.add_constraint(cycle.clone().geq(0.0));
+                problem.add_constraint(cycle.leq(1.0));
+            }
+        }
+
+        let inputs: Vec<_> = self
+            .free_inputs
+            .iter()
+            .map(|edge| ((edge.clone(), Direction::Forward), Place::Free))
+            .chain(
+                self.bound_inputs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, edge)| ((edge.clone(), Direction::Forward), Place::Fixed(i))),
+            )
+            .collect();
+        let outputs: Vec<_> = self
+            .free_outputs
+            .iter()
+            .chain(&self.bound_outputs)
+            .enumerate()
+            .map(|(i, edge)| ((edge.clone(), Direction::Forward), Place::Fixed(i)))
+            .collect();
+
+        let unordered = Ordering::new();
+        let layers = self.slices.len() + 1;
+        for layer in 0..layers {
+            let (above, above_ordering) = match layer.checked_sub(1) {
+                None => (inputs.clone(), &unordered),
+                Some(k) => (ports(&self.slices[k], false), &orderings[k]),
+            };
+            let (below, below_ordering) = match self.slices.get(layer) {
+                None => (outputs.clone(), &unordered),
+                Some(slice) => (ports(slice, true), &orderings[layer]),
+            };
+
+            let (above_links, above_places): (Vec<_>, Vec<_>) = above.into_iter().unzip();
+            let (below_links, below_places): (Vec<_>, Vec<_>) = below.into_iter().unzip();
+            let wires: Vec<(Place, Place)> =
+                generate_permutation::<O::T>(above_links.into_iter(), below_links.into_iter())
+                    .into_iter()
+                    .zip(above_places)
+                    .filter_map(|((_, out), place)| {
+                        Option::<usize>::from(out).map(|j| (place, below_places[j]))
+                    })
+                    .collect();
+
+            // Each pair of wires which are in a different order above and below the layer needs a swap
+            for ((x_above, x_below), (y_above, y_below)) in wires.into_iter().tuple_combinations() {
+                match (
+                    before(above_ordering, x_above, y_above),
+                    before(below_ordering, x_below, y_below),
+                ) {
+                    (Order::Any, _) | (_, Order::Any) | (Order::Known(_), Order::Known(_)) => {}
+                    (Order::Known(true), Order::Variable(x))
+                    | (Order::Variable(x), Order::Known(true)) => problem.add_objective(1.0 - x),
+                    (Order::Known(false), Order::Variable(x))
+                    | (Order::Variable(x), Order::Known(false)) => problem.add_objective(x),
+                    (Order::Variable(x), Order::Variable(y)) => {
+                        let swap = problem.add_variable(variable().min(0.0));
+                        variables += 1;
+                        problem.add_constraint((x.clone() - y.clone()).leq(swap));
+                        problem.add_constraint((y - x).leq(swap));
+                        problem.add_objective(swap);
+                    }
+                }
+            }
+        }
+
+        if variables > EXACT_VARIABLE_LIMIT {
+            return false;
+        }
+        let Ok(solution) = problem.minimise(solver) else {
+            return false;
+        };
+
+        for (slice, ordering) in self.slices.iter_mut().zip(&orderings) {
+            let mut positions = vec![0; slice.ops.len()];
+            for (&(a, b), var) in ordering {
+                if solution.value(*var) > 0.5 {
+                    positions[b] += 1;
+                } else {
+                    positions[a] += 1;
+                }
+            }
+            let mut ops: Vec<_> = std::mem::take(&mut slice.ops)
+                .into_iter()
+                .zip(positions)
+                .collect();
+            ops.sort_by_key(|(_, position)| *position);
+            slice.ops = ops.into_iter().map(|(op, _)| op).collect();
+        }
+
+        self.sort_free_inputs();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::{
+        hypergraph::generic::Ctx,
+        lp::Solver,
+        monoidal::{
+            graph::{MonoidalGraph, MonoidalOp},
+            wired_graph::from_graph,
+        },
+        term::Term,
+    };
+
+    /// The number of crossings in the swaps of a monoidal graph.
+    fn crossings<T: Ctx>(graph: &MonoidalGraph<T>) -> usize {
+        graph
+            .slices
+            .iter()
+            .flat_map(|slice| &slice.ops)
+            .map(|op| match op {
+                MonoidalOp::Swap { out_to_in, .. } => out_to_in
+                    .iter()
+                    .tuple_combinations()
+                    .filter(|(x, y)| x > y)
+                    .count(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn minimise_swaps_exact() {
+        let source = "
+x : 1 -> 3
+a : 1 -> 2
+b : 2 -> 1
+c : 1 -> 1
+
+x ; (b ⊗ id) ; swap ; (a ⊗ id) ; (b ⊗ id) ; (c ⊗ id)
+";
+        let graph = Term::parse(source).unwrap().to_graph().unwrap();
+        let heuristic = MonoidalGraph::from(&from_graph(&graph, Solver::Microlp, false));
+        let exact = MonoidalGraph::from(&from_graph(&graph, Solver::Microlp, true));
+        // Clarabel does not support integer variables, so falls back to the heuristic
+        let fallback = MonoidalGraph::from(&from_graph(&graph, Solver::Clarabel, true));
+        assert_eq!(crossings(&heuristic), 2);
+        assert_eq!(crossings(&exact), 1);
+        assert_eq!(crossings(&fallback), 2);
+    }
+}
@@ -9,8 +9,10 @@ use crate::{
     monoidal::permutation::{generate_permutation, PermutationOutput},
 };
 
+mod crossings;
 pub mod graph;
 pub mod permutation;
+pub mod term;
 pub mod wired_graph;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -132,6 +134,25 @@ impl<O: InOutIter + PartialEq + Eq + Hash + Clone + Debug> MonoidalTerm<O::T, O>
             edges_below = slice.input_links();
         }
 
+        drop(edges_below);
+        self.sort_free_inputs();
+    }
+}
+
+impl<O: InOutIter> MonoidalTerm<O::T, O> {
+    /// Order the free inputs of a monoidal term to match the inputs of its first slice
+    fn sort_free_inputs(&mut self) {
+        let edges_below = self.slices.first().map_or_else(
+            || {
+                Box::new(
+                    self.free_outputs
+                        .iter()
+                        .chain(self.bound_outputs.iter())
+                        .map(|edge| (edge.clone(), Direction::Forward)),
+                ) as Box<dyn Iterator<Item = Link<O::T>>>
+            },
+            InOutIter::input_links,
+        );
         let perm_map: HashMap<Link<O::T>, PermutationOutput> = generate_permutation::<O::T>(
             self.free_inputs
                 .iter()
@@ -0,0 +1,191 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use super::graph::{MonoidalGraph, MonoidalOp};
+use crate::{
+    common::InOut,
+    hypergraph::{
+        generic::{Ctx, Operation, Weight},
+        traits::WithWeight,
+    },
+};
+
+/// How an exported term is written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TermFormat {
+    /// Plain text, using the notation of the monoidal term language where it has one.
+    /// Thunks, permutations, backlinks, cups and caps, and operation labels are written
+    /// as they are drawn, so the output cannot in general be read back in.
+    #[default]
+    Plain,
+    /// LaTeX, for use in math mode.
+    Latex,
+}
+
+impl TermFormat {
+    fn compose(self) -> &'static str {
+        match self {
+            Self::Plain => " ; ",
+            Self::Latex => " \\mathbin{;} ",
+        }
+    }
+
+    fn tensor(self) -> &'static str {
+        match self {
+            Self::Plain => " ⊗ ",
+            Self::Latex => " \\otimes ",
+        }
+    }
+
+    /// A structural map such as an identity or swap, with optional parameters.
+    fn structural(self, name: &str, params: &[usize]) -> String {
+        match (self, params) {
+            (Self::Plain, []) => name.to_owned(),
+            (Self::Plain, _) => format!("{name}({})", params.iter().join(", ")),
+            (Self::Latex, []) => format!("\\mathrm{{{name}}}"),
+            (Self::Latex, _) => format!("\\mathrm{{{name}}}_{{{}}}", params.iter().join(",")),
+        }
+    }
+
+    fn operation(self, label: &str) -> String {
+        match self {
+            Self::Plain => label.to_owned(),
+            Self::Latex => format!("\\mathsf{{{}}}", escape_latex(label)),
+        }
+    }
+
+    fn thunk(self, body: &str) -> String {
+        match self {
+            Self::Plain => format!("[{body}]"),
+            Self::Latex => format!("\\left[{body}\\right]"),
+        }
+    }
+
+    fn parenthesise(self, term: &str) -> String {
+        match self {
+            Self::Plain => format!("({term})"),
+            Self::Latex => format!("\\left({term}\\right)"),
+        }
+    }
+}
+
+fn escape_latex(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' => "\\textbackslash{}".to_owned(),
+            '~' => "\\textasciitilde{}".to_owned(),
+            '^' => "\\textasciicircum{}".to_owned(),
+            '_' | '%' | '&' | '#' | '$' | '{' | '}' => format!("\\{c}"),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+impl<T: Ctx> MonoidalGraph<T>
+where
+    Weight<Operation<T>>: Display,
+{
+    /// Write the decomposition of the graph as a composite of its slices,
+    /// each of which is a tensor product of operations, with thunks as nested boxes.
+    /// Free inputs and outputs are taken in the order chosen by the layout.
+    #[must_use]
+    pub fn to_term(&self, format: TermFormat) -> String {
+        let slices: Vec<String> = self
+            .slices
+            .iter()
+            .map(|slice| {
+                let ops = slice_terms(&slice.ops, format);
+                let term = ops.join(format.tensor());
+                if ops.len() > 1 && self.slices.len() > 1 {
+                    format.parenthesise(&term)
+                } else {
+                    term
+                }
+            })
+            .collect();
+        if slices.is_empty() {
+            let wires = self.free_inputs.len() + self.bound_inputs.len();
+            identity(wires, format)
+        } else {
+            slices.join(format.compose())
+        }
+    }
+}
+
+fn identity(wires: usize, format: TermFormat) -> String {
+    if wires == 1 {
+        format.structural("id", &[])
+    } else {
+        format.structural("id", &[wires])
+    }
+}
+
+/// The terms of the operations of a slice, where adjacent identities are collected together.
+fn slice_terms<T: Ctx>(ops: &[MonoidalOp<T>], format: TermFormat) -> Vec<String>
+where
+    Weight<Operation<T>>: Display,
+{
+    ops.iter()
+        .map(|op| match op {
+            MonoidalOp::Copy { copies: 1, .. } => Err(1),
+            op => Ok(op_term(op, format)),
+        })
+        .coalesce(|x, y| match (x, y) {
+            (Err(m), Err(n)) => Ok(Err(m + n)),
+            (x, y) => Err((x, y)),
+        })
+        .map(|term| term.unwrap_or_else(|wires| identity(wires, format)))
+        .collect()
+}
+
+fn op_term<T: Ctx>(op: &MonoidalOp<T>, format: TermFormat) -> String
+where
+    Weight<Operation<T>>: Display,
+{
+    match op {
+        MonoidalOp::Copy { copies: 0, .. } => format.structural("del", &[]),
+        MonoidalOp::Copy { copies: 2, .. } => format.structural("copy", &[]),
+        MonoidalOp::Copy { copies, .. } => format.structural("copy", &[*copies]),
+        MonoidalOp::Operation { addr } => format.operation(&addr.weight().to_string()),
+        MonoidalOp::Thunk { body, .. } => format.thunk(&body.to_term(format)),
+        MonoidalOp::Swap { out_to_in, .. } => {
+            let wires = out_to_in.len();
+            // A swap of the first `m` wires past the rest.
+            let rotation = (1..wires).find(|m| {
+                out_to_in
+                    .iter()
+                    .enumerate()
+                    .all(|(i, input)| *input == (i + m) % wires)
+            });
+            match rotation {
+                Some(1) if wires == 2 => format.structural("swap", &[]),
+                Some(m) => format.structural("swap", &[m, wires - m]),
+                None => format.structural("perm", out_to_in),
+            }
+        }
+        MonoidalOp::Backlink { .. } => format.structural("back", &[]),
+        MonoidalOp::Cup { .. } => match op.number_of_outputs() {
+            0 => format.structural("cup", &[]),
+            wires => format.structural("cup", &[wires]),
+        },
+        MonoidalOp::Cap { .. } => match op.number_of_inputs() {
+            0 => format.structural("cap", &[]),
+            wires => format.structural("cap", &[wires]),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TermFormat;
+    use crate::examples;
+
+    #[test]
+    fn export_term() {
+        for graph in [examples::int(), examples::copy(), examples::thunk()] {
+            insta::assert_snapshot!(graph.to_term(TermFormat::Plain));
+            insta::assert_snapshot!(graph.to_term(TermFormat::Latex));
+        }
+    }
+}
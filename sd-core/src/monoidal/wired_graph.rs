@@ -130,6 +130,8 @@ struct MonoidalWiredGraphBuilder<T: Ctx> {
     backlinks: HashMap<T::Edge, usize>,
     /// Lp solver
     solver: Solver,
+    /// Whether to minimise swaps exactly where possible
+    exact_swaps: bool,
 }
 
 impl<T: Ctx> MonoidalWiredGraphBuilder<T> {
@@ -206,7 +208,7 @@ impl<T: Ctx> MonoidalWiredGraphBuilder<T> {
         let wired_op = match node {
             Node::Operation(op) => WiredOp::Operation { addr: op.clone() },
             Node::Thunk(thunk) => WiredOp::Thunk {
-                body: from_graph(thunk, self.solver),
+                body: from_graph(thunk, self.solver, self.exact_swaps),
                 addr: thunk.clone(),
             },
         };
@@ -249,10 +251,18 @@ impl<T: Ctx> MonoidalWiredGraphBuilder<T> {
     }
 }
 
+/// Lay out a graph as a monoidal term, solving for node layers with `solver`.
+///
+/// If `exact_swaps` is set, the swaps of small graphs are minimised exactly by an integer program
+/// when `solver` supports it; otherwise (and by default) they are reduced heuristically.
 #[allow(clippy::too_many_lines)]
 #[allow(clippy::cast_possible_truncation)]
 #[allow(clippy::cast_sign_loss)]
-pub fn from_graph<G: Graph>(graph: &G, solver: Solver) -> MonoidalWiredGraph<G::Ctx> {
+pub fn from_graph<G: Graph>(
+    graph: &G,
+    solver: Solver,
+    exact_swaps: bool,
+) -> MonoidalWiredGraph<G::Ctx> {
     let mut problem = LpProblem::default();
     let max = problem.add_variable(variable().min(0.5));
     let nodes: IndexMap<Node<G::Ctx>, Variable> = graph
@@ -331,7 +341,11 @@ pub fn from_graph<G: Graph>(graph: &G, solver: Solver) -> MonoidalWiredGraph<G::
 
     let soln = problem.minimise(solver).unwrap();
 
-    let mut builder = MonoidalWiredGraphBuilder::<G::Ctx>::default();
+    let mut builder = MonoidalWiredGraphBuilder::<G::Ctx> {
+        solver,
+        exact_swaps,
+        ..Default::default()
+    };
     let outputs: Vec<Edge<G::Ctx>> = graph.graph_outputs().collect();
 
     for edge in &outputs {
@@ -393,10 +407,13 @@ pub fn from_graph<G: Graph>(graph: &G, solver: Solver) -> MonoidalWiredGraph<G::
         bound_outputs: graph.bound_graph_outputs().collect(),
     };
 
-    // We can minimise swaps, keeping "compound terms" together
-    graph.minimise_swaps(false);
-    for _ in 0..10 {
-        graph.minimise_swaps(true);
+    // We can minimise swaps, keeping "compound terms" together,
+    // exactly for small graphs if asked to and heuristically otherwise
+    if !(exact_swaps && graph.minimise_swaps_exact(solver)) {
+        graph.minimise_swaps(false);
+        for _ in 0..10 {
+            graph.minimise_swaps(true);
+        }
     }
 
     // After this we can flatten the "compound terms"
@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+
+use derivative::Derivative;
+use indexmap::{IndexMap, IndexSet};
+
+use crate::{
+    graph::{Syntax, SyntaxHypergraph},
+    hypergraph::{
+        rewrite::{Result, Rewriter},
+        traits::{EdgeLike, Graph, NodeLike, WithWeight},
+        Edge, EndPoint, Hypergraph, Node, Thunk,
+    },
+    language::{Language, OpInfo},
+};
+
+/// Merge common subexpressions, then prune the nodes which are no longer used.
+///
+/// # Errors
+///
+/// This function will return an error if the optimised hypergraph is malformed.
+pub fn optimise<T: Language>(
+    graph: &SyntaxHypergraph<T>,
+) -> Result<SyntaxHypergraph<T>, Syntax<T>> {
+    prune_dead_nodes(&eliminate_common_subexpressions(graph)?)
+}
+
+/// Merge pure operations with the same weight and the same inputs.
+///
+/// An operation can only be merged into an operation of the same or an enclosing thunk,
+/// so that its outputs remain in scope.
+///
+/// # Errors
+///
+/// This function will return an error if the optimised hypergraph is malformed.
+pub fn eliminate_common_subexpressions<T: Language>(
+    graph: &SyntaxHypergraph<T>,
+) -> Result<SyntaxHypergraph<T>, Syntax<T>> {
+    let mut cse = CommonSubexpressions::default();
+    cse.visit(graph);
+
+    let mut rewriter = Rewriter::new();
+    for (edge, target) in cse.aliases {
+        rewriter.alias(edge, target);
+    }
+    rewriter.skip(cse.duplicates);
+    let builder = rewriter.copy_graph(graph);
+    rewriter.finish(builder)
+}
+
+/// Remove pure operations (and thunks) none of whose outputs are used.
+///
+/// # Errors
+///
+/// This function will return an error if the optimised hypergraph is malformed.
+pub fn prune_dead_nodes<T: Language>(
+    graph: &SyntaxHypergraph<T>,
+) -> Result<SyntaxHypergraph<T>, Syntax<T>> {
+    let mut dead = DeadNodes::default();
+    dead.visit(graph);
+
+    let mut rewriter = Rewriter::new();
+    rewriter.skip(dead.nodes);
+    let builder = rewriter.copy_graph(graph);
+    rewriter.finish(builder)
+}
+
+/// Pure operations identified by their weight and inputs.
+type Expression<T> = (<T as Language>::Op, Vec<Edge<Syntax<T>>>, usize);
+
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+struct CommonSubexpressions<T: Language> {
+    /// Expressions in scope, and the operations computing them
+    scope: IndexMap<Expression<T>, Node<Syntax<T>>>,
+    /// Outputs of duplicate operations, and the edges replacing them
+    aliases: HashMap<Edge<Syntax<T>>, Edge<Syntax<T>>>,
+    /// Operations to be removed
+    duplicates: Vec<Node<Syntax<T>>>,
+}
+
+impl<T: Language> CommonSubexpressions<T> {
+    /// Visit the operations of `graph` before the thunks it contains,
+    /// so that the operations inside a thunk can be merged with any operation around it.
+    fn visit(&mut self, graph: &impl Graph<Ctx = Hypergraph<Syntax<T>>>) {
+        // Nodes are sorted so that uses come first, so visit them in reverse.
+        for op in graph.operations().rev() {
+            let weight = op.weight();
+            if !weight.is_pure() || weight.get_cf().is_some() {
+                continue;
+            }
+            let inputs = op
+                .inputs()
+                .map(|edge| self.aliases.get(&edge).cloned().unwrap_or(edge))
+                .collect();
+            let expression = (weight, inputs, op.number_of_outputs());
+            let node = Node::Operation(op);
+            match self.scope.get(&expression) {
+                Some(representative) => {
+                    for (edge, target) in node.outputs().zip(representative.outputs()) {
+                        self.aliases.insert(edge, target);
+                    }
+                    self.duplicates.push(node);
+                }
+                None => {
+                    self.scope.insert(expression, node);
+                }
+            }
+        }
+
+        for thunk in graph.thunks() {
+            // Expressions depending on the thunk cannot be used inside it,
+            // and expressions inside the thunk are not in scope outside of it.
+            let dependants = dependants(&thunk);
+            let scope = self.scope.clone();
+            self.scope
+                .retain(|_, representative| !dependants.contains(representative));
+            self.visit(&thunk);
+            self.scope = scope;
+        }
+    }
+}
+
+/// Nodes next to `thunk` which depend on its outputs, directly or indirectly.
+fn dependants<T: Language>(thunk: &Thunk<Syntax<T>>) -> IndexSet<Node<Syntax<T>>> {
+    let level = thunk.backlink();
+    let mut dependants = IndexSet::new();
+    let mut frontier = vec![Node::Thunk(thunk.clone())];
+    while let Some(node) = frontier.pop() {
+        for edge in node.outputs() {
+            for target in edge.targets() {
+                // Find the node next to `thunk` containing the target, if any.
+                let mut target = match target {
+                    EndPoint::Node(node) => node,
+                    EndPoint::Boundary(Some(thunk)) => Node::Thunk(thunk),
+                    EndPoint::Boundary(None) => continue,
+                };
+                while target.backlink() != level {
+                    match target.backlink() {
+                        Some(thunk) => target = Node::Thunk(thunk),
+                        None => break,
+                    }
+                }
+                if target.backlink() == level && dependants.insert(target.clone()) {
+                    frontier.push(target);
+                }
+            }
+        }
+    }
+    dependants
+}
+
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+struct DeadNodes<T: Language> {
+    nodes: HashSet<Node<Syntax<T>>>,
+}
+
+impl<T: Language> DeadNodes<T> {
+    /// Visit the nodes so that every use of an edge is visited before the node defining it,
+    /// which is the order in which they are sorted (up to cycles, which are kept alive).
+    fn visit(&mut self, graph: &impl Graph<Ctx = Hypergraph<Syntax<T>>>) {
+        for node in graph.nodes() {
+            let removable = match &node {
+                Node::Operation(op) => {
+                    let weight = op.weight();
+                    weight.is_pure() && weight.get_cf().is_none()
+                }
+                Node::Thunk(thunk) => thunk.number_of_free_graph_outputs() == 0,
+            };
+            if removable && node.outputs().all(|edge| !self.is_used(&edge)) {
+                self.kill(node);
+            } else if let Node::Thunk(thunk) = node {
+                self.visit(&thunk);
+            }
+        }
+    }
+
+    fn is_used(&self, edge: &Edge<Syntax<T>>) -> bool {
+        edge.targets().any(|endpoint| match endpoint {
+            EndPoint::Boundary(None) => true,
+            EndPoint::Boundary(Some(thunk)) => !self.nodes.contains(&Node::Thunk(thunk)),
+            EndPoint::Node(node) => !self.nodes.contains(&node),
+        })
+    }
+
+    /// Mark `node` and everything it contains as dead.
+    fn kill(&mut self, node: Node<Syntax<T>>) {
+        if let Node::Thunk(thunk) = &node {
+            for inner in thunk.nodes() {
+                self.kill(inner);
+            }
+        }
+        self.nodes.insert(node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use from_pest::FromPest;
+    use pest::Parser;
+    use rstest::rstest;
+
+    use super::optimise;
+    use crate::{
+        language::spartan::{Expr, Rule, SpartanParser},
+        prettyprinter::PrettyPrint,
+    };
+
+    #[rstest]
+    #[case("cse", "bind a = plus(x, y) in bind b = plus(x, y) in times(a, b)")]
+    #[case(
+        "cse_chain",
+        "bind a = plus(x, y) in bind b = plus(x, y) in bind c = times(a, 2) in bind d = times(b, 2) in minus(c, d)"
+    )]
+    #[case(
+        "cse_nested",
+        "bind a = plus(x, y) in bind f = lambda(z . bind b = plus(x, y) in times(b, z)) in app(f, a)"
+    )]
+    #[case(
+        "cse_scope",
+        "bind f = lambda(z . bind b = plus(x, z) in b) in bind g = lambda(z . bind c = plus(x, z) in c) in app(f, app(g, 1))"
+    )]
+    #[case("impure", "bind a = deref(r) in bind b = deref(r) in plus(a, b)")]
+    #[case("impure_if", "bind u = if(c, . assign(r, 1), . 0) in 5")]
+    #[case(
+        "impure_if_cse",
+        "bind f = lambda(. assign(r, 1)) in bind a = if(c, f, f) in bind b = if(c, f, f) in plus(a, b)"
+    )]
+    #[case("dead", "bind a = plus(x, y) in bind b = times(a, y) in a")]
+    #[case(
+        "dead_thunk",
+        "bind f = lambda(x . plus(x, 1)) in bind g = lambda(y . app(f, y)) in 1"
+    )]
+    fn optimise_spartan(#[case] name: &str, #[case] source: &str) -> Result<()> {
+        let mut pairs = SpartanParser::parse(Rule::program, source)?;
+        let graph = Expr::from_pest(&mut pairs)?.to_graph(false)?;
+
+        let graph = optimise(&graph)?;
+        insta::assert_snapshot!(
+            format!("optimise_{name}"),
+            Expr::decompile(&graph)?.to_pretty()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+//! Programs of runtime-defined languages are printed in the syntax of spartan.
+
+use pretty::RcDoc;
+
+use super::{paran_list, PrettyPrint};
+use crate::language::dynamic::{Bind, Expr, Thunk, Value};
+
+impl PrettyPrint for Expr {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::concat(self.binds.iter().map(PrettyPrint::to_doc)).append({
+            if self.values.is_empty() {
+                RcDoc::text("()")
+            } else if self.values.len() == 1 {
+                self.values[0].to_doc()
+            } else {
+                paran_list(&self.values)
+            }
+        })
+    }
+}
+
+impl PrettyPrint for Bind {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::text("bind")
+            .append(RcDoc::space())
+            .append(if self.defs.len() == 1 {
+                self.defs[0].to_doc()
+            } else {
+                paran_list(&self.defs)
+            })
+            .append(RcDoc::space())
+            .append(RcDoc::text("="))
+            .append(RcDoc::space())
+            .append(self.value.to_doc())
+            .append(RcDoc::space())
+            .append(RcDoc::text("in"))
+            .append(RcDoc::line())
+    }
+}
+
+impl PrettyPrint for Value {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        match self {
+            Self::Variable(var) => var.to_doc(),
+            Self::Thunk(thunk) => thunk.to_doc(),
+            Self::Op { op, args } => {
+                if args.is_empty() {
+                    op.to_doc()
+                } else {
+                    op.to_doc().append(paran_list(args))
+                }
+            }
+        }
+    }
+}
+
+impl PrettyPrint for Thunk {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::intersperse(self.args.iter().map(PrettyPrint::to_doc), RcDoc::space())
+            .append(RcDoc::space())
+            .append(RcDoc::text("."))
+            .append(if self.body.binds.is_empty() {
+                RcDoc::space().append(self.body.to_doc())
+            } else {
+                RcDoc::line()
+                    .append(self.body.to_doc())
+                    .nest(4)
+                    .append(RcDoc::line())
+            })
+    }
+}
@@ -0,0 +1,131 @@
+use pretty::RcDoc;
+
+use super::{list, paran_list, PrettyPrint};
+use crate::language::llvm::{Bind, Block, BlockAddr, Expr, Op, Thunk, Value, Var};
+
+impl PrettyPrint for Op {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        let mut doc = RcDoc::text(&self.name);
+        if !self.attributes.is_empty() {
+            doc = doc.append(RcDoc::space()).append(&self.attributes);
+        }
+        for block in &self.incoming {
+            doc = doc.append(RcDoc::space()).append(format!("[{block}]"));
+        }
+        if let Some(sym_name) = &self.sym_name {
+            doc = doc.append(RcDoc::space()).append("@").append(sym_name);
+        }
+        doc
+    }
+}
+
+impl PrettyPrint for Var {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        match self {
+            Var::Var { id } => RcDoc::text(id),
+            Var::Symbol(s) => s.to_doc(),
+        }
+    }
+}
+
+impl PrettyPrint for BlockAddr {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::text(&self.0)
+    }
+}
+
+impl PrettyPrint for Expr {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::intersperse(
+            self.binds
+                .iter()
+                .map(PrettyPrint::to_doc)
+                .chain(self.values.iter().map(|value| {
+                    RcDoc::text("ret")
+                        .append(RcDoc::space())
+                        .append(value.to_doc())
+                })),
+            RcDoc::hardline(),
+        )
+    }
+}
+
+impl PrettyPrint for Bind {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        if self.defs.is_empty() {
+            self.value.to_doc()
+        } else {
+            list(&self.defs)
+                .append(RcDoc::space())
+                .append(RcDoc::text("="))
+                .append(RcDoc::space())
+                .append(self.value.to_doc())
+        }
+    }
+}
+
+impl PrettyPrint for Value {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        match self {
+            Self::Variable(var) => var.to_doc(),
+            Self::Thunk(thunk) => thunk.to_doc(),
+            Self::Op { op, args } => {
+                let (thunks, operands): (Vec<_>, Vec<_>) =
+                    args.iter().partition(|arg| matches!(arg, Value::Thunk(_)));
+                let mut doc = op.to_doc();
+                if !operands.is_empty() || !op.successors.is_empty() {
+                    doc = doc.append(RcDoc::space()).append(RcDoc::intersperse(
+                        operands.into_iter().map(PrettyPrint::to_doc).chain(
+                            op.successors
+                                .iter()
+                                .map(|successor| RcDoc::text("label ").append(successor.to_doc())),
+                        ),
+                        RcDoc::text(",").append(RcDoc::space()),
+                    ));
+                }
+                for thunk in thunks {
+                    doc = doc.append(thunk.to_doc());
+                }
+                doc
+            }
+        }
+    }
+}
+
+impl PrettyPrint for Thunk {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        paran_list(&self.args)
+            .append(RcDoc::text(" {"))
+            .append(RcDoc::hardline().append(self.body.to_doc()).nest(2))
+            .append(RcDoc::concat(self.blocks.iter().map(PrettyPrint::to_doc)))
+            .append(RcDoc::hardline())
+            .append(RcDoc::text("}"))
+    }
+}
+
+impl PrettyPrint for Block {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::hardline()
+            .append(RcDoc::hardline())
+            .append(self.addr.0.trim_start_matches('%'))
+            .append(RcDoc::text(":"))
+            .append(RcDoc::hardline().append(self.expr.to_doc()).nest(2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        language::llvm::{
+            internal::tests::{parse_llvm, FACTORIAL},
+            Expr,
+        },
+        prettyprinter::PrettyPrint,
+    };
+
+    #[test]
+    fn pretty_print_llvm() {
+        let expr = Expr::from(parse_llvm(FACTORIAL));
+        insta::assert_snapshot!(expr.to_pretty());
+    }
+}
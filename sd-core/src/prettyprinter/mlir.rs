@@ -1,3 +1,4 @@
+use itertools::Itertools;
 use pretty::RcDoc;
 
 use super::PrettyPrint;
@@ -5,7 +6,23 @@ use crate::language::mlir::{BlockAddr, Expr, Op, Thunk, Var};
 
 impl PrettyPrint for Op {
     fn to_doc(&self) -> RcDoc<'_, ()> {
-        RcDoc::text(&self.name).append(&self.attributes)
+        let mut doc = RcDoc::text(&self.name);
+        if !self.properties.is_empty() {
+            doc = doc
+                .append(RcDoc::space())
+                .append(format!("<{{{}}}>", self.properties.iter().join(", ")));
+        }
+        if !self.attributes.is_empty() {
+            doc = doc
+                .append(RcDoc::space())
+                .append(format!("{{{}}}", self.attributes.iter().join(", ")));
+        }
+        if let Some(location) = &self.location {
+            doc = doc
+                .append(RcDoc::space())
+                .append(format!("loc({location})"));
+        }
+        doc
     }
 }
 
@@ -4,7 +4,10 @@ use itertools::Either;
 use pretty::RcDoc;
 
 pub mod chil;
+pub mod dynamic;
+pub mod llvm;
 pub mod mlir;
+pub mod qasm;
 pub mod spartan;
 
 pub trait PrettyPrint {
@@ -0,0 +1,141 @@
+use pretty::RcDoc;
+
+use super::PrettyPrint;
+use crate::language::qasm::{Addr, Bind, Expr, Op, OpKind, Thunk, Value, Var};
+
+impl PrettyPrint for Op {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::text(self.to_string())
+    }
+}
+
+impl PrettyPrint for Var {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::text(self.to_string())
+    }
+}
+
+impl PrettyPrint for Addr {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::text(self.to_string())
+    }
+}
+
+/// Arguments of a statement, separated by commas.
+fn arguments<'a>(vars: impl IntoIterator<Item = &'a Var>) -> RcDoc<'a, ()> {
+    RcDoc::intersperse(
+        vars.into_iter().map(PrettyPrint::to_doc),
+        RcDoc::text(",").append(RcDoc::space()),
+    )
+}
+
+impl PrettyPrint for Expr {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        // The values of a circuit are the final states of its wires, which are implicit in QASM.
+        RcDoc::intersperse(
+            self.binds.iter().map(PrettyPrint::to_doc),
+            RcDoc::hardline(),
+        )
+    }
+}
+
+impl PrettyPrint for Bind {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        match &self.value {
+            Value::Op { op, args } => {
+                let qubits: Vec<_> = args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        Value::Variable(var @ Var::Qubit(_)) => Some(var),
+                        _ => None,
+                    })
+                    .collect();
+                let statement = match &op.kind {
+                    OpKind::Register {
+                        name,
+                        size,
+                        classical,
+                    } => match size {
+                        Some(size) => RcDoc::text(if *classical { "creg " } else { "qreg " })
+                            .append(name)
+                            .append(format!("[{size}]")),
+                        None => {
+                            RcDoc::text(if *classical { "bit " } else { "qubit " }).append(name)
+                        }
+                    },
+                    OpKind::Measure => {
+                        let mut doc = RcDoc::text("measure ").append(arguments(qubits));
+                        if let Some(bit) = self.defs.iter().find(|def| matches!(def, Var::Bit(_))) {
+                            doc = doc.append(" -> ").append(bit.to_doc());
+                        }
+                        doc
+                    }
+                    OpKind::Gate { .. } | OpKind::Reset | OpKind::Barrier => {
+                        op.to_doc().append(RcDoc::space()).append(arguments(qubits))
+                    }
+                };
+                let statement = statement.append(";");
+                match &op.condition {
+                    Some(condition) => RcDoc::text("if (")
+                        .append(condition)
+                        .append(")")
+                        .append(RcDoc::space())
+                        .append(statement),
+                    None => statement,
+                }
+            }
+            Value::Thunk(thunk) => thunk.to_doc(),
+            Value::Variable(var) => arguments(&self.defs)
+                .append(" = ")
+                .append(var.to_doc())
+                .append(";"),
+        }
+    }
+}
+
+impl PrettyPrint for Value {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        match self {
+            Self::Variable(var) => var.to_doc(),
+            Self::Thunk(thunk) => thunk.to_doc(),
+            Self::Op { op, args } => op
+                .to_doc()
+                .append(RcDoc::space())
+                .append(RcDoc::intersperse(
+                    args.iter().map(PrettyPrint::to_doc),
+                    RcDoc::text(",").append(RcDoc::space()),
+                )),
+        }
+    }
+}
+
+impl PrettyPrint for Thunk {
+    fn to_doc(&self) -> RcDoc<'_, ()> {
+        RcDoc::text("gate ")
+            .append(self.addr.to_doc())
+            .append(RcDoc::space())
+            .append(arguments(&self.args))
+            .append(RcDoc::text(" {"))
+            .append(RcDoc::hardline().append(self.body.to_doc()).nest(2))
+            .append(RcDoc::hardline())
+            .append(RcDoc::text("}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        language::qasm::{
+            tests::{parse_qasm, TELEPORT},
+            Expr,
+        },
+        prettyprinter::PrettyPrint,
+    };
+
+    #[test]
+    fn pretty_print_qasm() -> anyhow::Result<()> {
+        let expr = Expr::try_from(parse_qasm(TELEPORT))?;
+        insta::assert_snapshot!(expr.to_pretty());
+        Ok(())
+    }
+}
@@ -0,0 +1,692 @@
+use std::collections::HashSet;
+
+use derivative::Derivative;
+use either::Either::Left;
+use indexmap::IndexSet;
+use itertools::Itertools;
+use thiserror::Error;
+
+use crate::{
+    graph::{Name, Syntax, SyntaxHypergraph},
+    hypergraph::{
+        builder::{fragment::Fragment, HypergraphError},
+        rewrite::Rewriter,
+        subgraph::Subgraph,
+        traits::{EdgeLike, Graph, NodeLike, WithWeight},
+        Edge, EndPoint, Hypergraph, Node, Operation, Thunk,
+    },
+    language::{GetVar, Language},
+};
+
+/// Languages with first-class functions, into which parts of a program can be abstracted.
+pub trait Abstraction: Language {
+    /// Operation turning a thunk into a function value, if the language needs one.
+    fn lambda() -> Option<Self::Op>;
+
+    /// Operation applying a function value to `arity` arguments.
+    fn app(arity: usize) -> Self::Op;
+
+    /// A variable definition which does not define any of the `used` variables.
+    fn fresh_def(used: &HashSet<Self::Var>) -> Self::VarDef;
+
+    /// A thunk address which is not `used`, if the language distinguishes thunk addresses.
+    fn fresh_addr(used: &HashSet<Self::Addr>) -> Self::Addr;
+}
+
+#[derive(Derivative, Error)]
+#[derivative(Debug(bound = ""))]
+pub enum RefactorError<T: Language> {
+    #[error("Nothing is selected")]
+    EmptySelection,
+    #[error("Selection has no outputs")]
+    NoOutputs,
+    #[error("Selection spans several thunks")]
+    MixedScopes,
+    #[error("Thunk is not directly applied")]
+    NotApplied,
+    #[error("Thunk expects {expected} arguments but is applied to {actual}")]
+    ArityMismatch { expected: usize, actual: usize },
+    #[error("Inlining would capture variable {0}")]
+    Capture(T::Var),
+    #[error("Error constructing hypergraph: {0}")]
+    HypergraphError(#[from] HypergraphError<Syntax<T>>),
+}
+
+/// Names already in use in a graph, used to generate fresh ones.
+struct Names<T: Language> {
+    vars: HashSet<T::Var>,
+    addrs: HashSet<T::Addr>,
+}
+
+impl<T: Abstraction> Names<T> {
+    fn new(graph: &SyntaxHypergraph<T>) -> Self {
+        fn helper<T: Language>(
+            names: &mut Names<T>,
+            graph: &impl Graph<Ctx = Hypergraph<Syntax<T>>>,
+        ) {
+            for edge in graph.graph_inputs() {
+                names.vars.extend(edge.weight().into_var());
+            }
+            for node in graph.nodes() {
+                for edge in node.outputs() {
+                    names.vars.extend(edge.weight().into_var());
+                }
+                if let Node::Thunk(thunk) = node {
+                    if let Left(addr) = thunk.weight() {
+                        names.addrs.insert(addr);
+                    }
+                    helper(names, &thunk);
+                }
+            }
+        }
+
+        let mut names = Self {
+            vars: HashSet::new(),
+            addrs: HashSet::new(),
+        };
+        helper(&mut names, graph);
+        names
+    }
+
+    fn fresh_def(&mut self) -> T::VarDef {
+        let def = T::fresh_def(&self.vars);
+        self.vars.insert(def.var().clone());
+        def
+    }
+
+    fn fresh_addr(&mut self) -> T::Addr {
+        let addr = T::fresh_addr(&self.addrs);
+        self.addrs.insert(addr.clone());
+        addr
+    }
+}
+
+/// Replace the nodes of `subgraph` in `graph` by the application of a new function.
+///
+/// The function is a thunk containing the selected nodes, which binds the free inputs
+/// of the selection and returns its outputs.
+///
+/// # Errors
+///
+/// This function will return an error if the selection is empty, has no outputs,
+/// does not lie within a single thunk, or the resulting hypergraph is malformed.
+pub fn extract<T>(
+    graph: &SyntaxHypergraph<T>,
+    subgraph: &Subgraph<Hypergraph<Syntax<T>>>,
+) -> Result<SyntaxHypergraph<T>, RefactorError<T>>
+where
+    T: Abstraction,
+    T::VarDef: From<T::Var>,
+{
+    let roots: Vec<Node<Syntax<T>>> = subgraph.selection.roots().collect();
+    let containing = roots
+        .first()
+        .ok_or(RefactorError::EmptySelection)?
+        .backlink();
+    if roots.iter().any(|root| root.backlink() != containing) {
+        return Err(RefactorError::MixedScopes);
+    }
+
+    let inputs: Vec<Edge<Syntax<T>>> = subgraph
+        .free_graph_inputs()
+        .map(|edge| edge.into_inner())
+        .collect();
+    let outputs: IndexSet<Edge<Syntax<T>>> = subgraph
+        .bound_graph_outputs()
+        .map(|edge| edge.into_inner())
+        .collect();
+    if outputs.is_empty() {
+        return Err(RefactorError::NoOutputs);
+    }
+
+    let mut names = Names::new(graph);
+
+    let bound_inputs: Vec<Name<T>> = inputs
+        .iter()
+        .map(|edge| match edge.weight() {
+            Name::BoundVar(def) => Name::BoundVar(def),
+            Name::FreeVar(var) => Name::BoundVar(var.into()),
+            Name::Nil | Name::CF(_) => Name::BoundVar(names.fresh_def()),
+        })
+        .collect();
+    let output_weights: Vec<Name<T>> = outputs
+        .iter()
+        .map(|edge| match edge.weight() {
+            Name::Nil if outputs.len() > 1 => Name::BoundVar(names.fresh_def()),
+            name => name,
+        })
+        .collect();
+    let extraction = Extraction {
+        roots,
+        inputs,
+        outputs,
+        bound_inputs,
+        output_weights,
+        addr: names.fresh_addr(),
+    };
+
+    let mut rewriter = Rewriter::new();
+    rewriter.skip(extraction.roots.iter().cloned());
+    let mut builder = rewriter.copy_graph(graph);
+
+    match containing {
+        None => extraction.insert(&mut rewriter, &mut builder)?,
+        Some(thunk) => {
+            let thunk = rewriter
+                .thunk(&thunk)
+                .cloned()
+                .expect("containing thunk should have been copied");
+            builder.in_thunk(thunk, |mut cursor| {
+                extraction.insert(&mut rewriter, &mut cursor)
+            })?;
+        }
+    }
+
+    Ok(rewriter.finish(builder)?)
+}
+
+/// Whether `thunk` is applied directly to the right number of arguments, so that it can be inlined.
+#[must_use]
+pub fn can_inline<T: Abstraction>(thunk: &Thunk<Syntax<T>>) -> bool {
+    Application::find(thunk).is_ok()
+}
+
+/// Replace an application of `thunk` by a copy of its body (beta reduction).
+///
+/// The thunk must be applied directly, possibly after being turned into a function value.
+/// Definitions in the body are renamed if they clash with names used outside of it.
+/// The thunk itself is removed if the application was its only use.
+///
+/// # Errors
+///
+/// This function will return an error if the thunk is not applied, is applied to the wrong
+/// number of arguments, inlining it would capture a variable, or the resulting hypergraph is malformed.
+pub fn inline<T>(
+    graph: &SyntaxHypergraph<T>,
+    thunk: &Thunk<Syntax<T>>,
+) -> Result<SyntaxHypergraph<T>, RefactorError<T>>
+where
+    T: Abstraction,
+{
+    let Application {
+        function,
+        lambda,
+        value,
+        app,
+    } = Application::find(thunk)?;
+
+    let mut definitions = Vec::new();
+    collect_definitions(graph, thunk, &mut definitions);
+    for edge in thunk.free_graph_inputs() {
+        if let Some(var) = edge.weight().into_var() {
+            if definitions.iter().filter(|def| **def == var).count() > 1 {
+                return Err(RefactorError::Capture(var));
+            }
+        }
+    }
+
+    let mut rewriter = Rewriter::new();
+    let mut names = Names::new(graph);
+    let outside: HashSet<T::Var> = definitions.into_iter().collect();
+    rename_definitions(&mut rewriter, &mut names, &outside, thunk);
+
+    rewriter.skip([Node::Operation(app.clone())]);
+    if value.targets().count() == 1 {
+        if let Some(lambda) = lambda {
+            rewriter.skip([Node::Operation(lambda)]);
+        }
+        if function.targets().count() == 1 {
+            rewriter.skip([Node::Thunk(thunk.clone())]);
+        }
+    }
+    let mut builder = rewriter.copy_graph(graph);
+
+    let reduction = Reduction {
+        thunk: thunk.clone(),
+        app: app.clone(),
+    };
+    match app.backlink() {
+        None => reduction.insert(&mut rewriter, &mut builder)?,
+        Some(containing) => {
+            let containing = rewriter
+                .thunk(&containing)
+                .cloned()
+                .expect("containing thunk should have been copied");
+            builder.in_thunk(containing, |mut cursor| {
+                reduction.insert(&mut rewriter, &mut cursor)
+            })?;
+        }
+    }
+
+    Ok(rewriter.finish(builder)?)
+}
+
+/// The direct application of a thunk.
+struct Application<T: Language> {
+    /// The output of the thunk.
+    function: Edge<Syntax<T>>,
+    /// The operation turning the thunk into a function value, if the language needs one.
+    lambda: Option<Operation<Syntax<T>>>,
+    /// The function value which is applied.
+    value: Edge<Syntax<T>>,
+    app: Operation<Syntax<T>>,
+}
+
+impl<T: Abstraction> Application<T> {
+    fn find(thunk: &Thunk<Syntax<T>>) -> Result<Self, RefactorError<T>> {
+        let function = thunk
+            .outputs()
+            .skip(thunk.number_of_free_graph_outputs())
+            .exactly_one()
+            .map_err(|_| RefactorError::NotApplied)?;
+        let lambda = match T::lambda() {
+            None => None,
+            Some(lambda) => Some(
+                uses(&function)
+                    .find(|op| op.weight() == lambda)
+                    .ok_or(RefactorError::NotApplied)?,
+            ),
+        };
+        let value = lambda
+            .as_ref()
+            .map_or_else(|| function.clone(), |op| op.outputs().next().unwrap());
+        let app = uses(&value)
+            .find(|op| {
+                op.inputs().next().as_ref() == Some(&value)
+                    && op.weight() == T::app(op.number_of_inputs() - 1)
+            })
+            .ok_or(RefactorError::NotApplied)?;
+
+        let expected = thunk.number_of_bound_graph_inputs();
+        let actual = app.number_of_inputs() - 1;
+        if expected != actual || thunk.number_of_bound_graph_outputs() != app.number_of_outputs() {
+            return Err(RefactorError::ArityMismatch { expected, actual });
+        }
+
+        Ok(Self {
+            function,
+            lambda,
+            value,
+            app,
+        })
+    }
+}
+
+/// Operations using `edge`.
+fn uses<T: Language>(edge: &Edge<Syntax<T>>) -> impl Iterator<Item = Operation<Syntax<T>>> + '_ {
+    edge.targets().filter_map(|endpoint| match endpoint {
+        EndPoint::Node(Node::Operation(op)) => Some(op),
+        _ => None,
+    })
+}
+
+/// Variables defined in `graph`, excluding those defined inside `except`.
+fn collect_definitions<T: Language>(
+    graph: &impl Graph<Ctx = Hypergraph<Syntax<T>>>,
+    except: &Thunk<Syntax<T>>,
+    definitions: &mut Vec<T::Var>,
+) {
+    for edge in graph
+        .graph_backlink()
+        .map_or_else(|| graph.graph_inputs(), |_| graph.bound_graph_inputs())
+    {
+        definitions.extend(edge.weight().into_var());
+    }
+    for node in graph.nodes() {
+        for edge in node.outputs() {
+            definitions.extend(edge.weight().into_var());
+        }
+        if let Node::Thunk(thunk) = node {
+            if thunk != *except {
+                collect_definitions(&thunk, except, definitions);
+            }
+        }
+    }
+}
+
+/// Rename definitions inside `graph` which clash with the `outside` variables.
+fn rename_definitions<T: Abstraction>(
+    rewriter: &mut Rewriter<Syntax<T>>,
+    names: &mut Names<T>,
+    outside: &HashSet<T::Var>,
+    graph: &impl Graph<Ctx = Hypergraph<Syntax<T>>>,
+) {
+    for node in graph.nodes() {
+        let edges: Vec<_> = match &node {
+            Node::Operation(op) => op.outputs().collect(),
+            Node::Thunk(thunk) => thunk.bound_graph_inputs().collect(),
+        };
+        for edge in edges {
+            if let Name::BoundVar(def) = edge.weight() {
+                if outside.contains(def.var()) {
+                    rewriter.set_weight(edge, Name::BoundVar(names.fresh_def()));
+                }
+            }
+        }
+        if let Node::Thunk(thunk) = node {
+            rename_definitions(rewriter, names, outside, &thunk);
+        }
+    }
+}
+
+/// An application of a thunk which is being reduced.
+struct Reduction<T: Language> {
+    thunk: Thunk<Syntax<T>>,
+    app: Operation<Syntax<T>>,
+}
+
+impl<T: Language> Reduction<T> {
+    /// Add a copy of the body of the thunk to `fragment`, in place of the application.
+    fn insert<F>(
+        self,
+        rewriter: &mut Rewriter<Syntax<T>>,
+        fragment: &mut F,
+    ) -> Result<(), HypergraphError<Syntax<T>>>
+    where
+        F: Fragment<Weight = Syntax<T>>,
+    {
+        let arguments: Vec<_> = self
+            .app
+            .inputs()
+            .skip(1)
+            .map(|edge| {
+                rewriter
+                    .out_port(&edge)
+                    .cloned()
+                    .expect("arguments should have been copied")
+            })
+            .collect();
+
+        let results = rewriter.scoped(
+            fragment,
+            self.thunk.bound_graph_inputs().zip(arguments),
+            |rewriter, fragment| {
+                rewriter.copy_nodes(fragment, self.thunk.nodes());
+                self.thunk
+                    .bound_graph_outputs()
+                    .map(|edge| {
+                        rewriter
+                            .out_port(&edge)
+                            .cloned()
+                            .expect("results should have been copied")
+                    })
+                    .collect::<Vec<_>>()
+            },
+        )?;
+
+        for (edge, out_port) in self.app.outputs().zip(results) {
+            rewriter.set_out_port(edge, out_port);
+        }
+
+        Ok(())
+    }
+}
+
+/// A selection which is being abstracted into a function.
+struct Extraction<T: Language> {
+    /// The selected nodes
+    roots: Vec<Node<Syntax<T>>>,
+    /// Edges entering the selection
+    inputs: Vec<Edge<Syntax<T>>>,
+    /// Edges leaving the selection
+    outputs: IndexSet<Edge<Syntax<T>>>,
+    /// Names of the arguments of the function
+    bound_inputs: Vec<Name<T>>,
+    /// Names of the results of the application
+    output_weights: Vec<Name<T>>,
+    /// Address of the function
+    addr: T::Addr,
+}
+
+impl<T: Abstraction> Extraction<T> {
+    /// Add a thunk containing the selection to `fragment`, together with its application to the inputs.
+    fn insert<F>(
+        self,
+        rewriter: &mut Rewriter<Syntax<T>>,
+        fragment: &mut F,
+    ) -> Result<(), HypergraphError<Syntax<T>>>
+    where
+        F: Fragment<Weight = Syntax<T>>,
+    {
+        let thunk = fragment.add_thunk(
+            0,
+            self.bound_inputs,
+            self.outputs.len(),
+            vec![Name::Nil],
+            Left(self.addr),
+        );
+
+        fragment.in_thunk(thunk.clone(), |mut cursor| {
+            rewriter.scoped(
+                &mut cursor,
+                self.inputs.iter().cloned().zip(thunk.bound_inputs()),
+                |rewriter, cursor| {
+                    for node in &self.roots {
+                        rewriter.copy_node(cursor, node);
+                    }
+                    for (edge, in_port) in self.outputs.iter().zip(thunk.graph_outputs()) {
+                        rewriter.link(in_port, edge.clone());
+                    }
+                },
+            )
+        })?;
+
+        let mut function = thunk.outputs().next().unwrap();
+        if let Some(lambda) = T::lambda() {
+            let op = fragment.add_operation(1, vec![Name::Nil], lambda);
+            fragment.link(function, op.inputs().next().unwrap())?;
+            function = op.outputs().next().unwrap();
+        }
+
+        let arity = self.inputs.len();
+        let app = fragment.add_operation(arity + 1, self.output_weights, T::app(arity));
+        let mut app_inputs = app.inputs();
+        fragment.link(function, app_inputs.next().unwrap())?;
+        for (edge, in_port) in self.inputs.into_iter().zip(app_inputs) {
+            rewriter.link(in_port, edge);
+        }
+        for (edge, out_port) in self.outputs.into_iter().zip(app.outputs()) {
+            rewriter.set_out_port(edge, out_port);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use anyhow::Result;
+    use from_pest::FromPest;
+    use pest::Parser;
+    use rstest::rstest;
+
+    use super::{can_inline, extract, inline, RefactorError};
+    use crate::{
+        common::Matchable,
+        graph::{Syntax, SyntaxHypergraph},
+        hypergraph::{subgraph::Subgraph, traits::Graph, Node, Thunk},
+        language::{
+            chil::{self, ChilParser},
+            spartan::{Expr, Rule, Spartan, SpartanParser},
+        },
+        prettyprinter::PrettyPrint,
+        selection::SelectionMap,
+    };
+
+    fn parse(source: &str) -> Expr {
+        let mut pairs = SpartanParser::parse(Rule::program, source).unwrap();
+        Expr::from_pest(&mut pairs).unwrap()
+    }
+
+    #[rstest]
+    #[case("whole", "bind a = plus(x, y) in bind b = times(a, z) in b", &["plus", "times"])]
+    #[case("partial", "bind a = plus(x, y) in bind b = times(a, z) in b", &["plus"])]
+    #[case("anonymous", "times(plus(x, y), minus(x, y))", &["plus", "minus"])]
+    #[case("nested", "bind f = lambda(x . bind a = plus(x, y) in a) in app(f, 1)", &["plus"])]
+    fn extract_spartan(
+        #[case] name: &str,
+        #[case] source: &str,
+        #[case] selected: &[&str],
+    ) -> Result<()> {
+        fn select(
+            selection: &mut SelectionMap<SyntaxHypergraph<Spartan>>,
+            graph: &impl Graph<Ctx = SyntaxHypergraph<Spartan>>,
+            selected: &[&str],
+        ) {
+            for node in graph.nodes() {
+                match &node {
+                    Node::Operation(op) => {
+                        if selected.iter().any(|query| op.is_match(query)) {
+                            selection[&node] = true;
+                        }
+                    }
+                    Node::Thunk(thunk) => select(selection, thunk, selected),
+                }
+            }
+        }
+
+        let graph = parse(source).to_graph(false)?;
+        let mut selection = SelectionMap::new(&graph);
+        select(&mut selection, &graph, selected);
+
+        let graph = extract(&graph, &Subgraph::new(selection))?;
+        insta::assert_snapshot!(
+            format!("extract_{name}"),
+            Expr::decompile(&graph)?.to_pretty()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn extract_mixed_scopes() -> Result<()> {
+        let graph = parse("bind f = lambda(x . plus(x, 1)) in bind a = times(y, 2) in app(f, a)")
+            .to_graph(false)?;
+        let mut selection = SelectionMap::new(&graph);
+        for node in graph.nodes() {
+            match &node {
+                Node::Operation(op) if op.is_match("times") => selection[&node] = true,
+                Node::Thunk(thunk) => {
+                    for node in thunk.nodes() {
+                        if matches!(&node, Node::Operation(op) if op.is_match("plus")) {
+                            selection[&node] = true;
+                        }
+                    }
+                }
+                Node::Operation(_) => {}
+            }
+        }
+
+        // Normalising the selection would select the whole thunk instead
+        let subgraph = Subgraph {
+            selection: Arc::new(selection),
+        };
+        assert!(matches!(
+            extract(&graph, &subgraph),
+            Err(RefactorError::MixedScopes)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn extract_chil() -> Result<()> {
+        fn parse_chil(source: &str) -> Result<chil::Expr> {
+            let mut pairs = ChilParser::parse(chil::Rule::program, source)?;
+            Ok(chil::Expr::from_pest(&mut pairs)?)
+        }
+
+        let graph =
+            parse_chil("def %0 = +(%10, %11)\ndef %1 = *(%0, %11)\noutput %1")?.to_graph(false)?;
+        let mut selection = SelectionMap::new(&graph);
+        for node in graph.nodes() {
+            selection[&node] = true;
+        }
+
+        let graph = extract(&graph, &Subgraph::new(selection))?;
+        let source = chil::Expr::decompile(&graph)?.to_pretty();
+        insta::assert_snapshot!(source);
+        assert!(source.contains("func("));
+        assert!(source.contains("apply/2("));
+
+        // The extracted program reads back in as the same program
+        let reparsed = parse_chil(&source)?.to_graph(false)?;
+        assert_eq!(chil::Expr::decompile(&reparsed)?.to_pretty(), source);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("simple", "bind f = lambda(x . plus(x, 1)) in app(f, 2)")]
+    #[case(
+        "shared",
+        "bind f = lambda(x . plus(x, 1)) in bind a = app(f, 2) in app(f, a)"
+    )]
+    #[case(
+        "hygiene",
+        "bind f = lambda(x . bind a = plus(x, 1) in a) in bind a = 5 in bind b = app(f, a) in times(a, b)"
+    )]
+    #[case(
+        "nested",
+        "bind f = lambda(x . bind g = lambda(y . plus(x, y)) in app(g, 1)) in f"
+    )]
+    fn inline_spartan(#[case] name: &str, #[case] source: &str) -> Result<()> {
+        fn find_thunk(
+            graph: &impl Graph<Ctx = SyntaxHypergraph<Spartan>>,
+        ) -> Option<Thunk<Syntax<Spartan>>> {
+            graph.nodes().find_map(|node| match node {
+                Node::Operation(_) => None,
+                Node::Thunk(thunk) => {
+                    if thunk.nodes().any(|node| matches!(node, Node::Thunk(_))) {
+                        find_thunk(&thunk)
+                    } else {
+                        Some(thunk)
+                    }
+                }
+            })
+        }
+
+        let graph = parse(source).to_graph(false)?;
+        let thunk = find_thunk(&graph).unwrap();
+
+        let graph = inline(&graph, &thunk)?;
+        insta::assert_snapshot!(
+            format!("inline_{name}"),
+            Expr::decompile(&graph)?.to_pretty()
+        );
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("bind f = lambda(x . plus(x, 1)) in app(f, 2)", true)]
+    #[case("bind f = lambda(x . plus(x, 1)) in f", false)]
+    #[case("bind f = lambda(x . plus(x, 1)) in app(f, 2, 3)", false)]
+    fn can_inline_spartan(#[case] source: &str, #[case] expected: bool) -> Result<()> {
+        let graph = parse(source).to_graph(false)?;
+        let thunk = graph.thunks().next().unwrap();
+        assert_eq!(can_inline(&thunk), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn inline_capture() -> Result<()> {
+        let graph = parse(
+            "bind y = 1 in bind f = lambda(x . plus(x, y)) in bind g = lambda(y . app(f, y)) in g",
+        )
+        .to_graph(false)?;
+        let thunk = graph
+            .nodes()
+            .find_map(|node| match node {
+                Node::Thunk(thunk) if thunk.operations().any(|op| op.is_match("plus")) => {
+                    Some(thunk)
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        assert!(matches!(
+            inline(&graph, &thunk),
+            Err(RefactorError::Capture(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,419 @@
+//! An input language of algebraic monoidal terms, such as `(f ⊗ id) ; swap ; (g ⊗ h)`.
+//!
+//! Boxes are declared with their arities before the term, as in `f : 1 -> 2`.
+//! Composition is written `;` or `>>` and the tensor product `⊗` or `@`.
+
+use std::collections::HashMap;
+
+use pest::{
+    error::{Error as PestError, ErrorVariant, LineColLocation},
+    iterators::Pair,
+    Parser, Span,
+};
+use pest_derive::Parser;
+use thiserror::Error;
+
+use crate::{
+    codeable::Codeable,
+    dot::Label,
+    hypergraph::{
+        builder::{Fragment, HypergraphBuilder, HypergraphError, OutPort},
+        diff::Similarity,
+        isomorphism::Equivalence,
+        traits::WithWeight,
+        Edge, Hypergraph, Operation, Thunk, Weight,
+    },
+};
+
+#[derive(Parser)]
+#[grammar = "language/term.pest"]
+pub struct TermParser;
+
+impl Codeable for Operation<TermWeight> {
+    type Code = Label;
+
+    fn code(&self) -> Self::Code {
+        self.weight()
+    }
+}
+
+impl Codeable for Thunk<TermWeight> {
+    type Code = Label;
+
+    fn code(&self) -> Self::Code {
+        self.weight()
+    }
+}
+
+impl Codeable for Edge<TermWeight> {
+    type Code = Label;
+
+    fn code(&self) -> Self::Code {
+        self.weight()
+    }
+}
+
+pub struct TermWeight;
+
+impl Weight for TermWeight {
+    type EdgeWeight = Label;
+
+    type OperationWeight = Label;
+
+    type ThunkWeight = Label;
+}
+
+impl Equivalence for TermWeight {
+    fn equivalent_edges(x: &Label, y: &Label) -> bool {
+        x.0 == y.0
+    }
+
+    fn equivalent_operations(x: &Label, y: &Label) -> bool {
+        x.0 == y.0
+    }
+
+    fn equivalent_thunks(x: &Label, y: &Label) -> bool {
+        x.0 == y.0
+    }
+}
+
+impl Similarity for TermWeight {
+    fn operation_key(weight: &Label) -> String {
+        weight.0.clone()
+    }
+
+    fn thunk_key(weight: &Label) -> String {
+        weight.0.clone()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum TermError {
+    #[error("Parsing error:\n{message}")]
+    Parse {
+        message: String,
+        line_col: LineColLocation,
+    },
+    #[error("{message}\n{location}")]
+    Invalid {
+        message: String,
+        /// The offending part of the term, rendered with its location.
+        location: String,
+        line_col: LineColLocation,
+    },
+    #[error("Hypergraph error: {0:?}")]
+    HypergraphError(#[from] HypergraphError<TermWeight>),
+}
+
+impl TermError {
+    fn invalid(span: Span, message: String) -> Self {
+        let error = PestError::<Rule>::new_from_span(
+            ErrorVariant::CustomError {
+                message: message.clone(),
+            },
+            span,
+        );
+        Self::Invalid {
+            message,
+            location: error.to_string(),
+            line_col: error.line_col,
+        }
+    }
+
+    /// Where in the source the error occurred, if known.
+    #[must_use]
+    pub fn line_col(&self) -> Option<&LineColLocation> {
+        match self {
+            Self::Parse { line_col, .. } | Self::Invalid { line_col, .. } => Some(line_col),
+            Self::HypergraphError(_) => None,
+        }
+    }
+}
+
+/// A monoidal term whose arities have been checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Term {
+    Id(usize),
+    /// Swaps a block of `m` wires with the following block of `n` wires.
+    Swap(usize, usize),
+    /// Copies one wire into `n` wires.
+    Copy(usize),
+    Delete,
+    Box {
+        name: String,
+        inputs: usize,
+        outputs: usize,
+    },
+    Compose(Vec<Term>),
+    Tensor(Vec<Term>),
+}
+
+impl Term {
+    /// The number of inputs and outputs of the term.
+    #[must_use]
+    pub fn arity(&self) -> (usize, usize) {
+        match self {
+            Self::Id(n) => (*n, *n),
+            Self::Swap(m, n) => (m + n, m + n),
+            Self::Copy(n) => (1, *n),
+            Self::Delete => (1, 0),
+            Self::Box {
+                inputs, outputs, ..
+            } => (*inputs, *outputs),
+            Self::Compose(terms) => (
+                terms.first().map_or(0, |term| term.arity().0),
+                terms.last().map_or(0, |term| term.arity().1),
+            ),
+            Self::Tensor(terms) => terms
+                .iter()
+                .map(Self::arity)
+                .fold((0, 0), |acc, arity| (acc.0 + arity.0, acc.1 + arity.1)),
+        }
+    }
+
+    /// Parse a term and check that its arities line up.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the source cannot be parsed,
+    /// a box is undeclared or declared twice, or a composition has mismatched arities.
+    pub fn parse(source: &str) -> Result<Self, TermError> {
+        let pairs = TermParser::parse(Rule::program, source).map_err(|err| TermError::Parse {
+            message: err.to_string(),
+            line_col: err.line_col,
+        })?;
+
+        let mut boxes = HashMap::new();
+        let mut term = None;
+        for pair in pairs {
+            match pair.as_rule() {
+                Rule::declaration => {
+                    let span = pair.as_span();
+                    let mut inner = pair.into_inner();
+                    let name = inner.next().unwrap().as_str().to_owned();
+                    let inputs = number(inner.next().unwrap())?;
+                    let outputs = number(inner.next().unwrap())?;
+                    if boxes.insert(name.clone(), (inputs, outputs)).is_some() {
+                        return Err(TermError::invalid(
+                            span,
+                            format!("box `{name}` is declared more than once"),
+                        ));
+                    }
+                }
+                Rule::term => term = Some(Self::from_pair(pair, &boxes)?),
+                _ => {}
+            }
+        }
+        Ok(term.unwrap())
+    }
+
+    fn from_pair(
+        pair: Pair<Rule>,
+        boxes: &HashMap<String, (usize, usize)>,
+    ) -> Result<Self, TermError> {
+        let span = pair.as_span();
+        match pair.as_rule() {
+            Rule::term => {
+                let mut terms: Vec<Self> = Vec::new();
+                for pair in pair.into_inner() {
+                    let pair_span = pair.as_span();
+                    let term = Self::from_pair(pair, boxes)?;
+                    if let Some(previous) = terms.last() {
+                        let (outputs, inputs) = (previous.arity().1, term.arity().0);
+                        if outputs != inputs {
+                            return Err(TermError::invalid(
+                                pair_span,
+                                format!(
+                                    "cannot compose a term with {outputs} outputs and a term with {inputs} inputs"
+                                ),
+                            ));
+                        }
+                    }
+                    terms.push(term);
+                }
+                Ok(single_or(terms, Self::Compose))
+            }
+            Rule::tensor => Ok(single_or(
+                pair.into_inner()
+                    .map(|pair| Self::from_pair(pair, boxes))
+                    .collect::<Result<_, _>>()?,
+                Self::Tensor,
+            )),
+            Rule::identity => Ok(Self::Id(
+                pair.into_inner()
+                    .next()
+                    .map(number)
+                    .transpose()?
+                    .unwrap_or(1),
+            )),
+            Rule::swap => {
+                let mut inner = pair.into_inner();
+                match (inner.next(), inner.next()) {
+                    (Some(m), Some(n)) => Ok(Self::Swap(number(m)?, number(n)?)),
+                    _ => Ok(Self::Swap(1, 1)),
+                }
+            }
+            Rule::copy => Ok(Self::Copy(
+                pair.into_inner()
+                    .next()
+                    .map(number)
+                    .transpose()?
+                    .unwrap_or(2),
+            )),
+            Rule::delete => Ok(Self::Delete),
+            Rule::generator => {
+                let name = pair.as_str();
+                let (inputs, outputs) = boxes.get(name).copied().ok_or_else(|| {
+                    TermError::invalid(span, format!("box `{name}` has not been declared"))
+                })?;
+                Ok(Self::Box {
+                    name: name.to_owned(),
+                    inputs,
+                    outputs,
+                })
+            }
+            rule => unreachable!("unexpected rule {rule:?}"),
+        }
+    }
+
+    /// Build the hypergraph denoted by the term.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the hypergraph could not be built.
+    pub fn to_graph(&self) -> Result<Hypergraph<TermWeight>, TermError> {
+        let (inputs, outputs) = self.arity();
+        let mut builder = HypergraphBuilder::new(vec![Label(String::new()); inputs], outputs);
+        let inputs = builder.graph_inputs().collect();
+        let wires = self.build(&mut builder, inputs);
+        debug_assert_eq!(wires.len(), outputs);
+        for (wire, in_port) in wires
+            .into_iter()
+            .zip(builder.graph_outputs().collect::<Vec<_>>())
+        {
+            builder.link(wire, in_port)?;
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Add the term to `builder`, taking its input wires and returning its output wires.
+    fn build(
+        &self,
+        builder: &mut HypergraphBuilder<TermWeight>,
+        mut wires: Vec<OutPort<TermWeight>>,
+    ) -> Vec<OutPort<TermWeight>> {
+        match self {
+            Self::Id(_) => wires,
+            Self::Swap(m, _) => {
+                wires.rotate_left(*m);
+                wires
+            }
+            Self::Copy(n) => vec![wires[0].clone(); *n],
+            Self::Delete => vec![],
+            Self::Box { name, outputs, .. } => {
+                let op = builder.add_operation(
+                    wires.len(),
+                    vec![Label(String::new()); *outputs],
+                    Label(name.clone()),
+                );
+                for (wire, in_port) in wires.into_iter().zip(op.inputs()) {
+                    // Linking fresh ports cannot fail.
+                    builder.link(wire, in_port).unwrap();
+                }
+                op.outputs().collect()
+            }
+            Self::Compose(terms) => terms
+                .iter()
+                .fold(wires, |wires, term| term.build(builder, wires)),
+            Self::Tensor(terms) => {
+                let mut rest = wires.into_iter();
+                terms
+                    .iter()
+                    .flat_map(|term| {
+                        let wires = rest.by_ref().take(term.arity().0).collect();
+                        term.build(builder, wires)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn number(pair: Pair<Rule>) -> Result<usize, TermError> {
+    pair.as_str()
+        .parse()
+        .map_err(|_| TermError::invalid(pair.as_span(), "number is too large".to_owned()))
+}
+
+fn single_or(mut terms: Vec<Term>, f: impl FnOnce(Vec<Term>) -> Term) -> Term {
+    if terms.len() == 1 {
+        terms.pop().unwrap()
+    } else {
+        f(terms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pest::error::LineColLocation;
+
+    use super::{Term, TermError};
+    use crate::{
+        hypergraph::{isomorphism::is_isomorphic, petgraph::to_pet},
+        lp::Solver,
+        monoidal::{graph::MonoidalGraph, term::TermFormat, wired_graph::from_graph},
+    };
+
+    const SOURCE: &str = "
+f : 1 -> 2
+g : 1 -> 1
+h : 1 -> 0
+
+(f ⊗ id) ; (id ⊗ swap) ; (g ⊗ copy ⊗ h) ; del @ id(2)
+";
+
+    #[test]
+    fn parse_term() -> Result<(), TermError> {
+        let term = Term::parse(SOURCE)?;
+        assert_eq!(term.arity(), (2, 2));
+        insta::assert_debug_snapshot!(term);
+        insta::assert_ron_snapshot!(to_pet(&term.to_graph()?));
+        Ok(())
+    }
+
+    #[test]
+    fn export_round_trip() -> Result<(), TermError> {
+        // A single input, so that the layout has no free inputs to reorder.
+        let declarations = "f : 1 -> 2\ng : 1 -> 1\nh : 1 -> 0\n";
+        let source = format!("{declarations}f ; (g ⊗ copy) ; (h ⊗ swap) ; (copy ⊗ del)");
+        let graph = Term::parse(&source)?.to_graph()?;
+        let monoidal_graph = MonoidalGraph::from(&from_graph(&graph, Solver::default(), false));
+        let exported = monoidal_graph.to_term(TermFormat::Plain);
+        let reparsed = Term::parse(&format!("{declarations}{exported}"))?.to_graph()?;
+        assert!(is_isomorphic(&graph, &reparsed), "{exported}");
+        Ok(())
+    }
+
+    #[test]
+    fn term_errors() {
+        let cases = [
+            ("f : 1 -> 2\nf ; f", "cannot compose", (2, 5)),
+            ("f : 1 -> 2\nf ; g", "`g` has not been declared", (2, 5)),
+            (
+                "f : 1 -> 2\nf : 2 -> 1\nf",
+                "declared more than once",
+                (2, 1),
+            ),
+        ];
+        for (source, message, start) in cases {
+            let err = Term::parse(source).unwrap_err();
+            assert!(err.to_string().contains(message), "{err}");
+            match err.line_col() {
+                Some(LineColLocation::Pos(pos) | LineColLocation::Span(pos, _)) => {
+                    assert_eq!(*pos, start);
+                }
+                None => panic!("error has no location"),
+            }
+        }
+    }
+}
@@ -1,18 +1,26 @@
-use egui::{Pos2, Rounding};
+use egui::{Pos2, Rect, Rounding, Vec2};
 use flo_curves::Coord2;
-use sd_core::hypergraph::{
-    self,
-    adapter::{collapse::CollapseOperation, cut::CutOperation},
-    generic::{Ctx, Node, Operation},
-    subgraph::SubOperation,
-    traits::Graph,
-    Weight,
+use sd_core::{
+    examples::DummyOperation,
+    hypergraph::{
+        self,
+        adapter::{collapse::CollapseOperation, cut::CutOperation},
+        generic::{Ctx, Node, Operation},
+        subgraph::SubOperation,
+        traits::{Graph, WithWeight},
+        Weight,
+    },
+    prettyprinter::PrettyPrint,
 };
+use serde::{Deserialize, Serialize};
 
 pub const RADIUS_ARG: f32 = 0.05;
 pub const RADIUS_COPY: f32 = 0.1;
 pub const TOLERANCE: f32 = 0.3;
 pub const TEXT_SIZE: f32 = 0.28;
+/// The smallest text size on screen which is still drawn.
+/// When zoomed out past this, labels are dropped and thunks are collapsed into summary boxes.
+pub const MIN_TEXT_SIZE: f32 = 5.0;
 pub const RADIUS_OPERATION: f32 = 0.2;
 
 // pub trait ContainsPoint {
@@ -42,12 +50,80 @@ pub(crate) fn to_coord2(pos2: Pos2) -> Coord2 {
     Coord2(f64::from(pos2.x), f64::from(pos2.y))
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+/// The direction in which wires flow, from the inputs of a diagram to its outputs.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+pub enum Orientation {
+    #[default]
+    TopToBottom,
+    LeftToRight,
+    BottomToTop,
+}
+
+impl Orientation {
+    pub const ALL: [Self; 3] = [Self::TopToBottom, Self::LeftToRight, Self::BottomToTop];
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::TopToBottom => "Top to bottom",
+            Self::LeftToRight => "Left to right",
+            Self::BottomToTop => "Bottom to top",
+        }
+    }
+
+    /// Whether wires flow vertically.
+    #[must_use]
+    pub const fn is_vertical(self) -> bool {
+        !matches!(self, Self::LeftToRight)
+    }
+
+    /// Move a point of a top-to-bottom diagram with the given size into this orientation.
+    #[must_use]
+    pub fn transform_pos(self, pos: Pos2, size: Vec2) -> Pos2 {
+        match self {
+            Self::TopToBottom => pos,
+            Self::LeftToRight => Pos2::new(pos.y, pos.x),
+            Self::BottomToTop => Pos2::new(pos.x, size.y - pos.y),
+        }
+    }
+
+    /// Turn a direction of a top-to-bottom diagram into this orientation.
+    #[must_use]
+    pub fn transform_vec(self, vec: Vec2) -> Vec2 {
+        match self {
+            Self::TopToBottom => vec,
+            Self::LeftToRight => Vec2::new(vec.y, vec.x),
+            Self::BottomToTop => Vec2::new(vec.x, -vec.y),
+        }
+    }
+
+    #[must_use]
+    pub fn transform_rect(self, rect: Rect, size: Vec2) -> Rect {
+        Rect::from_two_pos(
+            self.transform_pos(rect.min, size),
+            self.transform_pos(rect.max, size),
+        )
+    }
+
+    /// The size of a top-to-bottom diagram once laid out in this orientation.
+    #[must_use]
+    pub fn transform_size(self, size: Vec2) -> Vec2 {
+        match self {
+            Self::TopToBottom | Self::BottomToTop => size,
+            Self::LeftToRight => Vec2::new(size.y, size.x),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ShapeKind {
     Squircle,
     Square,
     BulletUp,
     BulletDown,
+    BulletLeft,
+    BulletRight,
 }
 
 impl ShapeKind {
@@ -68,12 +144,40 @@ impl ShapeKind {
                 sw: radius,
                 se: radius,
             },
+            ShapeKind::BulletLeft => Rounding {
+                nw: radius,
+                ne: 0.0,
+                sw: radius,
+                se: 0.0,
+            },
+            ShapeKind::BulletRight => Rounding {
+                nw: 0.0,
+                ne: radius,
+                sw: 0.0,
+                se: radius,
+            },
+        }
+    }
+
+    /// The shape of a node of a top-to-bottom diagram once laid out in the given orientation,
+    /// so that bullets keep facing the inputs or the outputs.
+    #[must_use]
+    pub const fn orient(self, orientation: Orientation) -> Self {
+        match (orientation, self) {
+            (Orientation::LeftToRight, ShapeKind::BulletUp) => ShapeKind::BulletLeft,
+            (Orientation::LeftToRight, ShapeKind::BulletDown) => ShapeKind::BulletRight,
+            (Orientation::BottomToTop, ShapeKind::BulletUp) => ShapeKind::BulletDown,
+            (Orientation::BottomToTop, ShapeKind::BulletDown) => ShapeKind::BulletUp,
+            (_, kind) => kind,
         }
     }
 }
 
 pub trait Shapeable {
     fn to_shape(&self) -> ShapeKind;
+
+    /// The textual name of the operation, which style sheets can match against.
+    fn name(&self) -> String;
 }
 
 impl<G: Graph> Shapeable for CollapseOperation<G>
@@ -86,6 +190,13 @@ where
             Node::Thunk(_) => ShapeKind::Square,
         }
     }
+
+    fn name(&self) -> String {
+        match self.inner() {
+            Node::Operation(op) => op.name(),
+            Node::Thunk(_) => "thunk".to_owned(),
+        }
+    }
 }
 
 impl<G: Graph> Shapeable for CutOperation<G>
@@ -99,6 +210,14 @@ where
             Self::Store { .. } => ShapeKind::BulletDown,
         }
     }
+
+    fn name(&self) -> String {
+        match self {
+            Self::Inner { op, .. } => op.name(),
+            Self::Reuse { .. } => "reuse".to_owned(),
+            Self::Store { .. } => "store".to_owned(),
+        }
+    }
 }
 
 impl<T: Ctx> Shapeable for SubOperation<T>
@@ -108,10 +227,57 @@ where
     fn to_shape(&self) -> ShapeKind {
         self.inner().to_shape()
     }
+
+    fn name(&self) -> String {
+        self.inner().name()
+    }
 }
 
-impl<W: Weight> Shapeable for hypergraph::Operation<W> {
+impl<W: Weight> Shapeable for hypergraph::Operation<W>
+where
+    W::OperationWeight: PrettyPrint,
+{
+    fn to_shape(&self) -> ShapeKind {
+        ShapeKind::Squircle
+    }
+
+    fn name(&self) -> String {
+        self.weight().to_pretty()
+    }
+}
+
+impl Shapeable for DummyOperation {
     fn to_shape(&self) -> ShapeKind {
         ShapeKind::Squircle
     }
+
+    fn name(&self) -> String {
+        self.weight().to_pretty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, vec2, Rect};
+
+    use super::Orientation;
+
+    #[test]
+    fn orientation() {
+        let size = vec2(2.0, 5.0);
+        let rect = Rect::from_min_max(pos2(0.5, 1.0), pos2(1.5, 4.0));
+
+        let ltr = Orientation::LeftToRight;
+        assert_eq!(ltr.transform_size(size), vec2(5.0, 2.0));
+        assert_eq!(ltr.transform_pos(pos2(0.5, 1.0), size), pos2(1.0, 0.5));
+        assert_eq!(ltr.transform_vec(vec2(0.0, -1.0)), vec2(-1.0, 0.0));
+
+        let btt = Orientation::BottomToTop;
+        assert_eq!(btt.transform_size(size), size);
+        assert_eq!(
+            btt.transform_rect(rect, size),
+            Rect::from_min_max(pos2(0.5, 1.0), pos2(1.5, 4.0))
+        );
+        assert_eq!(btt.transform_pos(pos2(0.5, 0.0), size), pos2(0.5, 5.0));
+    }
 }
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     fmt::{Debug, Display},
     ops::Range,
 };
@@ -24,8 +25,9 @@ use tracing::{debug, info};
 use web_time::Instant;
 
 use crate::{
-    common::RADIUS_OPERATION,
+    common::{Orientation, Shapeable, RADIUS_OPERATION, TEXT_SIZE},
     intervals::{Interval, Intervals},
+    style::StyleSheet,
 };
 
 #[derive(Clone, Debug, Error)]
@@ -145,6 +147,9 @@ pub struct WireData<T: Ctx, H, V> {
     pub v_max: V,
     #[cfg_attr(test, serde(skip_serializing))]
     pub addr: T::Edge,
+    /// The label drawn beside the wire, when wire labels are shown.
+    #[cfg_attr(test, serde(skip_serializing_if = "Option::is_none"))]
+    pub label: Option<String>,
 }
 
 #[derive(Clone, Derivative)]
@@ -259,11 +264,13 @@ impl<T: Ctx, S> HLayout<T, S> {
                                  v_min,
                                  v_max,
                                  addr,
+                                 label,
                              }| WireData {
                                 h: solution.value(h) as f32,
                                 v_min,
                                 v_max,
                                 addr,
+                                label,
                             },
                         )
                         .collect()
@@ -349,11 +356,13 @@ impl<T: Ctx> Layout<T> {
                                  v_min,
                                  v_max,
                                  addr,
+                                 label,
                              }| WireData {
                                 h,
                                 v_min: solution.value(v_min) as f32,
                                 v_max: solution.value(v_max) as f32,
                                 addr,
+                                label,
                             },
                         )
                         .collect()
@@ -363,13 +372,50 @@ impl<T: Ctx> Layout<T> {
     }
 }
 
+/// How far the box of an operation extends past its centre beyond the radius of an operation,
+/// along the width of the text written on it.
+#[allow(clippy::cast_precision_loss)]
+fn label_extra_size<T: Ctx>(addr: &T::Operation, style_sheet: &StyleSheet) -> f32
+where
+    T::Operation: Shapeable,
+    Weight<T::Operation>: Display,
+{
+    let (_, text) = style_sheet.operation(addr);
+    (text.chars().count().saturating_sub(1) as f32 / 2.0) * RADIUS_OPERATION
+}
+
+/// The length of wire needed to write a label beside it.
+const WIRE_LABEL_HEIGHT: f32 = 1.5 * TEXT_SIZE;
+
+/// The label of a wire, if wire labels are being collected in `labelled`
+/// and its edge has not been labelled already.
+fn wire_label<T: Ctx>(addr: &T::Edge, labelled: &mut Option<HashSet<T::Edge>>) -> Option<String>
+where
+    Weight<T::Edge>: Display,
+{
+    let labelled = labelled.as_mut()?;
+    let label = addr.weight().to_string();
+    (!label.is_empty() && labelled.insert(addr.clone())).then_some(label)
+}
+
+/// How far a wire label extends from its wire, along the width of the label.
+#[allow(clippy::cast_precision_loss)]
+fn wire_label_size(label: &str) -> f32 {
+    (label.chars().count() as f32 + 1.0) * RADIUS_OPERATION
+}
+
 #[allow(clippy::too_many_lines)]
 fn h_layout_internal<T: Ctx>(
     graph: &MonoidalGraph<T>,
     problem: &mut LpProblem,
+    orientation: Orientation,
+    wire_labels: bool,
+    style_sheet: &StyleSheet,
 ) -> LayoutInternal<T, Variable, ()>
 where
+    T::Operation: Shapeable,
     Weight<T::Operation>: Display,
+    Weight<T::Edge>: Display,
 {
     // STEP 1. Generate variables for each layer.
     let min = problem.add_variable(variable().min(0.0));
@@ -378,18 +424,29 @@ where
     let mut nodes = Vec::default();
     let mut wires: Vec<Vec<WireData<T, Variable, ()>>> = Vec::default();
 
-    let add_constraints_wires =
-        |problem: &mut LpProblem, vs: &Vec<Variable>, min: Variable, max: Variable| {
-            if let Some(x) = vs.first().copied() {
-                problem.add_constraint((x - min).geq(0.5));
-            }
-            if let Some(x) = vs.last().copied() {
-                problem.add_constraint((max - x).geq(0.5));
-            }
-            for (x, y) in vs.iter().copied().tuple_windows() {
-                problem.add_constraint((y - x).geq(1.0));
+    let mut labelled = wire_labels.then(HashSet::default);
+
+    // Labels are written across vertical wires, so leave room for them to the right of each wire.
+    let add_constraints_wires = |problem: &mut LpProblem,
+                                 ws: &[WireData<T, Variable, ()>],
+                                 min: Variable,
+                                 max: Variable| {
+        let label_size = |w: &WireData<T, Variable, ()>| -> f64 {
+            match &w.label {
+                Some(label) if orientation.is_vertical() => f64::from(wire_label_size(label)),
+                _ => 0.0,
             }
         };
+        if let Some(w) = ws.first() {
+            problem.add_constraint((w.h - min).geq(0.5));
+        }
+        if let Some(w) = ws.last() {
+            problem.add_constraint((max - w.h).geq(label_size(w).max(0.5)));
+        }
+        for (w, v) in ws.iter().tuple_windows() {
+            problem.add_constraint((v.h - w.h).geq(label_size(w).max(1.0)));
+        }
+    };
 
     let add_constraints_nodes = |problem: &mut LpProblem, ns: &Vec<NodeOffset<T, Variable, ()>>| {
         if let Some(x) = ns.first() {
@@ -407,34 +464,34 @@ where
         variable().min(0.0),
         graph.free_inputs.len() + graph.bound_inputs.len(),
     );
+    let inputs: Vec<_> = inputs
+        .into_iter()
+        .zip(graph.free_inputs.iter().chain(&graph.bound_inputs))
+        .map(|(h, addr)| WireData {
+            h,
+            v_min: (),
+            v_max: (),
+            addr: addr.clone(),
+            label: wire_label::<T>(addr, &mut labelled),
+        })
+        .collect();
     add_constraints_wires(problem, &inputs, min, max);
-    wires.push(
-        inputs
+    wires.push(inputs);
+    for slice in &graph.slices {
+        let outputs = problem.add_variables(variable().min(0.0), slice.number_of_outputs());
+        let outputs: Vec<_> = outputs
             .into_iter()
-            .zip(graph.free_inputs.iter().chain(&graph.bound_inputs))
-            .map(|(h, addr)| WireData {
+            .zip(slice.output_links())
+            .map(|(h, link)| WireData {
                 h,
                 v_min: (),
                 v_max: (),
-                addr: addr.clone(),
+                label: wire_label::<T>(&link.0, &mut labelled),
+                addr: link.0,
             })
-            .collect(),
-    );
-    for slice in &graph.slices {
-        let outputs = problem.add_variables(variable().min(0.0), slice.number_of_outputs());
+            .collect();
         add_constraints_wires(problem, &outputs, min, max);
-        wires.push(
-            outputs
-                .into_iter()
-                .zip(slice.output_links())
-                .map(|(h, link)| WireData {
-                    h,
-                    v_min: (),
-                    v_max: (),
-                    addr: link.0,
-                })
-                .collect(),
-        );
+        wires.push(outputs);
 
         let mut input_offset = 0;
         let mut output_offset = 0;
@@ -446,7 +503,13 @@ where
                 let node = match op {
                     MonoidalOp::Thunk { body, addr, .. } => Node::Thunk {
                         addr: addr.clone(),
-                        layout: h_layout_internal(body, problem),
+                        layout: h_layout_internal(
+                            body,
+                            problem,
+                            orientation,
+                            wire_labels,
+                            style_sheet,
+                        ),
                         inputs: problem.add_variables(variable().min(0.0), addr.number_of_inputs()),
                         outputs: problem
                             .add_variables(variable().min(0.0), addr.number_of_outputs()),
@@ -472,10 +535,12 @@ where
                     MonoidalOp::Operation { addr } => Node::Atom {
                         h_pos: problem.add_variable(variable().min(0.0)),
                         v_pos: (),
-                        extra_size: (addr.weight().to_string().chars().count().saturating_sub(1)
-                            as f32
-                            / 2.0)
-                            * RADIUS_OPERATION,
+                        // Labels are written across vertical wires but along horizontal ones.
+                        extra_size: if orientation.is_vertical() {
+                            label_extra_size::<T>(addr, style_sheet)
+                        } else {
+                            0.0
+                        },
                         atype: AtomType::Op(addr.clone()),
                     },
                     MonoidalOp::Copy { copies, .. } if *copies != 1 => Node::Atom {
@@ -656,7 +721,13 @@ where
 fn v_layout_internal<T: Ctx>(
     problem: &mut LpProblem,
     h_layout: HLayout<T, ()>,
-) -> LayoutInternal<T, f32, Variable> {
+    orientation: Orientation,
+    style_sheet: &StyleSheet,
+) -> LayoutInternal<T, f32, Variable>
+where
+    T::Operation: Shapeable,
+    Weight<T::Operation>: Display,
+{
     // Set up wires
     let wires: Vec<Vec<WireData<T, f32, Variable>>> = h_layout
         .wires
@@ -667,7 +738,13 @@ fn v_layout_internal<T: Ctx>(
                     let v_top = problem.add_variable(variable().min(0.0));
                     let v_bot = problem.add_variable(variable().min(0.0));
 
-                    problem.add_constraint(Expression::leq(v_top.into(), v_bot));
+                    // Labels are written beside vertical wires but along horizontal ones.
+                    let length = match &v.label {
+                        Some(_) if orientation.is_vertical() => WIRE_LABEL_HEIGHT,
+                        Some(label) => wire_label_size(label),
+                        None => 0.0,
+                    };
+                    problem.add_constraint(Expression::leq(v_top + length, v_bot));
                     problem.add_objective(v_bot - v_top);
 
                     WireData {
@@ -675,6 +752,7 @@ fn v_layout_internal<T: Ctx>(
                         v_min: v_top,
                         v_max: v_bot,
                         addr: v.addr,
+                        label: v.label,
                     }
                 })
                 .collect()
@@ -721,6 +799,13 @@ fn v_layout_internal<T: Ctx>(
                         } => {
                             let v_pos = problem.add_variable(variable().min(0.0));
 
+                            let label_size = match &atype {
+                                AtomType::Op(addr) if !orientation.is_vertical() => {
+                                    label_extra_size::<T>(addr, style_sheet)
+                                }
+                                _ => 0.0,
+                            };
+
                             let in_gap = if n.inputs.len() < 2 {
                                 1.0
                             } else {
@@ -739,9 +824,11 @@ fn v_layout_internal<T: Ctx>(
                             } / 2.0;
 
                             let start = problem.add_variable(variable().min(0.0));
-                            problem.add_constraint(Expression::eq(v_pos - in_gap, start));
+                            problem
+                                .add_constraint(Expression::eq(v_pos - in_gap - label_size, start));
                             let end = problem.add_variable(variable().min(0.0));
-                            problem.add_constraint(Expression::eq(v_pos + out_gap, end));
+                            problem
+                                .add_constraint(Expression::eq(v_pos + out_gap + label_size, end));
 
                             (
                                 Node::Atom {
@@ -797,7 +884,8 @@ fn v_layout_internal<T: Ctx>(
                             inputs,
                             outputs,
                         } => {
-                            let layout = v_layout_internal(problem, layout);
+                            let layout =
+                                v_layout_internal(problem, layout, orientation, style_sheet);
 
                             let x = ins
                                 .iter()
@@ -884,21 +972,37 @@ fn v_layout_internal<T: Ctx>(
     }
 }
 
-pub fn layout<T: Ctx>(graph: &MonoidalGraph<T>, solver: Solver) -> Result<Layout<T>, LayoutError>
+/// Lay out a graph from top to bottom, leaving room for its labels once turned into the given orientation.
+/// If `wire_labels` is set, each wire is labelled once with the weight of its edge.
+/// Operations are made wide enough for the text given to them by `style_sheet`.
+pub fn layout<T: Ctx>(
+    graph: &MonoidalGraph<T>,
+    solver: Solver,
+    orientation: Orientation,
+    wire_labels: bool,
+    style_sheet: &StyleSheet,
+) -> Result<Layout<T>, LayoutError>
 where
+    T::Operation: Shapeable,
     Weight<T::Operation>: Display,
+    Weight<T::Edge>: Display,
 {
     let mut problem = LpProblem::default();
 
     let now = Instant::now();
     info!("Calculating horizontal layout");
-    let layout = h_layout_internal(graph, &mut problem);
+    let layout = h_layout_internal(graph, &mut problem, orientation, wire_labels, style_sheet);
     problem.add_objective(layout.h_max);
     let h_solution = problem.minimise(solver)?;
 
     problem = LpProblem::default();
     info!("Calculating vertical layout");
-    let v_layout = v_layout_internal(&mut problem, HLayout::from_solution_h(layout, &*h_solution));
+    let v_layout = v_layout_internal(
+        &mut problem,
+        HLayout::from_solution_h(layout, &*h_solution),
+        orientation,
+        style_sheet,
+    );
     problem.add_objective(v_layout.v_max);
     let v_solution = problem.minimise(solver)?;
 
@@ -917,28 +1021,73 @@ where
 
 #[cfg(test)]
 mod tests {
-    use sd_core::{examples, lp::Solver};
+    use from_pest::FromPest;
+    use pest::Parser;
+    use sd_core::{
+        examples,
+        language::spartan::{Expr, Rule, SpartanParser},
+        lp::Solver,
+        monoidal::{graph::MonoidalGraph, wired_graph::from_graph},
+    };
 
-    use super::layout;
+    use super::{layout, wire_label_size};
+    use crate::{common::Orientation, style::StyleSheet};
 
     #[test]
     fn int() {
         insta::with_settings!({sort_maps => true}, {
-            insta::assert_ron_snapshot!(layout(&examples::int(), Solver::default()).expect("Layout failed"));
+            insta::assert_ron_snapshot!(layout(&examples::int(), Solver::default(), Orientation::default(), false, &StyleSheet::default()).expect("Layout failed"));
         });
     }
 
     #[test]
     fn copy() {
         insta::with_settings!({sort_maps => true}, {
-            insta::assert_ron_snapshot!(layout(&examples::copy(), Solver::default()).expect("Layout failed"));
+            insta::assert_ron_snapshot!(layout(&examples::copy(), Solver::default(), Orientation::default(), false, &StyleSheet::default()).expect("Layout failed"));
         });
     }
 
     #[test]
     fn thunk() {
         insta::with_settings!({sort_maps => true}, {
-            insta::assert_ron_snapshot!(layout(&examples::thunk(), Solver::default()).expect("Layout failed"));
+            insta::assert_ron_snapshot!(layout(&examples::thunk(), Solver::default(), Orientation::default(), false, &StyleSheet::default()).expect("Layout failed"));
         });
     }
+
+    #[test]
+    fn wire_labels() {
+        let mut pairs =
+            SpartanParser::parse(Rule::program, "bind long_name = 1 in plus(long_name, 2)")
+                .unwrap();
+        let graph = Expr::from_pest(&mut pairs)
+            .unwrap()
+            .to_graph(false)
+            .unwrap();
+        let graph = MonoidalGraph::from(&from_graph(&graph, Solver::default(), false));
+        let layout = |wire_labels| {
+            layout(
+                &graph,
+                Solver::default(),
+                Orientation::default(),
+                wire_labels,
+                &StyleSheet::default(),
+            )
+            .expect("Layout failed")
+        };
+
+        // The gap after a labelled wire is widened to fit its label
+        let labelled = layout(true);
+        let (gap, label) = labelled
+            .wires
+            .iter()
+            .flat_map(|ws| ws.windows(2))
+            .find_map(|ws| Some((ws[1].h - ws[0].h, ws[0].label.clone()?)))
+            .expect("no labelled wire beside another");
+        assert_eq!(label, "long_name");
+        assert!(gap >= wire_label_size(&label) - 1e-3);
+
+        let unlabelled = layout(false);
+        assert!(unlabelled.wires.iter().flatten().all(|w| w.label.is_none()));
+        assert!(labelled.width() > unlabelled.width());
+    }
 }
@@ -4,4 +4,6 @@ pub mod layout;
 pub mod render;
 pub mod renderable;
 pub mod shape;
+pub mod spatial;
+pub mod style;
 pub mod svg;
@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
-use egui::{emath::RectTransform, show_tooltip_at_pointer, Id, Pos2, Rect, Response};
+use egui::{
+    emath::RectTransform, show_tooltip_at_pointer, vec2, Id, Pos2, Rect, Response, Stroke, Vec2,
+};
 use indexmap::IndexSet;
 use itertools::Itertools;
 use sd_core::{
@@ -15,17 +17,20 @@ use sd_core::{
 };
 
 use crate::{
-    common::{Shapeable, RADIUS_ARG, RADIUS_COPY, RADIUS_OPERATION},
+    common::{
+        Shapeable, MIN_TEXT_SIZE, RADIUS_ARG, RADIUS_COPY, RADIUS_OPERATION, TEXT_SIZE, TOLERANCE,
+    },
     layout::{AtomType, Layout, NodeOffset},
     renderable::RenderableGraph,
-    shape::Shape,
+    shape::{Shape, Shapes},
+    style::StyleSheet,
 };
 
 #[allow(clippy::needless_collect)]
 pub fn render<G>(
     graph: &mut G,
     ui: &egui::Ui,
-    shapes: &[Shape<G::Ctx>],
+    shapes: &Shapes<G::Ctx>,
     response: &Response,
     to_screen: RectTransform,
     search: Option<&str>,
@@ -35,30 +40,50 @@ where
     Edge<G::Ctx>: Codeable,
     Operation<G::Ctx>: Codeable + Matchable,
     Thunk<G::Ctx>: Matchable,
+    Weight<Thunk<G::Ctx>>: Display,
     Weight<Edge<G::Ctx>>: WithType,
 {
     let viewport = *to_screen.from();
+    let collapse = collapsed(&to_screen);
 
     let mut highlight_op = None;
     let mut highlight_edges = IndexSet::default();
 
+    let visible = visible_shapes(shapes, viewport, collapse);
+
+    // Highlight the wires under the pointer.
+    if let Some(hover_pos) = response.hover_pos() {
+        let hover_pos = to_screen.inverse().transform_pos(hover_pos);
+        let hover_rect = Rect::from_center_size(hover_pos, Vec2::splat(2.0 * TOLERANCE));
+        for i in shapes.index.query(hover_rect) {
+            match &shapes.shapes[i] {
+                shape @ (Shape::Line { addr, .. } | Shape::CubicBezier { addr, .. })
+                    if visible.binary_search(&i).is_ok()
+                        && shape.contains_point(hover_pos, TOLERANCE) =>
+                {
+                    highlight_edges.insert(addr.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+
     let id = Id::new(graph.key());
-    let shapes_vec: Vec<_> = shapes
-        .iter()
-        .filter(|shape| viewport.intersects(shape.bounding_box()))
-        .map(|shape| {
-            let mut s = shape.clone();
+    let shapes_vec: Vec<_> = visible
+        .into_iter()
+        .map(|i| {
+            let mut s = shapes.shapes[i].clone();
+            if let Shape::Rectangle { addr, summary, .. } = &mut s {
+                if collapse {
+                    *summary = Some(format!(
+                        "{} ({} nodes)",
+                        addr.weight(),
+                        addr.nodes().count()
+                    ));
+                }
+            }
             s.apply_transform(&to_screen);
-            s.collect_highlights(
-                graph,
-                id,
-                ui,
-                response,
-                &to_screen,
-                search,
-                &mut highlight_op,
-                &mut highlight_edges,
-            );
+            s.collect_highlights(graph, id, ui, &to_screen, search, &mut highlight_op);
             s
         })
         .collect();
@@ -86,9 +111,42 @@ where
         .collect()
 }
 
+/// Whether the view is too far zoomed out to read labels,
+/// so thunks are collapsed into summary boxes.
+fn collapsed(to_screen: &RectTransform) -> bool {
+    TEXT_SIZE * to_screen.scale().min_elem() <= MIN_TEXT_SIZE
+}
+
+/// The indices of the shapes in `viewport`, in order.
+/// If `collapse` is set, the shapes drawn inside thunks are left out.
+fn visible_shapes<T: Ctx>(shapes: &Shapes<T>, viewport: Rect, collapse: bool) -> Vec<usize> {
+    let mut visible = Vec::default();
+    let mut skip_until = 0;
+    for i in shapes.index.query(viewport) {
+        if i < skip_until {
+            continue;
+        }
+        let shape = &shapes.shapes[i];
+        if let Shape::Rectangle { contents, .. } = shape {
+            if collapse {
+                skip_until = i + contents + 1;
+            }
+        }
+        if viewport.intersects(shape.bounding_box()) {
+            visible.push(i);
+        }
+    }
+    visible
+}
+
 #[allow(clippy::too_many_lines)]
-pub fn generate_shapes<T>(shapes: &mut Vec<Shape<T>>, layout: &Layout<T>, arrows: bool)
-where
+/// Generate the shapes of a layout, drawing operations in the styles given by `style_sheet`.
+pub fn generate_shapes<T>(
+    shapes: &mut Vec<Shape<T>>,
+    layout: &Layout<T>,
+    arrows: bool,
+    style_sheet: &StyleSheet,
+) where
     T: Ctx,
     T::Edge: ExtensibleEdge,
     T::Operation: Shapeable,
@@ -102,7 +160,7 @@ where
                     addr: wire.addr.clone(),
                     to_add: vec![node],
                     center: Pos2::new(wire.h, layout.v_min - 0.5),
-                    upwards: true,
+                    direction: vec2(0.0, -1.0),
                     stroke: None,
                     height: 0.1,
                 });
@@ -117,7 +175,7 @@ where
                     addr: wire.addr.clone(),
                     to_add: targets,
                     center: Pos2::new(wire.h, layout.v_max + 0.5),
-                    upwards: false,
+                    direction: vec2(0.0, 1.0),
                     stroke: None,
                     height: 0.1,
                 });
@@ -132,6 +190,15 @@ where
             end: Pos2::new(wire.h, wire.v_max),
             addr: wire.addr.clone(),
         });
+        if let Some(label) = &wire.label {
+            shapes.push(Shape::WireLabel {
+                anchor: Pos2::new(wire.h, (wire.v_min + wire.v_max) / 2.0),
+                offset: vec2(RADIUS_OPERATION / 2.0, 0.0),
+                size: TEXT_SIZE,
+                label: label.clone(),
+                addr: wire.addr.clone(),
+            });
+        }
     }
 
     // Nodes
@@ -221,14 +288,15 @@ where
                             });
                         }
                         AtomType::Op(addr) => {
+                            let (style, label) = style_sheet.operation(addr);
                             shapes.push(Shape::Operation {
                                 center,
                                 addr: addr.clone(),
-                                label: addr.weight().to_string(),
-                                kind: addr.to_shape(),
+                                label,
+                                kind: style.shape.unwrap_or_else(|| addr.to_shape()),
                                 radius: RADIUS_OPERATION,
-                                fill: None,
-                                stroke: None,
+                                fill: style.fill,
+                                stroke: style.stroke.map(|color| Stroke::new(1.0_f32, color)),
                             });
                         }
                         _ => (),
@@ -277,10 +345,13 @@ where
                         Pos2::new(layout.h_min, layout.v_min),
                         Pos2::new(layout.h_max, layout.v_max),
                     );
+                    let rect_index = shapes.len();
                     shapes.push(Shape::Rectangle {
                         rect: thunk_rect,
                         addr: addr.clone(),
                         stroke: None,
+                        contents: 0,
+                        summary: None,
                     });
 
                     for (edge, &x) in addr.bound_graph_inputs().rev().zip(layout.inputs().rev()) {
@@ -301,7 +372,12 @@ where
                         });
                     }
 
-                    generate_shapes(shapes, layout, false);
+                    generate_shapes(shapes, layout, false, style_sheet);
+
+                    let shapes_len = shapes.len();
+                    if let Shape::Rectangle { contents, .. } = &mut shapes[rect_index] {
+                        *contents = shapes_len - rect_index - 1;
+                    }
                 }
             }
         }
@@ -334,3 +410,88 @@ fn vertical_out_vertical_in(start: Pos2, end: Pos2) -> [Pos2; 4] {
         end,
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use egui::{emath::RectTransform, vec2, Rect};
+    use from_pest::FromPest;
+    use pest::Parser;
+    use sd_core::{
+        graph::Syntax,
+        hypergraph::Hypergraph,
+        language::spartan::{Expr, Rule, Spartan, SpartanParser},
+        lp::Solver,
+        monoidal::{graph::MonoidalGraph, wired_graph::from_graph},
+    };
+
+    use super::{collapsed, generate_shapes, visible_shapes};
+    use crate::{
+        common::{Orientation, MIN_TEXT_SIZE, TEXT_SIZE},
+        layout::layout,
+        shape::{Shape, Shapes},
+        style::StyleSheet,
+    };
+
+    fn shapes(source: &str) -> Shapes<Hypergraph<Syntax<Spartan>>> {
+        let mut pairs = SpartanParser::parse(Rule::program, source).unwrap();
+        let graph = Expr::from_pest(&mut pairs)
+            .unwrap()
+            .to_graph(false)
+            .unwrap();
+        let graph = MonoidalGraph::from(&from_graph(&graph, Solver::default(), false));
+        let style_sheet = StyleSheet::default();
+        let layout = layout(
+            &graph,
+            Solver::default(),
+            Orientation::default(),
+            false,
+            &style_sheet,
+        )
+        .unwrap();
+        let mut shapes = Vec::new();
+        generate_shapes(&mut shapes, &layout, true, &style_sheet);
+        Shapes::new(shapes, layout.size())
+    }
+
+    #[test]
+    fn collapse_when_zoomed_out() {
+        let zoom = |scale: f32| {
+            RectTransform::from_to(
+                Rect::from_min_size(Default::default(), vec2(1.0, 1.0)),
+                Rect::from_min_size(Default::default(), vec2(scale, scale)),
+            )
+        };
+        let cutoff = MIN_TEXT_SIZE / TEXT_SIZE;
+        assert!(collapsed(&zoom(cutoff / 2.0)));
+        assert!(collapsed(&zoom(cutoff)));
+        assert!(!collapsed(&zoom(cutoff * 2.0)));
+    }
+
+    #[test]
+    fn collapsed_thunks_hide_their_contents() {
+        let shapes = shapes("bind f = lambda(x . plus(x, 1)) in app(f, 2)");
+        let (rect, contents) = shapes
+            .shapes
+            .iter()
+            .enumerate()
+            .find_map(|(i, shape)| match shape {
+                Shape::Rectangle { contents, .. } => Some((i, *contents)),
+                _ => None,
+            })
+            .expect("no thunk drawn");
+        assert!(contents > 0);
+        let inside = rect + 1..=rect + contents;
+
+        let expanded = visible_shapes(&shapes, Rect::EVERYTHING, false);
+        assert_eq!(expanded, (0..shapes.shapes.len()).collect::<Vec<_>>());
+
+        let collapsed = visible_shapes(&shapes, Rect::EVERYTHING, true);
+        assert!(collapsed.contains(&rect));
+        assert!(!collapsed.iter().any(|i| inside.contains(i)));
+        assert_eq!(collapsed.len(), shapes.shapes.len() - contents);
+
+        // Shapes outside the viewport are not drawn either way
+        let empty = Rect::from_min_size(shapes.size.to_pos2() + vec2(1.0, 1.0), vec2(1.0, 1.0));
+        assert!(visible_shapes(&shapes, empty, false).is_empty());
+    }
+}
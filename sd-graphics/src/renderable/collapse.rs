@@ -2,6 +2,7 @@ use sd_core::hypergraph::{
     adapter::collapse::{
         CollapseEdge, CollapseGraph, CollapseNode, CollapseOperation, CollapseThunk,
     },
+    diff::Change,
     generic::Node,
 };
 
@@ -13,6 +14,11 @@ impl<G: RenderableGraph> RenderableGraph for CollapseGraph<G> {
         self.inner().selected(node.into_inner())
     }
 
+    // Delegate to inner graph.
+    fn change(&self, node: CollapseNode<G>) -> Option<Change> {
+        self.inner().change(node.into_inner())
+    }
+
     // Delegate to inner graph.
     fn clicked_edge(&mut self, edge: CollapseEdge<G>) {
         self.inner_mut().clicked_edge(edge.into_inner());
@@ -44,6 +50,16 @@ impl<G: RenderableGraph> RenderableGraph for CollapseGraph<G> {
         }
     }
 
+    // Delegate to inner graph.
+    fn can_inline(&self, thunk: &CollapseThunk<G>) -> bool {
+        self.inner().can_inline(thunk.inner())
+    }
+
+    // Delegate to inner graph.
+    fn inline_thunk(&mut self, thunk: CollapseThunk<G>) {
+        self.inner_mut().inline_thunk(thunk.into_inner());
+    }
+
     // Delegate to inner graph.
     fn extend(&mut self, nodes: impl Iterator<Item = CollapseNode<G>>) {
         self.inner_mut().extend(nodes.map(CollapseNode::into_inner));
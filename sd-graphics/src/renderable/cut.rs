@@ -1,5 +1,8 @@
 use itertools::Either;
-use sd_core::hypergraph::adapter::cut::{CutEdge, CutGraph, CutNode, CutOperation, CutThunk};
+use sd_core::hypergraph::{
+    adapter::cut::{CutEdge, CutGraph, CutNode, CutOperation, CutThunk},
+    diff::Change,
+};
 
 use super::RenderableGraph;
 
@@ -11,6 +14,14 @@ impl<G: RenderableGraph> RenderableGraph for CutGraph<G> {
             .either(|node| self.inner().selected(node), |_edge| false)
     }
 
+    // Inner nodes: delegate to inner graph.
+    // Store and reuse nodes: always return none.
+    fn change(&self, node: CutNode<G>) -> Option<Change> {
+        node.into_inner()
+            .left()
+            .and_then(|node| self.inner().change(node))
+    }
+
     // Toggle the edge.
     fn clicked_edge(&mut self, edge: CutEdge<G>) {
         self.toggle(edge.inner());
@@ -34,6 +45,16 @@ impl<G: RenderableGraph> RenderableGraph for CutGraph<G> {
         self.inner_mut().clicked_thunk(thunk.into_inner(), primary);
     }
 
+    // Delegate to inner graph.
+    fn can_inline(&self, thunk: &CutThunk<G>) -> bool {
+        self.inner().can_inline(thunk.inner())
+    }
+
+    // Delegate to inner graph.
+    fn inline_thunk(&mut self, thunk: CutThunk<G>) {
+        self.inner_mut().inline_thunk(thunk.into_inner());
+    }
+
     // Filter out store and reuse edges, then delegate to inner graph.
     fn extend(&mut self, nodes: impl Iterator<Item = CutNode<G>>) {
         self.inner_mut()
@@ -1,4 +1,4 @@
-use sd_core::hypergraph::{Edge, Hypergraph, Node, Operation, Thunk, Weight};
+use sd_core::hypergraph::{diff::Change, Edge, Hypergraph, Node, Operation, Thunk, Weight};
 
 use super::RenderableGraph;
 
@@ -8,6 +8,11 @@ impl<W: Weight> RenderableGraph for Hypergraph<W> {
         false
     }
 
+    // Always none
+    fn change(&self, _node: Node<W>) -> Option<Change> {
+        None
+    }
+
     // Noop
     fn clicked_edge(&mut self, _edge: Edge<W>) {}
 
@@ -17,6 +22,13 @@ impl<W: Weight> RenderableGraph for Hypergraph<W> {
     // Noop
     fn clicked_thunk(&mut self, _thunk: Thunk<W>, _primary: bool) {}
 
+    fn can_inline(&self, _thunk: &Thunk<W>) -> bool {
+        false
+    }
+
+    // Noop
+    fn inline_thunk(&mut self, _thunk: Thunk<W>) {}
+
     // Noop
     fn extend(&mut self, _nodes: impl Iterator<Item = Node<W>>) {}
 }
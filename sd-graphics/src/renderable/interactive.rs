@@ -1,5 +1,9 @@
+use itertools::Either;
 use sd_core::{
-    hypergraph::generic::{Ctx, Edge, Node, Operation, Thunk},
+    hypergraph::{
+        diff::Change,
+        generic::{Ctx, Edge, Node, Operation, Thunk},
+    },
     interactive::{InteractiveGraph, InteractiveSubgraph},
 };
 
@@ -7,23 +11,44 @@ use super::RenderableGraph;
 
 impl<G: RenderableGraph> RenderableGraph for InteractiveGraph<G> {
     fn selected(&self, node: Node<Self::Ctx>) -> bool {
-        self.0.selected(node)
+        self.graph.selected(node)
+    }
+
+    fn change(&self, node: Node<Self::Ctx>) -> Option<Change> {
+        let node = node.into_inner().left()?.into_inner();
+        self.change_of(&node)
     }
 
     fn clicked_edge(&mut self, edge: Edge<Self::Ctx>) {
-        self.0.clicked_edge(edge);
+        self.graph.clicked_edge(edge);
     }
 
     fn clicked_operation(&mut self, op: Operation<Self::Ctx>, primary: bool) {
-        self.0.clicked_operation(op, primary);
+        if primary {
+            if let Either::Left(op) = op.inner() {
+                self.inspect(op.inner().clone());
+            }
+        }
+        self.graph.clicked_operation(op, primary);
     }
 
     fn clicked_thunk(&mut self, thunk: Thunk<Self::Ctx>, primary: bool) {
-        self.0.clicked_thunk(thunk, primary);
+        if primary {
+            self.inspect(Node::Thunk(thunk.inner().inner().clone()));
+        }
+        self.graph.clicked_thunk(thunk, primary);
+    }
+
+    fn can_inline(&self, thunk: &Thunk<Self::Ctx>) -> bool {
+        InteractiveGraph::can_inline(self, thunk.inner().inner())
+    }
+
+    fn inline_thunk(&mut self, thunk: Thunk<Self::Ctx>) {
+        self.request_inline(thunk.into_inner().into_inner());
     }
 
     fn extend(&mut self, nodes: impl Iterator<Item = Node<Self::Ctx>>) {
-        self.0.extend(nodes);
+        self.graph.extend(nodes);
     }
 }
 
@@ -32,6 +57,10 @@ impl<T: Ctx> RenderableGraph for InteractiveSubgraph<T> {
         self.0.selected(node)
     }
 
+    fn change(&self, node: Node<Self::Ctx>) -> Option<Change> {
+        self.0.change(node)
+    }
+
     fn clicked_edge(&mut self, edge: Edge<Self::Ctx>) {
         self.0.clicked_edge(edge);
     }
@@ -44,6 +73,14 @@ impl<T: Ctx> RenderableGraph for InteractiveSubgraph<T> {
         self.0.clicked_thunk(thunk, primary);
     }
 
+    fn can_inline(&self, thunk: &Thunk<Self::Ctx>) -> bool {
+        self.0.can_inline(thunk)
+    }
+
+    fn inline_thunk(&mut self, thunk: Thunk<Self::Ctx>) {
+        self.0.inline_thunk(thunk);
+    }
+
     fn extend(&mut self, nodes: impl Iterator<Item = Node<Self::Ctx>>) {
         self.0.extend(nodes);
     }
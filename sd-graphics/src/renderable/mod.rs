@@ -1,4 +1,5 @@
 use sd_core::hypergraph::{
+    diff::Change,
     generic::{Edge, Node, Operation, Thunk},
     traits::Graph,
 };
@@ -13,8 +14,11 @@ mod subgraph;
 /// Abstraction over a graph that can be rendered.
 pub trait RenderableGraph: Graph {
     fn selected(&self, node: Node<Self::Ctx>) -> bool;
+    fn change(&self, node: Node<Self::Ctx>) -> Option<Change>;
     fn clicked_edge(&mut self, edge: Edge<Self::Ctx>);
     fn clicked_operation(&mut self, op: Operation<Self::Ctx>, primary: bool);
     fn clicked_thunk(&mut self, thunk: Thunk<Self::Ctx>, primary: bool);
+    fn can_inline(&self, thunk: &Thunk<Self::Ctx>) -> bool;
+    fn inline_thunk(&mut self, thunk: Thunk<Self::Ctx>);
     fn extend(&mut self, nodes: impl Iterator<Item = Node<Self::Ctx>>);
 }
@@ -1,5 +1,6 @@
 use sd_core::hypergraph::{
     adapter::selectable::SelectableGraph,
+    diff::Change,
     generic::{Edge, Node, Operation, Thunk},
 };
 
@@ -11,6 +12,11 @@ impl<G: RenderableGraph> RenderableGraph for SelectableGraph<G> {
         *self.selected(&node)
     }
 
+    // Delegate to inner graph.
+    fn change(&self, node: Node<G::Ctx>) -> Option<Change> {
+        self.inner().change(node)
+    }
+
     // Delegate to inner graph.
     fn clicked_edge(&mut self, edge: Edge<G::Ctx>) {
         self.inner_mut().clicked_edge(edge);
@@ -26,6 +32,16 @@ impl<G: RenderableGraph> RenderableGraph for SelectableGraph<G> {
         *self.selected_mut(&Node::Thunk(thunk)) ^= true;
     }
 
+    // Delegate to inner graph.
+    fn can_inline(&self, thunk: &Thunk<G::Ctx>) -> bool {
+        self.inner().can_inline(thunk)
+    }
+
+    // Delegate to inner graph.
+    fn inline_thunk(&mut self, thunk: Thunk<G::Ctx>) {
+        self.inner_mut().inline_thunk(thunk);
+    }
+
     // Delegate to inner graph.
     fn extend(&mut self, nodes: impl Iterator<Item = Node<G::Ctx>>) {
         self.inner_mut().extend(nodes);
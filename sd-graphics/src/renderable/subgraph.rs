@@ -1,4 +1,5 @@
 use sd_core::hypergraph::{
+    diff::Change,
     generic::{Ctx, Node},
     subgraph::{SubEdge, SubNode, SubOperation, SubThunk, Subgraph},
 };
@@ -11,6 +12,11 @@ impl<T: Ctx> RenderableGraph for Subgraph<T> {
         false
     }
 
+    // Always none
+    fn change(&self, _node: SubNode<T>) -> Option<Change> {
+        None
+    }
+
     // Noop
     fn clicked_edge(&mut self, _edge: SubEdge<T>) {}
 
@@ -24,6 +30,13 @@ impl<T: Ctx> RenderableGraph for Subgraph<T> {
         self.remove(&Node::Thunk(thunk.into_inner()));
     }
 
+    fn can_inline(&self, _thunk: &SubThunk<T>) -> bool {
+        false
+    }
+
+    // Noop
+    fn inline_thunk(&mut self, _thunk: SubThunk<T>) {}
+
     // Delegate to inner graph.
     fn extend(&mut self, nodes: impl Iterator<Item = SubNode<T>>) {
         self.extend(nodes.map(SubNode::into_inner));
@@ -2,23 +2,28 @@ use derivative::Derivative;
 use egui::{
     emath::RectTransform,
     epaint::{CubicBezierShape, PathShape, RectShape},
-    vec2, Align2, Color32, Id, Pos2, Rect, Response, Rounding, Sense, Stroke, Vec2,
+    vec2, Align2, Color32, Id, Pos2, Rect, Rounding, Sense, Stroke, Vec2,
 };
 use flo_curves::bezier::{solve_curve_for_t_along_axis, Curve};
 use indexmap::IndexSet;
 use sd_core::{
     common::Matchable,
     hypergraph::{
+        diff::Change,
         generic::{Ctx, Node, Weight},
         traits::{Keyable, WireType, WithType, WithWeight},
     },
 };
 
 use crate::{
-    common::{to_coord2, ShapeKind, TEXT_SIZE, TOLERANCE},
+    common::{to_coord2, Orientation, ShapeKind, MIN_TEXT_SIZE, TEXT_SIZE},
     renderable::RenderableGraph,
+    spatial::SpatialIndex,
 };
 
+/// The size on screen of the text summarising a collapsed thunk.
+const SUMMARY_TEXT_SIZE: f32 = 12.0;
+
 #[derive(Derivative)]
 #[derivative(Clone(bound = ""))]
 pub enum Shape<T: Ctx> {
@@ -35,6 +40,10 @@ pub enum Shape<T: Ctx> {
         rect: Rect,
         addr: T::Thunk,
         stroke: Option<Stroke>,
+        /// The number of shapes following this one which are drawn inside the thunk.
+        contents: usize,
+        /// A summary of the thunk, drawn in place of its contents when zoomed out.
+        summary: Option<String>,
     },
     CircleFilled {
         center: Pos2,
@@ -51,11 +60,22 @@ pub enum Shape<T: Ctx> {
         fill: Option<Color32>,
         stroke: Option<Stroke>,
     },
+    WireLabel {
+        /// The point on the wire beside which the label is written.
+        anchor: Pos2,
+        /// The offset of the label from its wire.
+        offset: Vec2,
+        /// The height of the text.
+        size: f32,
+        label: String,
+        addr: T::Edge,
+    },
     Arrow {
         addr: T::Edge,
         to_add: Vec<Node<T>>,
         center: Pos2,
-        upwards: bool,
+        /// The direction in which the arrow points.
+        direction: Vec2,
         stroke: Option<Stroke>,
         height: f32,
     },
@@ -64,9 +84,120 @@ pub enum Shape<T: Ctx> {
 pub struct Shapes<T: Ctx> {
     pub shapes: Vec<Shape<T>>,
     pub size: Vec2,
+    /// An index of the bounding boxes of the shapes.
+    pub index: SpatialIndex,
+}
+
+impl<T: Ctx> Shapes<T> {
+    #[must_use]
+    pub fn new(shapes: Vec<Shape<T>>, size: Vec2) -> Self {
+        let mut shapes = Self {
+            shapes,
+            size,
+            index: SpatialIndex::default(),
+        };
+        shapes.reindex();
+        shapes
+    }
+
+    fn reindex(&mut self) {
+        let boxes: Vec<Rect> = self.shapes.iter().map(Shape::bounding_box).collect();
+        self.index = SpatialIndex::new(&boxes);
+    }
+
+    /// Turn shapes generated for a top-to-bottom diagram into the given orientation.
+    /// Labels stay upright.
+    pub fn orient(&mut self, orientation: Orientation) {
+        for shape in &mut self.shapes {
+            shape.orient(orientation, self.size);
+        }
+        self.size = orientation.transform_size(self.size);
+        self.reindex();
+    }
+
+    /// The shapes which match a search query, in reading order.
+    pub fn find<'a>(&'a self, query: &'a str) -> impl Iterator<Item = &'a Shape<T>> + 'a
+    where
+        T::Operation: Matchable,
+        T::Thunk: Matchable,
+    {
+        self.index
+            .iter()
+            .map(|i| &self.shapes[i])
+            .filter(move |shape| shape.is_match(query))
+    }
+
+    /// The transform onto an overview of the diagram, at most `size` across and inset by `margin`
+    /// from the bottom right corner of `area`.
+    /// There is no overview when the whole diagram is within `viewport`.
+    #[must_use]
+    pub fn overview(
+        &self,
+        viewport: Rect,
+        area: Rect,
+        size: f32,
+        margin: f32,
+    ) -> Option<RectTransform> {
+        let bounds = Rect::from_min_size(Pos2::ZERO, self.size);
+        if viewport.contains_rect(bounds) || self.size.min_elem() <= 0.0 {
+            return None;
+        }
+        let size = self.size * (size / self.size.max_elem());
+        let overview = Rect::from_min_size(area.max - size - Vec2::splat(margin), size);
+        Some(RectTransform::from_to(bounds, overview))
+    }
 }
 
 impl<T: Ctx> Shape<T> {
+    fn orient(&mut self, orientation: Orientation, size: Vec2) {
+        match self {
+            Shape::Line { start, end, .. } => {
+                *start = orientation.transform_pos(*start, size);
+                *end = orientation.transform_pos(*end, size);
+            }
+            Shape::CubicBezier { points, .. } => {
+                for point in points {
+                    *point = orientation.transform_pos(*point, size);
+                }
+            }
+            Shape::Rectangle { rect, .. } => {
+                *rect = orientation.transform_rect(*rect, size);
+            }
+            Shape::CircleFilled { center, .. } => {
+                *center = orientation.transform_pos(*center, size);
+            }
+            Shape::Operation { center, kind, .. } => {
+                *center = orientation.transform_pos(*center, size);
+                *kind = kind.orient(orientation);
+            }
+            Shape::WireLabel { anchor, offset, .. } => {
+                *anchor = orientation.transform_pos(*anchor, size);
+                *offset = orientation.transform_vec(*offset);
+            }
+            Shape::Arrow {
+                center, direction, ..
+            } => {
+                *center = orientation.transform_pos(*center, size);
+                *direction = orientation.transform_vec(*direction);
+            }
+        }
+    }
+
+    /// Where the text of a wire label is aligned, on the side of the wire given by `offset`.
+    pub(crate) fn label_align(offset: Vec2) -> Align2 {
+        if offset.x.abs() >= offset.y.abs() {
+            if offset.x >= 0.0 {
+                Align2::LEFT_CENTER
+            } else {
+                Align2::RIGHT_CENTER
+            }
+        } else if offset.y >= 0.0 {
+            Align2::CENTER_TOP
+        } else {
+            Align2::CENTER_BOTTOM
+        }
+    }
+
     pub(crate) fn apply_transform(&mut self, transform: &RectTransform) {
         match self {
             Shape::Line { start, end, .. } => {
@@ -86,6 +217,16 @@ impl<T: Ctx> Shape<T> {
                 *center = transform.transform_pos(*center);
                 *radius *= transform.scale().min_elem(); // NOTE(calintat): should this be length?
             }
+            Shape::WireLabel {
+                anchor,
+                offset,
+                size,
+                ..
+            } => {
+                *anchor = transform.transform_pos(*anchor);
+                *offset *= transform.scale().min_elem();
+                *size *= transform.scale().min_elem();
+            }
             Shape::Arrow { center, height, .. } => {
                 *center = transform.transform_pos(*center);
                 *height *= transform.scale().min_elem();
@@ -100,33 +241,20 @@ impl<T: Ctx> Shape<T> {
         graph: &mut G,
         id: Id,
         ui: &egui::Ui,
-        response: &Response,
         transform: &RectTransform,
         search: Option<&str>,
         highlight_op: &mut Option<T::Operation>,
-        highlight_edges: &mut IndexSet<T::Edge>,
     ) where
         G: RenderableGraph<Ctx = T>,
         T::Operation: Matchable,
         T::Thunk: Matchable,
     {
         let bounds = *transform.to();
-        let tolerance = TOLERANCE * transform.scale().min_elem();
 
         let bounding_box = self.bounding_box();
 
-        if let Some(hover_pos) = response.hover_pos() {
-            if self.contains_point(hover_pos, tolerance) {
-                match self {
-                    Shape::Line { addr, .. } | Shape::CubicBezier { addr, .. } => {
-                        highlight_edges.insert(addr.clone());
-                    }
-                    _ => {}
-                }
-            }
-        }
         match self {
-            Shape::Line { .. } | Shape::CubicBezier { .. } => {}
+            Shape::Line { .. } | Shape::CubicBezier { .. } | Shape::WireLabel { .. } => {}
             Shape::CircleFilled { addr, coord, .. } => {
                 let circle_response = ui.interact(
                     bounding_box.intersect(bounds),
@@ -153,6 +281,9 @@ impl<T: Ctx> Shape<T> {
                 if !selected {
                     new_stroke.color = new_stroke.color.gamma_multiply(0.35);
                 }
+                if let Some(change) = graph.change(Node::Thunk(addr.clone())) {
+                    new_stroke.color = change_color(change);
+                }
                 if search_match {
                     new_stroke.color = Color32::LIGHT_RED;
                 }
@@ -164,6 +295,15 @@ impl<T: Ctx> Shape<T> {
                 if thunk_response.secondary_clicked() {
                     graph.clicked_thunk(addr.clone(), false);
                 }
+                // Right clicking still selects the thunk, as well as offering to inline it if possible
+                if graph.can_inline(addr) {
+                    thunk_response.context_menu(|ui| {
+                        if ui.button("Inline").clicked() {
+                            graph.inline_thunk(addr.clone());
+                            ui.close_menu();
+                        }
+                    });
+                }
             }
             Shape::Operation {
                 addr, fill, stroke, ..
@@ -181,16 +321,23 @@ impl<T: Ctx> Shape<T> {
                 if op_response.secondary_clicked() {
                     graph.clicked_operation(addr.clone(), false);
                 }
-                *fill = Some(
-                    ui.style()
-                        .interact_selectable(&op_response, selected)
-                        .bg_fill,
-                );
+                // The colours of the style sheet give way to those of selected or hovered operations
+                let visuals = ui.style().interact_selectable(&op_response, selected);
+                let styled = !selected && !op_response.hovered();
+                *fill = Some(fill.filter(|_| styled).unwrap_or(visuals.bg_fill));
                 *stroke = Some(
-                    ui.style()
-                        .interact_selectable(&op_response, selected)
-                        .fg_stroke,
+                    stroke
+                        .filter(|_| styled)
+                        .map_or(visuals.fg_stroke, |stroke| {
+                            Stroke::new(visuals.fg_stroke.width, stroke.color)
+                        }),
                 );
+                if let Some(change) = graph.change(Node::Operation(addr.clone())) {
+                    if !selected {
+                        *fill = Some(change_color(change).gamma_multiply(0.5));
+                    }
+                    stroke.as_mut().unwrap().color = change_color(change);
+                }
                 if search_match {
                     *fill = Some(Color32::DARK_RED);
                     stroke.as_mut().unwrap().color = Color32::LIGHT_RED;
@@ -258,6 +405,13 @@ impl<T: Ctx> Shape<T> {
                         stroke.color = Color32::DARK_GREEN;
                     }
                 }
+                WireType::Classical => {
+                    if highlighted {
+                        stroke.color = Color32::LIGHT_BLUE;
+                    } else {
+                        stroke.color = Color32::BLUE;
+                    }
+                }
             }
             stroke
         };
@@ -278,12 +432,44 @@ impl<T: Ctx> Shape<T> {
                 );
                 egui::Shape::CubicBezier(bezier)
             }
-            Shape::Rectangle { rect, stroke, .. } => egui::Shape::Rect(RectShape::new(
+            Shape::Rectangle {
+                rect,
+                stroke,
+                summary: None,
+                ..
+            } => egui::Shape::Rect(RectShape::new(
                 rect,
                 Rounding::ZERO,
                 Color32::default(),
                 stroke.unwrap_or(default_stroke),
             )),
+            Shape::Rectangle {
+                rect,
+                stroke,
+                summary: Some(summary),
+                ..
+            } => {
+                let stroke = stroke.unwrap_or(default_stroke);
+                let mut shapes = vec![egui::Shape::Rect(RectShape::new(
+                    rect,
+                    Rounding::ZERO,
+                    ui.visuals().widgets.inactive.bg_fill,
+                    stroke,
+                ))];
+                let galley = ui.fonts(|fonts| {
+                    fonts.layout_no_wrap(
+                        summary,
+                        egui::FontId::monospace(SUMMARY_TEXT_SIZE),
+                        ui.visuals().strong_text_color(),
+                    )
+                });
+                // Only label boxes which are large enough to hold the summary
+                if rect.size().x >= galley.size().x && rect.size().y >= galley.size().y {
+                    let pos = rect.center() - galley.size() / 2.0;
+                    shapes.push(egui::Shape::galley(pos, galley, stroke.color));
+                }
+                egui::Shape::Vec(shapes)
+            }
             Shape::CircleFilled {
                 center,
                 radius,
@@ -312,7 +498,7 @@ impl<T: Ctx> Shape<T> {
                     stroke.unwrap_or(default_stroke),
                 ));
                 let text_size: f32 = TEXT_SIZE * transform.scale().min_elem();
-                if text_size <= 5.0 {
+                if text_size <= MIN_TEXT_SIZE {
                     return rect;
                 }
                 let text = ui.fonts(|fonts| {
@@ -327,9 +513,31 @@ impl<T: Ctx> Shape<T> {
                 });
                 egui::Shape::Vec(vec![rect, text])
             }
+            Shape::WireLabel {
+                anchor,
+                offset,
+                size,
+                label,
+                addr,
+            } => {
+                if size <= MIN_TEXT_SIZE {
+                    return egui::Shape::Noop;
+                }
+                let stroke = wire_stroke(highlight_edges.contains(&addr), addr.weight().get_type());
+                ui.fonts(|fonts| {
+                    egui::Shape::text(
+                        fonts,
+                        anchor + offset,
+                        Self::label_align(offset),
+                        label,
+                        egui::FontId::monospace(size),
+                        stroke.color,
+                    )
+                })
+            }
             Shape::Arrow {
                 center,
-                upwards,
+                direction,
                 height,
                 stroke,
                 ..
@@ -338,17 +546,12 @@ impl<T: Ctx> Shape<T> {
 
                 let fill = stroke.color;
 
-                let vertical_offset = if upwards { height } else { -height };
-
-                let left = center + Vec2::new(-height / 2.0, vertical_offset);
-                let right = center + Vec2::new(height / 2.0, vertical_offset);
+                // The base of the arrow lies behind its tip.
+                let base = center - direction * height;
+                let across = direction.rot90() * height / 2.0;
 
                 // Paths should be clockwise
-                let points = if upwards {
-                    vec![left, center, right]
-                } else {
-                    vec![left, right, center]
-                };
+                let points = vec![base + across, center, base - across];
 
                 egui::Shape::Path(PathShape {
                     points,
@@ -368,6 +571,7 @@ impl<T: Ctx> Shape<T> {
             Shape::CircleFilled { center, .. }
             | Shape::Operation { center, .. }
             | Shape::Arrow { center, .. } => *center,
+            Shape::WireLabel { anchor, .. } => *anchor,
         }
     }
 
@@ -388,6 +592,16 @@ impl<T: Ctx> Shape<T> {
                 *center,
                 *radius * vec2(label.chars().count() as f32 + 1.0, 2.0),
             ),
+            Shape::WireLabel {
+                anchor,
+                offset,
+                size,
+                label,
+                ..
+            } => Self::label_align(*offset).anchor_size(
+                *anchor + *offset,
+                *size * vec2(label.chars().count() as f32, 1.0),
+            ),
             Shape::Arrow { center, height, .. } => {
                 Rect::from_center_size(*center, Vec2::splat(*height * 5.0))
             }
@@ -425,6 +639,15 @@ impl<T: Ctx> Shape<T> {
     }
 }
 
+/// Colour of nodes which differ from another version of the graph.
+const fn change_color(change: Change) -> Color32 {
+    match change {
+        Change::Added => Color32::from_rgb(0x4c, 0xaf, 0x50),
+        Change::Removed => Color32::from_rgb(0xe5, 0x39, 0x35),
+        Change::Changed => Color32::from_rgb(0xff, 0xa7, 0x26),
+    }
+}
+
 impl<T: Ctx> Matchable for Shape<T>
 where
     T::Operation: Matchable,
@@ -438,3 +661,40 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, vec2, Rect};
+    use sd_core::examples::DummyCtx;
+
+    use super::Shapes;
+
+    #[test]
+    fn overview() {
+        let shapes = Shapes::<DummyCtx>::new(vec![], vec2(20.0, 10.0));
+        let area = Rect::from_min_size(pos2(0.0, 0.0), vec2(400.0, 300.0));
+
+        // The whole diagram is already visible
+        let viewport = Rect::from_min_size(pos2(-1.0, -1.0), vec2(30.0, 30.0));
+        assert!(shapes.overview(viewport, area, 100.0, 10.0).is_none());
+
+        let viewport = Rect::from_min_size(pos2(5.0, 5.0), vec2(4.0, 4.0));
+        let to_overview = shapes.overview(viewport, area, 100.0, 10.0).unwrap();
+        assert_eq!(
+            *to_overview.to(),
+            Rect::from_min_max(pos2(290.0, 240.0), pos2(390.0, 290.0))
+        );
+        // Points on the overview map back to the same point of the diagram
+        assert_eq!(
+            to_overview.transform_pos(pos2(10.0, 5.0)),
+            pos2(340.0, 265.0)
+        );
+        assert_eq!(
+            to_overview.inverse().transform_pos(pos2(340.0, 265.0)),
+            pos2(10.0, 5.0)
+        );
+
+        let empty = Shapes::<DummyCtx>::new(vec![], vec2(0.0, 10.0));
+        assert!(empty.overview(viewport, area, 100.0, 10.0).is_none());
+    }
+}
@@ -0,0 +1,189 @@
+use std::{collections::HashSet, ops::RangeInclusive};
+
+use egui::{vec2, Rect, Vec2};
+
+/// A uniform grid over the bounding boxes of a collection of shapes,
+/// for finding the shapes in a region without scanning all of them.
+#[derive(Clone, Debug)]
+pub struct SpatialIndex {
+    /// The number of shapes indexed.
+    len: usize,
+    bounds: Rect,
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    /// For each cell in row-major order, the indices of the shapes whose bounding boxes meet it.
+    cells: Vec<Vec<usize>>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            bounds: Rect::NOTHING,
+            cell_size: Self::MIN_CELL_SIZE,
+            columns: 0,
+            rows: 0,
+            cells: Vec::default(),
+        }
+    }
+}
+
+impl SpatialIndex {
+    /// The smallest side of a cell, in layout units.
+    const MIN_CELL_SIZE: f32 = 1.0;
+
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_precision_loss)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn new(boxes: &[Rect]) -> Self {
+        let bounds = boxes.iter().copied().fold(Rect::NOTHING, Rect::union);
+        if !bounds.is_finite() || bounds.is_negative() {
+            return Self::default();
+        }
+
+        // Aim for around one shape per cell
+        let cell_size = (bounds.area() / boxes.len() as f32)
+            .sqrt()
+            .max(Self::MIN_CELL_SIZE);
+        let mut index = Self {
+            len: boxes.len(),
+            bounds,
+            cell_size,
+            columns: (bounds.width() / cell_size) as usize + 1,
+            rows: (bounds.height() / cell_size) as usize + 1,
+            cells: Vec::default(),
+        };
+        index.cells = vec![Vec::default(); index.columns * index.rows];
+        for (i, rect) in boxes.iter().enumerate() {
+            if let Some((columns, rows)) = index.cell_range(*rect) {
+                for row in rows {
+                    for column in columns.clone() {
+                        index.cells[row * index.columns + column].push(i);
+                    }
+                }
+            }
+        }
+        index
+    }
+
+    /// The columns and rows of the cells which meet `rect`.
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    fn cell_range(&self, rect: Rect) -> Option<(RangeInclusive<usize>, RangeInclusive<usize>)> {
+        let rect = rect.intersect(self.bounds);
+        if self.cells.is_empty() || rect.is_negative() {
+            return None;
+        }
+        let cell =
+            |x: f32, min: f32, len: usize| (((x - min) / self.cell_size) as usize).min(len - 1);
+        Some((
+            cell(rect.min.x, self.bounds.min.x, self.columns)
+                ..=cell(rect.max.x, self.bounds.min.x, self.columns),
+            cell(rect.min.y, self.bounds.min.y, self.rows)
+                ..=cell(rect.max.y, self.bounds.min.y, self.rows),
+        ))
+    }
+
+    /// The indices of the shapes whose bounding boxes may meet `rect`, in increasing order.
+    #[must_use]
+    pub fn query(&self, rect: Rect) -> Vec<usize> {
+        if rect.contains_rect(self.bounds) {
+            return (0..self.len).collect();
+        }
+        let Some((columns, rows)) = self.cell_range(rect) else {
+            return Vec::default();
+        };
+        let mut indices: Vec<usize> = rows
+            .flat_map(|row| {
+                columns
+                    .clone()
+                    .flat_map(move |column| &self.cells[row * self.columns + column])
+            })
+            .copied()
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    /// Each cell of the grid, with the number of shapes which meet it.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn density(&self) -> impl Iterator<Item = (Rect, usize)> + '_ {
+        self.cells.iter().enumerate().map(|(i, cell)| {
+            let offset = vec2((i % self.columns) as f32, (i / self.columns) as f32);
+            let min = self.bounds.min + offset * self.cell_size;
+            (
+                Rect::from_min_size(min, Vec2::splat(self.cell_size)),
+                cell.len(),
+            )
+        })
+    }
+
+    /// The indices of all the shapes, ordered by the first cell they meet, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut seen = HashSet::new();
+        self.cells
+            .iter()
+            .flatten()
+            .copied()
+            .filter(move |i| seen.insert(*i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, vec2, Rect};
+
+    use super::SpatialIndex;
+
+    #[test]
+    fn spatial_index() {
+        let boxes = [
+            Rect::from_min_size(pos2(0.0, 0.0), vec2(1.0, 1.0)),
+            Rect::from_min_size(pos2(8.0, 0.0), vec2(1.0, 1.0)),
+            Rect::from_min_size(pos2(0.0, 8.0), vec2(9.0, 0.0)),
+            Rect::from_min_size(pos2(4.0, 0.2), vec2(0.2, 0.2)),
+        ];
+        let index = SpatialIndex::new(&boxes);
+
+        assert_eq!(index.query(Rect::EVERYTHING), vec![0, 1, 2, 3]);
+        assert_eq!(
+            index.query(Rect::from_min_size(pos2(7.5, 0.5), vec2(0.1, 0.1))),
+            vec![1]
+        );
+        assert_eq!(
+            index.query(Rect::from_min_size(pos2(5.0, 7.5), vec2(1.0, 1.0))),
+            vec![2]
+        );
+        assert!(index
+            .query(Rect::from_min_size(pos2(20.0, 20.0), vec2(1.0, 1.0)))
+            .is_empty());
+        assert_eq!(index.iter().collect::<Vec<_>>(), vec![0, 3, 1, 2]);
+    }
+
+    #[test]
+    fn density() {
+        let boxes = [
+            Rect::from_min_size(pos2(0.0, 0.0), vec2(1.0, 1.0)),
+            Rect::from_min_size(pos2(0.2, 0.2), vec2(0.5, 0.5)),
+            Rect::from_min_size(pos2(3.5, 3.5), vec2(0.2, 0.2)),
+        ];
+        let index = SpatialIndex::new(&boxes);
+        let density: Vec<_> = index.density().collect();
+
+        // The cells tile the bounds of the shapes, and count every shape meeting them
+        assert_eq!(density.len(), index.columns * index.rows);
+        let covered = density
+            .iter()
+            .fold(Rect::NOTHING, |covered, (cell, _)| covered.union(*cell));
+        assert!(covered.contains_rect(index.bounds));
+        for (cell, n) in &density {
+            let meeting = boxes.iter().filter(|b| b.intersects(*cell)).count();
+            assert_eq!(*n, meeting, "{cell:?}");
+        }
+        assert_eq!(density[0].1, 2);
+        assert_eq!(density.iter().map(|(_, n)| n).sum::<usize>(), 3);
+    }
+}
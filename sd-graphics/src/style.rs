@@ -0,0 +1,182 @@
+//! Style sheets, which change how operations are drawn depending on their names.
+//!
+//! A style sheet is written in TOML as a list of rules, for example:
+//!
+//! ```toml
+//! [[rule]]
+//! match = "arith.*"
+//! shape = "square"
+//! fill = "#fde68a"
+//! stroke = "#92400e"
+//!
+//! [[rule]]
+//! match = "lambda"
+//! label = "fun"
+//! glyph = "λ"
+//! ```
+//!
+//! A rule applies to an operation when its pattern matches either the label of the operation
+//! or its textual name (e.g. `lambda` in spartan or `apply/2` in chil), where `*` stands for
+//! any sequence of characters. When several rules apply, later rules take precedence.
+
+use std::{fmt::Display, str::FromStr};
+
+use egui::Color32;
+use sd_core::{common::glob, hypergraph::traits::WithWeight};
+use serde::{de::Error, Deserialize, Deserializer};
+
+use crate::common::{ShapeKind, Shapeable};
+
+/// How to draw an operation, where each field which is not set is left as the default.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Style {
+    pub shape: Option<ShapeKind>,
+    pub fill: Option<Color32>,
+    pub stroke: Option<Color32>,
+    /// Text written on the operation instead of its label.
+    pub label: Option<String>,
+    /// A symbol written before the label.
+    pub glyph: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rule {
+    #[serde(rename = "match")]
+    pattern: String,
+    shape: Option<ShapeKind>,
+    #[serde(default, deserialize_with = "colour")]
+    fill: Option<Color32>,
+    #[serde(default, deserialize_with = "colour")]
+    stroke: Option<Color32>,
+    label: Option<String>,
+    glyph: Option<String>,
+}
+
+fn colour<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Color32>, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    Color32::from_hex(&hex)
+        .map(Some)
+        .map_err(|_| D::Error::custom(format!("invalid colour `{hex}`")))
+}
+
+impl Style {
+    /// Override the fields of this style with those set by `rule`.
+    fn apply(&mut self, rule: &Rule) {
+        if rule.shape.is_some() {
+            self.shape = rule.shape;
+        }
+        if rule.fill.is_some() {
+            self.fill = rule.fill;
+        }
+        if rule.stroke.is_some() {
+            self.stroke = rule.stroke;
+        }
+        if rule.label.is_some() {
+            self.label.clone_from(&rule.label);
+        }
+        if rule.glyph.is_some() {
+            self.glyph.clone_from(&rule.glyph);
+        }
+    }
+
+    /// The text written on an operation with the given label.
+    #[must_use]
+    pub fn text(&self, label: &str) -> String {
+        let label = self.label.as_deref().unwrap_or(label);
+        match &self.glyph {
+            Some(glyph) if label.is_empty() => glyph.clone(),
+            Some(glyph) => format!("{glyph} {label}"),
+            None => label.to_owned(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StyleSheet {
+    #[serde(default, rename = "rule")]
+    rules: Vec<Rule>,
+}
+
+impl FromStr for StyleSheet {
+    type Err = toml::de::Error;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        toml::from_str(source)
+    }
+}
+
+impl StyleSheet {
+    /// The style of an operation with the given label and textual name.
+    #[must_use]
+    pub fn style(&self, label: &str, name: &str) -> Style {
+        let mut style = Style::default();
+        for rule in &self.rules {
+            if glob(&rule.pattern, label) || glob(&rule.pattern, name) {
+                style.apply(rule);
+            }
+        }
+        style
+    }
+
+    /// The style of an operation and the text written on it.
+    pub(crate) fn operation<O>(&self, op: &O) -> (Style, String)
+    where
+        O: Shapeable + WithWeight,
+        O::Weight: Display,
+    {
+        let label = op.weight().to_string();
+        let style = self.style(&label, &op.name());
+        let text = style.text(&label);
+        (style, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Color32;
+
+    use super::StyleSheet;
+    use crate::common::ShapeKind;
+
+    #[test]
+    fn style_sheet() {
+        let style_sheet: StyleSheet = r##"
+[[rule]]
+match = "arith.*"
+shape = "square"
+fill = "#ff0000"
+
+[[rule]]
+match = "arith.addi"
+fill = "#00ff00"
+glyph = "+"
+
+[[rule]]
+match = "apply/*"
+label = "app"
+"##
+        .parse()
+        .unwrap();
+
+        let addi = style_sheet.style("arith.addi", "arith.addi");
+        assert_eq!(addi.shape, Some(ShapeKind::Square));
+        assert_eq!(addi.fill, Some(Color32::GREEN));
+        assert_eq!(addi.text("arith.addi"), "+ arith.addi");
+
+        let muli = style_sheet.style("arith.muli", "arith.muli");
+        assert_eq!(muli.fill, Some(Color32::RED));
+        assert_eq!(muli.text("arith.muli"), "arith.muli");
+
+        assert_eq!(style_sheet.style("@", "apply/2").text("@"), "app");
+        assert_eq!(style_sheet.style("λ", "lambda"), Default::default());
+
+        assert!("[[rule]]\nmatch = \"x\"\nfill = \"red\""
+            .parse::<StyleSheet>()
+            .is_err());
+        assert!("[[rule]]\nmatch = \"x\"\ncolour = \"#fff\""
+            .parse::<StyleSheet>()
+            .is_err());
+    }
+}
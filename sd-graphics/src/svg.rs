@@ -1,4 +1,4 @@
-use egui::{emath::RectTransform, Pos2, Rect};
+use egui::{emath::RectTransform, vec2, Align, Pos2, Rect, Rounding};
 use sd_core::hypergraph::generic::Ctx;
 use svg::{
     node::element::{path::Data, Circle, Group, Line, Path, Rectangle, Text},
@@ -14,21 +14,26 @@ impl<T: Ctx> Shape<T> {
                 center,
                 radius,
                 label,
+                kind,
+                fill,
+                stroke,
                 ..
             } => {
                 let x_size = radius * (label.chars().count().max(1) as f32 + 1.0);
+                let rect = Rect::from_center_size(*center, vec2(x_size, radius * 2.0));
                 Box::new(
                     Group::new()
                         .add(
-                            Rectangle::new()
-                                .set("x", center.x - x_size / 2.0)
-                                .set("y", center.y - radius)
-                                .set("width", x_size)
-                                .set("height", radius * 2.0)
-                                .set("rx", *radius)
-                                .set("ry", *radius)
-                                .set("fill", "white")
-                                .set("stroke", "black")
+                            Path::new()
+                                .set("d", rounded_rect(rect, kind.into_rounding(*radius)))
+                                .set(
+                                    "fill",
+                                    fill.map_or_else(|| "white".to_owned(), |c| c.to_hex()),
+                                )
+                                .set(
+                                    "stroke",
+                                    stroke.map_or_else(|| "black".to_owned(), |s| s.color.to_hex()),
+                                )
                                 .set("stroke-width", 1),
                         )
                         .add(
@@ -85,6 +90,39 @@ impl<T: Ctx> Shape<T> {
                     .set("stroke", "black")
                     .set("stroke-width", 1)
             }),
+            Self::WireLabel {
+                anchor,
+                offset,
+                size,
+                label,
+                ..
+            } => {
+                let align = Self::label_align(*offset);
+                let pos = *anchor + *offset;
+                Box::new(
+                    Text::new(html_escape::encode_text(label))
+                        .set("x", pos.x)
+                        .set("y", pos.y)
+                        .set("font-size", *size)
+                        .set("font-family", "monospace")
+                        .set(
+                            "text-anchor",
+                            match align.x() {
+                                Align::Min => "start",
+                                Align::Center => "middle",
+                                Align::Max => "end",
+                            },
+                        )
+                        .set(
+                            "dominant-baseline",
+                            match align.y() {
+                                Align::Min => "hanging",
+                                Align::Center => "middle",
+                                Align::Max => "text-after-edge",
+                            },
+                        ),
+                )
+            }
             Self::Arrow { .. } => {
                 panic!("Arrows should not be in svgs")
             }
@@ -92,6 +130,22 @@ impl<T: Ctx> Shape<T> {
     }
 }
 
+/// The outline of a rectangle with rounded corners.
+fn rounded_rect(rect: Rect, rounding: Rounding) -> Data {
+    let Rounding { nw, ne, sw, se } = rounding;
+    Data::new()
+        .move_to((rect.min.x + nw, rect.min.y))
+        .line_to((rect.max.x - ne, rect.min.y))
+        .elliptical_arc_to((ne, ne, 0, 0, 1, rect.max.x, rect.min.y + ne))
+        .line_to((rect.max.x, rect.max.y - se))
+        .elliptical_arc_to((se, se, 0, 0, 1, rect.max.x - se, rect.max.y))
+        .line_to((rect.min.x + sw, rect.max.y))
+        .elliptical_arc_to((sw, sw, 0, 0, 1, rect.min.x, rect.max.y - sw))
+        .line_to((rect.min.x, rect.min.y + nw))
+        .elliptical_arc_to((nw, nw, 0, 0, 1, rect.min.x + nw, rect.min.y))
+        .close()
+}
+
 impl<T: Ctx> Shapes<T> {
     const SCALE: f32 = 50.0;
 
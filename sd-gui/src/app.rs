@@ -14,20 +14,19 @@ use eframe::{
 };
 use egui_notify::Toasts;
 use poll_promise::Promise;
-use sd_core::{
-    common::Direction,
-    dot::{dot_to_graph, DotSettings},
-    language::mlir::MlirSettings,
-    lp::Solver,
-};
+use sd_core::{common::Direction, lp::Solver, monoidal::term::TermFormat};
+use sd_graphics::common::Orientation;
 
 use crate::{
     code_generator::clear_code_cache,
     code_ui::code_ui,
     graph_ui::GraphUi,
-    parser::{parse, ParseError, ParseOutput, UiLanguage},
+    languages::{load_language, set_dialects, Settings, UiLanguage},
+    parser::{ParseError, ParseOutput},
     selection::Selection,
-    shape_generator::clear_shape_cache,
+    session::{GraphState, SelectionState, Session},
+    shape_generator::{clear_shape_cache, set_style_sheet},
+    source_viewer::SourceViewer,
     squiggly_line::show_parse_error,
 };
 
@@ -36,6 +35,15 @@ enum Message {
     Compile,
     SetLanguage(UiLanguage),
     ParseError(ParseError),
+    Diff(String),
+    Session(String),
+    StyleSheet(String),
+    Dialects(String),
+    LoadLanguage {
+        name: String,
+        grammar: String,
+        mapping: String,
+    },
 }
 
 pub struct App {
@@ -44,17 +52,30 @@ pub struct App {
     rx: Receiver<Message>,
     about: bool,
     editor: bool,
+    inspector: bool,
+    // whether the inspector is shown as a side panel rather than a floating window
+    inspector_docked: bool,
+    source: bool,
+    source_viewer: SourceViewer,
     code: Arc<Mutex<String>>,
     last_parse: Option<Arc<Mutex<Promise<Option<ParseOutput>>>>>,
     last_parse_error: Option<ParseError>,
     language: UiLanguage,
-    dot_settings: DotSettings,
-    mlir_settings: MlirSettings,
+    settings: Settings,
     graph_ui: Option<Promise<anyhow::Result<GraphUi>>>,
+    // code and graph of the version being compared against
+    diff_code: Option<String>,
+    diff_ui: Option<Promise<anyhow::Result<GraphUi>>>,
+    diffed: bool,
     selections: Vec<Selection>,
+    // graph state of a restored session, applied once the graph has been compiled
+    pending_state: Option<GraphState>,
     find: Option<(String, usize)>,
     toasts: Toasts,
     solver: Solver,
+    orientation: Orientation,
+    wire_labels: bool,
+    exact_swaps: bool,
 }
 
 impl App {
@@ -64,12 +85,6 @@ impl App {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
-        // Load previous app state (if any).
-        // Note that you must enable the `persistence` feature for this to work.
-        // if let Some(storage) = cc.storage {
-        //     return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
-        // }
-
         let font_name = "mono_font".to_owned();
 
         let mut font_definitions = FontDefinitions::default();
@@ -88,27 +103,128 @@ impl App {
         cc.egui_ctx.set_fonts(font_definitions);
 
         let (tx, rx) = channel();
-        Self {
+        let mut app = Self {
             tx,
             rx,
             about: Default::default(),
             editor: Default::default(),
+            inspector: Default::default(),
+            inspector_docked: true,
+            source: Default::default(),
+            source_viewer: SourceViewer::default(),
             code: Arc::default(),
             last_parse: Option::default(),
             last_parse_error: Option::default(),
             language: UiLanguage::default(),
-            dot_settings: DotSettings::default(),
-            mlir_settings: MlirSettings::default(),
+            settings: Settings::default(),
             graph_ui: Option::default(),
+            diff_code: None,
+            diff_ui: None,
+            diffed: false,
             selections: Vec::default(),
+            pending_state: None,
             find: None,
             toasts: Toasts::default(),
             solver,
+            orientation: Orientation::default(),
+            wire_labels: false,
+            exact_swaps: false,
+        };
+
+        // Load previous session (if any).
+        if let Some(session) = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<Session>(storage, eframe::APP_KEY))
+        {
+            app.restore_session(session);
         }
+        app
+    }
+
+    /// The current state of the visualiser.
+    fn session(&self) -> Session {
+        let graph = finished(&self.graph_ui).map(|graph_ui| GraphState {
+            interactive: graph_ui.state(),
+            panzoom: graph_ui.panzoom(),
+            selections: self
+                .selections
+                .iter()
+                .map(|selection| SelectionState {
+                    name: selection.name().to_owned(),
+                    displayed: selection.is_displayed(),
+                    nodes: selection.nodes().to_vec(),
+                })
+                .collect(),
+        });
+        Session {
+            code: self.code.lock().unwrap().clone(),
+            language: self.language,
+            settings: self.settings.clone(),
+            orientation: self.orientation,
+            wire_labels: self.wire_labels,
+            exact_swaps: self.exact_swaps,
+            graph,
+        }
+    }
+
+    /// Replace the current state with a saved session, recompiling its code.
+    fn restore_session(&mut self, session: Session) {
+        *self.code.lock().unwrap() = session.code;
+        self.language = session.language;
+        self.settings = session.settings;
+        self.orientation = session.orientation;
+        self.wire_labels = session.wire_labels;
+        self.exact_swaps = session.exact_swaps;
+        self.pending_state = session.graph;
+        self.tx
+            .send(Message::Compile)
+            .expect("failed to send message");
+    }
+
+    /// Lay out every graph being shown with the chosen layout settings.
+    fn apply_layout(&mut self) {
+        for graph_ui in [&mut self.graph_ui, &mut self.diff_ui] {
+            if let Some(graph_ui) = finished_mut(graph_ui) {
+                graph_ui.set_orientation(self.orientation);
+                graph_ui.set_wire_labels(self.wire_labels);
+                graph_ui.set_exact_swaps(self.exact_swaps);
+            }
+        }
+        for selection in &mut self.selections {
+            selection.set_orientation(self.orientation);
+            selection.set_wire_labels(self.wire_labels);
+            selection.set_exact_swaps(self.exact_swaps);
+        }
+    }
+
+    /// Apply the graph state of a restored session once the graph is compiled.
+    fn restore_graph_state(&mut self) {
+        if self.pending_state.is_none() {
+            return;
+        }
+        let Some(graph_ui) = finished_mut(&mut self.graph_ui) else {
+            return;
+        };
+        let state = self.pending_state.take().unwrap();
+        for selection in state.selections {
+            graph_ui.select(&selection.nodes);
+            if let Some(mut sel) = graph_ui.selection(selection.name, self.solver) {
+                *sel.displayed() = selection.displayed;
+                self.selections.push(sel);
+            }
+        }
+        graph_ui.restore(&state.interactive);
+        graph_ui.set_panzoom(state.panzoom);
+    }
+
+    /// Minimise swaps exactly (for small enough graphs) rather than heuristically.
+    pub fn set_exact_swaps(&mut self, exact_swaps: bool) {
+        self.exact_swaps = exact_swaps;
     }
 
     pub fn set_file(&mut self, code: &str, language: Option<UiLanguage>) {
         *self.code.lock().unwrap() = code.to_string();
+        self.pending_state = None;
         if let Some(language) = language {
             self.tx
                 .send(Message::SetLanguage(language))
@@ -126,13 +242,13 @@ impl App {
             tracing::trace!("code changed changed");
             self.trigger_parse(ui.ctx(), false);
         }
-        if let Some(error) = &self.last_parse_error {
-            match error {
-                ParseError::Chil(err) => show_parse_error(ui, err, &text_edit_out),
-                ParseError::Mlir(err) => show_parse_error(ui, err, &text_edit_out),
-                ParseError::Spartan(err) => show_parse_error(ui, err, &text_edit_out),
-                ParseError::Dot(_) | ParseError::Conversion(_) => (),
-            }
+        if let Some(ParseError::Syntax {
+            message,
+            line_col: Some(line_col),
+            ..
+        }) = &self.last_parse_error
+        {
+            show_parse_error(ui, line_col, message, &text_edit_out);
         }
     }
 
@@ -154,7 +270,7 @@ impl App {
         self.last_parse
             .replace(Arc::new(Mutex::new(crate::spawn!("parse", {
                 let guard = code.lock().unwrap();
-                let parsed = parse(&guard, language);
+                let parsed = language.parse(&guard);
                 match parsed {
                     Ok(parse) => {
                         ctx.request_repaint();
@@ -177,8 +293,7 @@ impl App {
         {
             let parse = self.last_parse.as_ref().unwrap().clone();
             let ctx = ctx.clone();
-            let dot_settings = self.dot_settings;
-            let mlir_settings = self.mlir_settings;
+            let settings = self.settings.clone();
             let solver = self.solver;
             self.graph_ui.replace(crate::spawn!("compile", {
                 let promise = parse.lock().unwrap();
@@ -186,32 +301,35 @@ impl App {
                     .block_until_ready()
                     .as_ref()
                     .ok_or_else(|| anyhow!("no parse"))?;
-                let compile = Ok(match parse_output {
-                    ParseOutput::Chil(expr) => {
-                        tracing::debug!("Converting chil to hypergraph...");
-                        GraphUi::new_chil(expr.to_graph(false)?, solver)
-                    }
-                    ParseOutput::Mlir(expr) => {
-                        tracing::debug!("Converting mlir to hypergraph...");
-                        GraphUi::new_mlir(expr.to_graph(mlir_settings.sym_name_linking)?, solver)
-                    }
-                    ParseOutput::Spartan(expr) => {
-                        tracing::debug!("Converting spartan to hypergraph...");
-                        GraphUi::new_spartan(expr.to_graph(false)?, solver)
-                    }
-                    ParseOutput::Dot(graph) => {
-                        tracing::debug!("Converting dot to hypergraph...");
-                        GraphUi::new_dot(dot_to_graph(graph, dot_settings)?, solver)
-                    }
-                });
+                let compile = parse_output.compile(&settings, solver);
                 ctx.request_repaint();
                 compile
             }));
         }
+        self.trigger_diff(ctx);
 
         self.selections.clear();
         self.find = None;
     }
+
+    /// Compile the code being compared against, if any.
+    fn trigger_diff(&mut self, ctx: &egui::Context) {
+        self.diffed = false;
+        let Some(code) = self.diff_code.clone() else {
+            self.diff_ui = None;
+            return;
+        };
+        let ctx = ctx.clone();
+        let language = self.language;
+        let settings = self.settings.clone();
+        let solver = self.solver;
+        self.diff_ui.replace(crate::spawn!("diff", {
+            let parse_output = language.parse(&code)?;
+            let compile = parse_output.compile(&settings, solver);
+            ctx.request_repaint();
+            compile
+        }));
+    }
 }
 
 impl eframe::App for App {
@@ -230,6 +348,62 @@ impl eframe::App for App {
                     tracing::debug!("{}", err);
                     self.last_parse_error.replace(err);
                 }
+                Message::Diff(code) => {
+                    self.diff_code = Some(code);
+                    self.trigger_diff(ctx);
+                }
+                Message::LoadLanguage {
+                    name,
+                    grammar,
+                    mapping,
+                } => match load_language(&name, &grammar, &mapping) {
+                    Ok(language) => {
+                        self.language = language;
+                        self.tx
+                            .send(Message::Compile)
+                            .expect("failed to send message");
+                    }
+                    Err(err) => {
+                        self.toasts.error(err.to_string());
+                    }
+                },
+                Message::Session(session) => match Session::from_ron(&session) {
+                    Ok(session) => self.restore_session(session),
+                    Err(err) => {
+                        self.toasts.error(err.to_string());
+                    }
+                },
+                Message::StyleSheet(style_sheet) => match style_sheet.parse() {
+                    Ok(style_sheet) => set_style_sheet(style_sheet),
+                    Err(err) => {
+                        self.toasts.error(format!("Invalid style sheet: {err}"));
+                    }
+                },
+                Message::Dialects(dialects) => match dialects.parse() {
+                    Ok(dialects) => {
+                        set_dialects(dialects);
+                        self.tx
+                            .send(Message::Compile)
+                            .expect("failed to send message");
+                    }
+                    Err(err) => {
+                        self.toasts.error(format!("Invalid dialects: {err}"));
+                    }
+                },
+            }
+        }
+
+        self.apply_layout();
+        self.restore_graph_state();
+
+        if !self.diffed {
+            if let Some((graph_ui, diff_ui)) =
+                finished_mut(&mut self.graph_ui).zip(finished_mut(&mut self.diff_ui))
+            {
+                self.diffed = true;
+                if let Err(err) = graph_ui.diff(diff_ui) {
+                    self.toasts.error(err.to_string());
+                }
             }
         }
 
@@ -287,48 +461,109 @@ impl eframe::App for App {
                     self.editor = !self.editor;
                 };
 
+                if ui.selectable_label(self.inspector, "Inspector").clicked() {
+                    self.inspector = !self.inspector;
+                };
+
+                if ui
+                    .selectable_label(self.source, "Source")
+                    .on_hover_text("Show the source line of the operation clicked on")
+                    .clicked()
+                {
+                    self.source = !self.source;
+                };
+
                 egui::widgets::global_dark_light_mode_buttons(ui);
 
+                ui.menu_button("Layout", |ui| {
+                    for orientation in Orientation::ALL {
+                        ui.radio_value(&mut self.orientation, orientation, orientation.label());
+                    }
+                    ui.separator();
+                    ui.checkbox(&mut self.wire_labels, "Wire labels");
+                    ui.checkbox(&mut self.exact_swaps, "Exact swap minimisation")
+                        .on_hover_text("Minimise swaps exactly for small graphs (slower)");
+                });
+
                 ui.separator();
 
                 ui.menu_button("Language", |ui| {
-                    ui.radio_value(&mut self.language, UiLanguage::Chil, "Chil");
-                    ui.radio_value(&mut self.language, UiLanguage::Mlir, "Mlir");
-                    ui.radio_value(&mut self.language, UiLanguage::Spartan, "Spartan");
-                    ui.radio_value(&mut self.language, UiLanguage::Dot, "Dot");
+                    for language in UiLanguage::all() {
+                        ui.radio_value(&mut self.language, language, language.label());
+                    }
                 });
 
-                if self.language == UiLanguage::Dot {
-                    ui.menu_button("Settings", |ui| {
-                        if ui
-                            .selectable_label(self.dot_settings.invert, "Invert edges")
-                            .clicked()
-                        {
-                            self.dot_settings.invert = !self.dot_settings.invert;
-                            self.tx
-                                .send(Message::Compile)
-                                .expect("Failed to send message");
-                        }
-                        if ui
-                            .selectable_label(self.dot_settings.collect, "Collect edges")
-                            .clicked()
-                        {
-                            self.dot_settings.collect = !self.dot_settings.collect;
-                            self.tx
-                                .send(Message::Compile)
-                                .expect("Failed to send message");
-                        }
-                    });
+                if ui
+                    .button("Load language")
+                    .on_hover_text("Load a pest grammar and a TOML mapping of its rules")
+                    .clicked()
+                {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if let Some((grammar, mapping)) = rfd::FileDialog::new()
+                        .add_filter("Grammar", &["pest"])
+                        .pick_file()
+                        .zip(
+                            rfd::FileDialog::new()
+                                .add_filter("Mapping", &["toml"])
+                                .pick_file(),
+                        )
+                    {
+                        let name = grammar
+                            .file_stem()
+                            .map(|stem| stem.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        self.tx
+                            .send(Message::LoadLanguage {
+                                name,
+                                grammar: std::fs::read_to_string(grammar)
+                                    .expect("file picker returned invalid path"),
+                                mapping: std::fs::read_to_string(mapping)
+                                    .expect("file picker returned invalid path"),
+                            })
+                            .expect("failed to send message");
+                    }
+
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        let tx = self.tx.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            let Some(grammar) = rfd::AsyncFileDialog::new()
+                                .add_filter("Grammar", &["pest"])
+                                .pick_file()
+                                .await
+                            else {
+                                return;
+                            };
+                            let Some(mapping) = rfd::AsyncFileDialog::new()
+                                .add_filter("Mapping", &["toml"])
+                                .pick_file()
+                                .await
+                            else {
+                                return;
+                            };
+                            let name = grammar
+                                .file_name()
+                                .split_once('.')
+                                .map_or_else(|| grammar.file_name(), |(stem, _)| stem.to_owned());
+                            if let (Ok(grammar), Ok(mapping)) = (
+                                String::from_utf8(grammar.read().await),
+                                String::from_utf8(mapping.read().await),
+                            ) {
+                                tx.send(Message::LoadLanguage {
+                                    name,
+                                    grammar,
+                                    mapping,
+                                })
+                                .expect("failed to send message");
+                            }
+                        });
+                    }
                 }
 
-                if self.language == UiLanguage::Mlir {
+                let language = self.language;
+                if language.has_settings() {
                     ui.menu_button("Settings", |ui| {
-                        if ui
-                            .selectable_label(self.mlir_settings.sym_name_linking, "Link symbols")
-                            .clicked()
-                        {
-                            self.mlir_settings.sym_name_linking =
-                                !self.mlir_settings.sym_name_linking;
+                        if language.settings_ui(ui, &mut self.settings) {
                             self.tx
                                 .send(Message::Compile)
                                 .expect("Failed to send message");
@@ -336,14 +571,25 @@ impl eframe::App for App {
                     });
                 }
 
+                if language.optimisable()
+                    && ui
+                        .selectable_label(self.settings.optimise, "Optimise")
+                        .on_hover_text("Merge common subexpressions and remove unused operations")
+                        .clicked()
+                {
+                    self.settings.optimise = !self.settings.optimise;
+                    self.tx
+                        .send(Message::Compile)
+                        .expect("Failed to send message");
+                }
+
                 if button!("Import file", egui::Modifiers::COMMAND, egui::Key::O) {
                     #[cfg(not(target_arch = "wasm32"))]
                     if let Some(path) = rfd::FileDialog::new().pick_file() {
-                        let language = match path.extension() {
-                            Some(ext) if ext == "sd" => Some(UiLanguage::Spartan),
-                            Some(ext) if ext == "chil" => Some(UiLanguage::Chil),
-                            Some(_) | None => None,
-                        };
+                        let language = path
+                            .extension()
+                            .and_then(|ext| UiLanguage::from_extension(ext.to_str()?));
+                        self.source_viewer.base = path.parent().map(std::path::Path::to_path_buf);
                         self.set_file(
                             &std::fs::read_to_string(path)
                                 .expect("file picker returned invalid path"),
@@ -359,11 +605,10 @@ impl eframe::App for App {
                         wasm_bindgen_futures::spawn_local(async move {
                             let file = task.await.unwrap();
                             tracing::trace!("got file name {:?}", file.file_name());
-                            let language = match file.file_name().split('.').last() {
-                                Some("chil") => Some(UiLanguage::Chil),
-                                Some("sd") => Some(UiLanguage::Spartan),
-                                Some(_) | None => None,
-                            };
+                            let language = file
+                                .file_name()
+                                .rsplit_once('.')
+                                .and_then(|(_, ext)| UiLanguage::from_extension(ext));
                             let contents = file.read().await;
                             if let Ok(string) = String::from_utf8(contents) {
                                 *code.lock().unwrap() = string.to_owned();
@@ -377,6 +622,148 @@ impl eframe::App for App {
                     }
                 }
 
+                if button!("Diff with file") {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                        let code = std::fs::read_to_string(path)
+                            .expect("file picker returned invalid path");
+                        self.tx
+                            .send(Message::Diff(code))
+                            .expect("failed to send message");
+                    }
+
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        let task = rfd::AsyncFileDialog::new().pick_file();
+                        let tx = self.tx.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            let file = task.await.unwrap();
+                            let contents = file.read().await;
+                            if let Ok(string) = String::from_utf8(contents) {
+                                tx.send(Message::Diff(string))
+                                    .expect("failed to send message");
+                            }
+                        });
+                    }
+                }
+
+                if button!("Open session") {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Session", &["ron"])
+                        .pick_file()
+                    {
+                        let session = std::fs::read_to_string(path)
+                            .expect("file picker returned invalid path");
+                        self.tx
+                            .send(Message::Session(session))
+                            .expect("failed to send message");
+                    }
+
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        let task = rfd::AsyncFileDialog::new()
+                            .add_filter("Session", &["ron"])
+                            .pick_file();
+                        let tx = self.tx.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            let file = task.await.unwrap();
+                            let contents = file.read().await;
+                            if let Ok(string) = String::from_utf8(contents) {
+                                tx.send(Message::Session(string))
+                                    .expect("failed to send message");
+                            }
+                        });
+                    }
+                }
+
+                if button!("Load style sheet") {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Style sheet", &["toml"])
+                        .pick_file()
+                    {
+                        let style_sheet = std::fs::read_to_string(path)
+                            .expect("file picker returned invalid path");
+                        self.tx
+                            .send(Message::StyleSheet(style_sheet))
+                            .expect("failed to send message");
+                    }
+
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        let task = rfd::AsyncFileDialog::new()
+                            .add_filter("Style sheet", &["toml"])
+                            .pick_file();
+                        let tx = self.tx.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            let file = task.await.unwrap();
+                            let contents = file.read().await;
+                            if let Ok(string) = String::from_utf8(contents) {
+                                tx.send(Message::StyleSheet(string))
+                                    .expect("failed to send message");
+                            }
+                        });
+                    }
+                }
+
+                if button!("Load MLIR dialects") {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Dialects", &["toml"])
+                        .pick_file()
+                    {
+                        let dialects = std::fs::read_to_string(path)
+                            .expect("file picker returned invalid path");
+                        self.tx
+                            .send(Message::Dialects(dialects))
+                            .expect("failed to send message");
+                    }
+
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        let task = rfd::AsyncFileDialog::new()
+                            .add_filter("Dialects", &["toml"])
+                            .pick_file();
+                        let tx = self.tx.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            let file = task.await.unwrap();
+                            let contents = file.read().await;
+                            if let Ok(string) = String::from_utf8(contents) {
+                                tx.send(Message::Dialects(string))
+                                    .expect("failed to send message");
+                            }
+                        });
+                    }
+                }
+
+                #[cfg(not(target_arch = "wasm32"))]
+                if button!("Save session") {
+                    match self.session().to_ron() {
+                        Ok(session) => {
+                            if let Some(path) = rfd::FileDialog::new()
+                                .add_filter("Session", &["ron"])
+                                .save_file()
+                            {
+                                if let Err(err) = std::fs::write(path, session) {
+                                    self.toasts.error(format!("Could not save session: {err}"));
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            self.toasts.error(err.to_string());
+                        }
+                    }
+                }
+
+                if button!("Close diff", enabled = self.diff_code.is_some()) {
+                    self.diff_code = None;
+                    self.trigger_diff(ctx);
+                    if let Some(graph_ui) = finished_mut(&mut self.graph_ui) {
+                        graph_ui.clear_changes();
+                    }
+                }
+
                 ui.separator();
 
                 // will be true if any graph is currently being drawn
@@ -446,11 +833,9 @@ impl eframe::App for App {
                     enabled = ready && has_selections
                 ) {
                     if let Some(graph_ui) = finished_mut(&mut self.graph_ui) {
-                        if let Some(sel) = Selection::from_graph(
-                            graph_ui,
-                            format!("Selection {}", self.selections.len()),
-                            self.solver,
-                        ) {
+                        if let Some(sel) = graph_ui
+                            .selection(format!("Selection {}", self.selections.len()), self.solver)
+                        {
                             self.selections.push(sel);
                         }
                         graph_ui.clear_selection();
@@ -462,6 +847,21 @@ impl eframe::App for App {
                         graph_ui.clear_selection();
                     }
                 }
+                let can_extract = finished(&self.graph_ui)
+                    .map(GraphUi::can_extract)
+                    .unwrap_or_default();
+                if button!(
+                    "Extract selection",
+                    enabled = ready && has_selections && can_extract
+                ) {
+                    match finished(&self.graph_ui).map(GraphUi::extract_selection) {
+                        Some(Ok(code)) => self.set_file(&code, None),
+                        Some(Err(err)) => {
+                            self.toasts.error(err.to_string());
+                        }
+                        None => (),
+                    }
+                }
                 ui.add_enabled_ui(ready && has_selections, |ui| {
                     ui.menu_button("Extend selection", |ui| {
                         for (label, direction) in [
@@ -480,9 +880,34 @@ impl eframe::App for App {
                     });
                 });
 
+                ui.separator();
+                ui.add_enabled_ui(ready, |ui| {
+                    ui.menu_button("Export term", |ui| {
+                        for (label, format) in [
+                            ("Plain text", TermFormat::Plain),
+                            ("LaTeX", TermFormat::Latex),
+                        ] {
+                            if ui.button(label).clicked() {
+                                if let Some(graph_ui) = finished(&self.graph_ui) {
+                                    ui.ctx().copy_text(graph_ui.export_term(format));
+                                    self.toasts.info("Copied term to clipboard");
+                                }
+                                ui.close_menu();
+                            }
+                        }
+                        ui.separator();
+                        if ui.button("DisCoPy JSON").clicked() {
+                            if let Some(graph_ui) = finished(&self.graph_ui) {
+                                ui.ctx().copy_text(graph_ui.export_discopy());
+                                self.toasts.info("Copied DisCoPy diagram to clipboard");
+                            }
+                            ui.close_menu();
+                        }
+                    });
+                });
+
                 #[cfg(not(target_arch = "wasm32"))]
                 {
-                    ui.separator();
                     if button!("Export SVG", enabled = ready) {
                         if let Some(graph_ui) = finished(&self.graph_ui) {
                             let svg = graph_ui.export_svg();
@@ -504,12 +929,58 @@ impl eframe::App for App {
             selection.ui(ctx);
         }
 
+        if let Some(result) = finished_mut(&mut self.graph_ui).and_then(GraphUi::inline_requested) {
+            match result {
+                Ok(code) => self.set_file(&code, None),
+                Err(err) => {
+                    self.toasts.error(err.to_string());
+                }
+            }
+        }
+
+        if self.source {
+            let location = finished_mut(&mut self.graph_ui).and_then(|ui| ui.inspected_location());
+            egui::SidePanel::left("source_panel")
+                .resizable(true)
+                .show(ctx, |ui| self.source_viewer.ui(ui, location));
+        }
+
         egui::SidePanel::right("selection_panel").show(ctx, |ui| {
             egui::ScrollArea::vertical()
                 .id_source("selections")
                 .show(ui, |ui| self.selection_ui(ui));
         });
 
+        if self.inspector {
+            let docked_before = self.inspector_docked;
+            let docked = &mut self.inspector_docked;
+            let graph_ui = &mut self.graph_ui;
+            let inspector_ui = |ui: &mut egui::Ui| {
+                if ui.button(if *docked { "Undock" } else { "Dock" }).clicked() {
+                    *docked = !*docked;
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().id_source("inspector").show(
+                    ui,
+                    |ui| match finished_mut(graph_ui) {
+                        Some(graph_ui) => graph_ui.inspector_ui(ui),
+                        None => {
+                            ui.weak("No graph");
+                        }
+                    },
+                );
+            };
+            if docked_before {
+                egui::SidePanel::right("inspector_panel")
+                    .resizable(true)
+                    .show(ctx, inspector_ui);
+            } else {
+                egui::Window::new("Inspector")
+                    .open(&mut self.inspector)
+                    .show(ctx, inspector_ui);
+            }
+        }
+
         #[allow(clippy::redundant_closure_call)]
         egui::CentralPanel::default().show(ctx, |ui| {
             macro_rules! optional_editor {
@@ -526,19 +997,16 @@ impl eframe::App for App {
                     }
                 };
             }
-            optional_editor!(|ui| {
-                match self
-                    .graph_ui
-                    .as_mut()
-                    .map(|p| p.poll_mut().map(Result::as_mut))
-                {
-                    Some(Poll::Ready(Ok(graph_ui))) => {
-                        graph_ui.ui(ui, self.find.as_ref().map(|x| x.0.as_str()));
-                    }
-                    Some(Poll::Pending) => {
-                        ui.centered_and_justified(eframe::egui::Ui::spinner);
-                    }
-                    Some(Poll::Ready(Err(_))) | None => { /* No pending successful compilation */ }
+            optional_editor!(|ui: &mut egui::Ui| {
+                let search = self.find.as_ref().map(|x| x.0.as_str());
+                if self.diff_ui.is_some() {
+                    // Show the version being compared against next to the current one.
+                    ui.columns(2, |columns| {
+                        graph_ui(&mut columns[0], &mut self.graph_ui, search);
+                        graph_ui(&mut columns[1], &mut self.diff_ui, search);
+                    });
+                } else {
+                    graph_ui(ui, &mut self.graph_ui, search);
                 }
             });
         });
@@ -600,6 +1068,27 @@ impl eframe::App for App {
 
         self.toasts.show(ctx);
     }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.session());
+    }
+}
+
+/// Show the graph once it has been compiled.
+fn graph_ui(
+    ui: &mut egui::Ui,
+    graph_ui: &mut Option<Promise<anyhow::Result<GraphUi>>>,
+    search: Option<&str>,
+) {
+    match graph_ui.as_mut().map(|p| p.poll_mut().map(Result::as_mut)) {
+        Some(Poll::Ready(Ok(graph_ui))) => {
+            graph_ui.ui(ui, search);
+        }
+        Some(Poll::Pending) => {
+            ui.centered_and_justified(eframe::egui::Ui::spinner);
+        }
+        Some(Poll::Ready(Err(_))) | None => { /* No pending successful compilation */ }
+    }
 }
 
 #[allow(clippy::inline_always)]
@@ -5,7 +5,7 @@ use eframe::{
 
 use crate::{
     highlighter::{highlight, CodeTheme},
-    parser::UiLanguage,
+    languages::UiLanguage,
 };
 
 pub fn code_ui(
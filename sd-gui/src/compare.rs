@@ -0,0 +1,13 @@
+use crate::{languages::Settings, UiLanguage};
+
+/// Check whether two programs have isomorphic hypergraphs,
+/// i.e. they are equal up to renaming of bound variables.
+///
+/// # Errors
+///
+/// This function will return an error if either program cannot be parsed or converted to a hypergraph.
+pub fn compare(left: &str, right: &str, language: UiLanguage) -> anyhow::Result<bool> {
+    let left = language.parse(left)?;
+    let right = language.parse(right)?;
+    left.is_isomorphic(right.as_ref(), &Settings::default())
+}
@@ -1,7 +1,8 @@
 #![allow(clippy::inline_always)]
 
-use std::fmt::Display;
+use std::{any::Any, fmt::Display};
 
+use anyhow::anyhow;
 use delegate::delegate;
 use eframe::{
     egui,
@@ -10,75 +11,402 @@ use eframe::{
 use sd_core::{
     codeable::Codeable,
     common::{Direction, Matchable},
-    dot::DotWeight,
     graph::SyntaxHypergraph,
     hypergraph::{
-        generic::{Edge, Operation, Thunk, Weight},
+        self,
+        diff::{diff, Similarity},
+        generic::{Edge, Endpoint, Operation, Thunk, Weight},
+        path::NodePath,
         subgraph::ExtensibleEdge,
-        traits::{Graph, WithType},
-        Hypergraph,
+        traits::{EdgeLike, Graph, WithType, WithWeight},
+        Hypergraph, Weight as HypergraphWeight,
     },
-    interactive::InteractiveGraph,
-    language::{chil::Chil, mlir::Mlir, spartan::Spartan},
+    interactive::{InteractiveGraph, InteractiveState},
+    language::{Expr, Language, OpInfo, CF},
     lp::Solver,
+    monoidal::{graph::MonoidalGraph, term::TermFormat, wired_graph::from_graph},
+    prettyprinter::PrettyPrint,
+    refactor::{can_inline, extract, inline, Abstraction},
+};
+use sd_graphics::{
+    common::{Orientation, Shapeable},
+    renderable::RenderableGraph,
+    shape::Shape as DiagramShape,
 };
-use sd_graphics::{common::Shapeable, renderable::RenderableGraph};
 
-use crate::{panzoom::Panzoom, shape_generator::generate_shapes};
+use crate::{
+    inspector::inspector, minimap::minimap, panzoom::Panzoom, selection::Selection,
+    shape_generator::generate_shapes,
+};
 
-pub enum GraphUi {
-    Chil(GraphUiInternal<InteractiveGraph<SyntaxHypergraph<Chil>>>),
-    Mlir(GraphUiInternal<InteractiveGraph<SyntaxHypergraph<Mlir>>>),
-    Spartan(GraphUiInternal<InteractiveGraph<SyntaxHypergraph<Spartan>>>),
-    Dot(GraphUiInternal<InteractiveGraph<Hypergraph<DotWeight>>>),
-}
+/// A graph of a program in one of the registered languages.
+pub struct GraphUi(Box<dyn GraphView>);
 
 impl GraphUi {
-    pub(crate) fn new_chil(graph: SyntaxHypergraph<Chil>, solver: Solver) -> Self {
-        Self::Chil(GraphUiInternal::new(InteractiveGraph::new(graph), solver))
+    pub(crate) fn new<W>(graph: Hypergraph<W>, solver: Solver) -> Self
+    where
+        W: HypergraphWeight + 'static,
+        GraphUiInternal<InteractiveGraph<Hypergraph<W>>>: GraphView,
+    {
+        Self(Box::new(GraphUiInternal::new(
+            InteractiveGraph::new(graph),
+            solver,
+        )))
     }
 
-    pub(crate) fn new_mlir(graph: SyntaxHypergraph<Mlir>, solver: Solver) -> Self {
-        Self::Mlir(GraphUiInternal::new(InteractiveGraph::new(graph), solver))
+    /// A graph of a program in a language which supports inlining,
+    /// whose thunks offer to be inlined wherever they are applied directly.
+    pub(crate) fn new_inlinable<T>(graph: SyntaxHypergraph<T>, solver: Solver) -> Self
+    where
+        T: Abstraction + 'static,
+        GraphUiInternal<InteractiveGraph<SyntaxHypergraph<T>>>: GraphView,
+    {
+        let mut graph = InteractiveGraph::new(graph);
+        graph.set_inlinable(can_inline::<T>);
+        Self(Box::new(GraphUiInternal::new(graph, solver)))
     }
 
-    pub(crate) fn new_spartan(graph: SyntaxHypergraph<Spartan>, solver: Solver) -> Self {
-        Self::Spartan(GraphUiInternal::new(InteractiveGraph::new(graph), solver))
+    /// Colours the nodes of this graph and `new` according to how they differ.
+    pub(crate) fn diff(&mut self, new: &mut Self) -> anyhow::Result<()> {
+        self.0.diff(new.0.as_mut())
     }
 
-    pub(crate) fn new_dot(graph: Hypergraph<DotWeight>, solver: Solver) -> Self {
-        Self::Dot(GraphUiInternal::new(InteractiveGraph::new(graph), solver))
+    /// Shows the details of the node last clicked, panning to the source of any edge clicked on.
+    pub(crate) fn inspector_ui(&mut self, ui: &mut egui::Ui) {
+        let details = self.0.inspected_details();
+        self.0.inspector_ui(ui, &details);
     }
 
     delegate! {
-        to match self {
-            GraphUi::Chil(graph_ui) => graph_ui,
-            GraphUi::Mlir(graph_ui) => graph_ui,
-            GraphUi::Spartan(graph_ui) => graph_ui,
-        GraphUi::Dot(graph_ui) => graph_ui
-        } {
+        to self.0 {
             pub(crate) fn ui(&mut self, ui: &mut egui::Ui, search: Option<&str>);
-            pub(crate) const fn ready(&self) -> bool;
+            pub(crate) fn ready(&self) -> bool;
             pub(crate) fn reset(&mut self);
+            pub(crate) fn set_orientation(&mut self, orientation: Orientation);
+            pub(crate) fn set_wire_labels(&mut self, wire_labels: bool);
+            pub(crate) fn set_exact_swaps(&mut self, exact_swaps: bool);
             pub(crate) fn zoom_in(&mut self);
             pub(crate) fn zoom_out(&mut self);
             pub(crate) fn find(&mut self, query: &str, offset: usize);
+            #[cfg(not(target_arch = "wasm32"))]
             pub(crate) fn export_svg(&self) -> String;
-        }
-    }
-
-    delegate! {
-        to match self {
-            GraphUi::Chil(graph_ui) => graph_ui.graph,
-            GraphUi::Mlir(graph_ui) => graph_ui.graph,
-            GraphUi::Spartan(graph_ui) => graph_ui.graph,
-        GraphUi::Dot(graph_ui) => graph_ui.graph
-        } {
+            pub(crate) fn export_term(&self, format: TermFormat) -> String;
+            pub(crate) fn export_discopy(&self) -> String;
+            pub(crate) fn panzoom(&self) -> Panzoom;
+            pub(crate) fn set_panzoom(&mut self, panzoom: Panzoom);
             pub(crate) fn is_empty(&self) -> bool;
             pub(crate) fn clear_selection(&mut self);
             pub(crate) fn extend_selection(&mut self, direction: Option<(Direction, usize)>);
             pub(crate) fn set_expanded_all(&mut self, expanded: bool);
+            pub(crate) fn clear_changes(&mut self);
+            pub(crate) fn select(&mut self, paths: &[NodePath]);
+            pub(crate) fn state(&self) -> InteractiveState;
+            pub(crate) fn restore(&mut self, state: &InteractiveState);
+            pub(crate) fn can_extract(&self) -> bool;
+            pub(crate) fn extract_selection(&self) -> anyhow::Result<String>;
+            pub(crate) fn inline_requested(&mut self) -> Option<anyhow::Result<String>>;
+            pub(crate) fn selection(&self, name: String, solver: Solver) -> Option<Selection>;
+            pub(crate) fn inspected_location(&self) -> Option<(String, usize)>;
+        }
+    }
+}
+
+/// The parts of a graph view which are the same for every language.
+pub(crate) trait GraphViewBase: Send {
+    fn ui(&mut self, ui: &mut egui::Ui, search: Option<&str>);
+    fn ready(&self) -> bool;
+    fn reset(&mut self);
+    fn set_orientation(&mut self, orientation: Orientation);
+    fn set_wire_labels(&mut self, wire_labels: bool);
+    fn set_exact_swaps(&mut self, exact_swaps: bool);
+    fn zoom_in(&mut self);
+    fn zoom_out(&mut self);
+    fn find(&mut self, query: &str, offset: usize);
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_svg(&self) -> String;
+    /// The monoidal term which the laid out diagram denotes.
+    fn export_term(&self, format: TermFormat) -> String;
+    /// The laid out diagram as a DisCoPy diagram, serialised to JSON.
+    fn export_discopy(&self) -> String;
+    fn panzoom(&self) -> Panzoom;
+    fn set_panzoom(&mut self, panzoom: Panzoom);
+    fn is_empty(&self) -> bool;
+    fn clear_selection(&mut self);
+    fn extend_selection(&mut self, direction: Option<(Direction, usize)>);
+    fn set_expanded_all(&mut self, expanded: bool);
+    fn clear_changes(&mut self);
+    fn select(&mut self, paths: &[NodePath]);
+    fn state(&self) -> InteractiveState;
+    fn restore(&mut self, state: &InteractiveState);
+    /// Forgets the thunk last chosen to be inlined, returning whether there was one.
+    fn take_inline_request(&mut self) -> bool;
+    /// Colours the nodes of this graph and `new` according to how they differ.
+    fn diff(&mut self, new: &mut dyn GraphView) -> anyhow::Result<()>;
+    /// Shows the details of the node last clicked, along with the given language-specific details.
+    fn inspector_ui(&mut self, ui: &mut egui::Ui, details: &[(&'static str, String)]);
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// The parts of a graph view which depend on the language, implemented alongside each language.
+pub(crate) trait GraphView: GraphViewBase {
+    /// Whether the selection can be extracted into a function.
+    fn can_extract(&self) -> bool {
+        false
+    }
+
+    /// Replaces the selection by the application of a new function and returns the resulting code.
+    fn extract_selection(&self) -> anyhow::Result<String> {
+        Err(anyhow!("extraction is not supported for this language"))
+    }
+
+    /// Inlines the thunk chosen from its context menu, if any, and returns the resulting code.
+    fn inline_requested(&mut self) -> Option<anyhow::Result<String>> {
+        self.take_inline_request()
+            .then(|| Err(anyhow!("inlining is not supported for this language")))
+    }
+
+    /// A window showing the current selection on its own, if the language supports it.
+    fn selection(&self, _name: String, _solver: Solver) -> Option<Selection> {
+        None
+    }
+
+    /// Details of the inspected operation which depend on the language, as pairs of field names and values.
+    fn inspected_details(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    /// The source file and line which the inspected operation came from, if the language records it.
+    fn inspected_location(&self) -> Option<(String, usize)> {
+        None
+    }
+}
+
+/// The context of an interactive hypergraph, which is the hypergraph itself.
+type Ctx<W> = <InteractiveGraph<Hypergraph<W>> as Graph>::Ctx;
+
+impl<W> GraphViewBase for GraphUiInternal<InteractiveGraph<Hypergraph<W>>>
+where
+    W: Similarity + 'static,
+    Self: Send,
+    InteractiveGraph<Hypergraph<W>>: RenderableGraph,
+    Edge<Ctx<W>>: Codeable + ExtensibleEdge,
+    Operation<Ctx<W>>: Codeable + Matchable + Shapeable,
+    Thunk<Ctx<W>>: Codeable + Matchable,
+    Weight<Operation<Ctx<W>>>: Display,
+    Weight<Thunk<Ctx<W>>>: Display,
+    Weight<Edge<Ctx<W>>>: WithType + Display,
+    W::EdgeWeight: Display,
+    W::OperationWeight: Display,
+    W::ThunkWeight: Display,
+    hypergraph::Operation<W>: Codeable,
+{
+    fn ui(&mut self, ui: &mut egui::Ui, search: Option<&str>) {
+        Self::ui(self, ui, search);
+    }
+
+    fn ready(&self) -> bool {
+        Self::ready(self)
+    }
+
+    fn reset(&mut self) {
+        Self::reset(self);
+    }
+
+    fn set_orientation(&mut self, orientation: Orientation) {
+        Self::set_orientation(self, orientation);
+    }
+
+    fn set_wire_labels(&mut self, wire_labels: bool) {
+        Self::set_wire_labels(self, wire_labels);
+    }
+
+    fn set_exact_swaps(&mut self, exact_swaps: bool) {
+        Self::set_exact_swaps(self, exact_swaps);
+    }
+
+    fn zoom_in(&mut self) {
+        Self::zoom_in(self);
+    }
+
+    fn zoom_out(&mut self) {
+        Self::zoom_out(self);
+    }
+
+    fn find(&mut self, query: &str, offset: usize) {
+        Self::find(self, query, offset);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_svg(&self) -> String {
+        Self::export_svg(self)
+    }
+
+    fn export_term(&self, format: TermFormat) -> String {
+        Self::export_term(self, format)
+    }
+
+    fn export_discopy(&self) -> String {
+        Self::export_discopy(self)
+    }
+
+    fn panzoom(&self) -> Panzoom {
+        Self::panzoom(self)
+    }
+
+    fn set_panzoom(&mut self, panzoom: Panzoom) {
+        Self::set_panzoom(self, panzoom);
+    }
+
+    fn take_inline_request(&mut self) -> bool {
+        self.graph.take_inline_request().is_some()
+    }
+
+    fn diff(&mut self, new: &mut dyn GraphView) -> anyhow::Result<()> {
+        let new = new
+            .as_any_mut()
+            .downcast_mut::<Self>()
+            .ok_or_else(|| anyhow!("cannot compare graphs of different languages"))?;
+        let diff = diff(self.graph.inner(), new.graph.inner());
+        self.graph.set_changes(diff.old_changes().clone());
+        new.graph.set_changes(diff.new_changes().clone());
+        Ok(())
+    }
+
+    fn inspector_ui(&mut self, ui: &mut egui::Ui, details: &[(&'static str, String)]) {
+        if let Some(edge) = inspector(ui, &self.graph, details) {
+            self.pan_to_source(&edge);
+        }
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    delegate! {
+        to self.graph {
+            fn is_empty(&self) -> bool;
+            fn clear_selection(&mut self);
+            fn extend_selection(&mut self, direction: Option<(Direction, usize)>);
+            fn set_expanded_all(&mut self, expanded: bool);
+            fn clear_changes(&mut self);
+            fn select(&mut self, paths: &[NodePath]);
+            fn state(&self) -> InteractiveState;
+            fn restore(&mut self, state: &InteractiveState);
+        }
+    }
+}
+
+impl<W> GraphUiInternal<InteractiveGraph<Hypergraph<W>>>
+where
+    W: HypergraphWeight + 'static,
+    Edge<Ctx<W>>: ExtensibleEdge,
+    Operation<Ctx<W>>: Shapeable,
+    Weight<Operation<Ctx<W>>>: Display,
+    Weight<Edge<Ctx<W>>>: Display,
+{
+    /// Pans to the operation or thunk which an edge comes from,
+    /// or to the start of the edge if it comes from the boundary of a thunk or is hidden.
+    fn pan_to_source(&mut self, edge: &hypergraph::Edge<W>) {
+        let shapes = generate_shapes(
+            &self.graph,
+            self.solver,
+            self.orientation,
+            self.wire_labels,
+            self.exact_swaps,
+        );
+        let guard = shapes.lock().unwrap();
+        let Some(shapes) = guard.ready() else {
+            return;
+        };
+
+        let source = match edge.source() {
+            Endpoint::Node(node) => Some(node),
+            Endpoint::Boundary(_) => None,
+        };
+        let node_pos = shapes.shapes.iter().find_map(|shape| match shape {
+            DiagramShape::Operation { addr, center, .. } => {
+                (addr.inner().left().map(|op| op.inner()) == source.as_ref()).then_some(*center)
+            }
+            DiagramShape::Rectangle { addr, rect, .. } => source
+                .as_ref()
+                .is_some_and(|node| *node == hypergraph::Node::Thunk(addr.inner().inner().clone()))
+                .then_some(rect.center_bottom()),
+            _ => None,
+        });
+        let edge_pos = || {
+            shapes.shapes.iter().find_map(|shape| match shape {
+                DiagramShape::Line { addr, start, .. } => {
+                    (addr.inner().inner() == edge).then_some(*start)
+                }
+                _ => None,
+            })
+        };
+        if let Some(pos) = node_pos.or_else(edge_pos) {
+            self.panzoom.set_pan(pos);
+        }
+    }
+}
+
+impl<T> GraphUiInternal<InteractiveGraph<SyntaxHypergraph<T>>>
+where
+    T: 'static + Language,
+{
+    /// Details of the inspected operation which every language can give:
+    /// the symbol it defines, the symbols it uses and where control flows to.
+    pub(crate) fn syntax_details(&self) -> Vec<(&'static str, String)> {
+        let Some(hypergraph::Node::Operation(op)) = self.graph.inspected() else {
+            return Vec::new();
+        };
+        let weight = op.weight();
+        let mut details = Vec::new();
+        if let Some(sym_name) = weight.sym_name() {
+            details.push(("Symbol", sym_name.to_string()));
+        }
+        let symbols = weight
+            .symbols_used()
+            .map(|symbol| symbol.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !symbols.is_empty() {
+            details.push(("Uses", symbols));
         }
+        match weight.get_cf() {
+            Some(CF::Return) => details.push(("Control flow", "return".to_owned())),
+            Some(CF::Brs(successors)) => {
+                let successors = successors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                details.push(("Successors", successors.join(", ")));
+            }
+            None => (),
+        }
+        details
+    }
+}
+
+impl<T> GraphUiInternal<InteractiveGraph<SyntaxHypergraph<T>>>
+where
+    T: 'static + Abstraction,
+    T::VarDef: From<T::Var>,
+    Expr<T>: PrettyPrint,
+{
+    /// Replaces the selection by the application of a new function and returns the resulting code.
+    pub(crate) fn extracted_code(&self) -> anyhow::Result<String> {
+        let subgraph = self.graph.to_subgraph();
+        let graph = extract(self.graph.inner(), subgraph.0.inner())?;
+        Ok(Expr::decompile(&graph)?.to_pretty())
+    }
+
+    /// Inlines the thunk chosen from its context menu, if any, and returns the resulting code.
+    pub(crate) fn inlined_code(&mut self) -> Option<anyhow::Result<String>> {
+        let thunk = self.graph.take_inline_request()?;
+        Some(self.inline(&thunk))
+    }
+
+    fn inline(&self, thunk: &Thunk<SyntaxHypergraph<T>>) -> anyhow::Result<String> {
+        let graph = inline(self.graph.inner(), thunk)?;
+        Ok(Expr::decompile(&graph)?.to_pretty())
     }
 }
 
@@ -88,6 +416,9 @@ pub struct GraphUiInternal<G: Graph> {
     ready: bool,
     reset_requested: bool,
     solver: Solver,
+    orientation: Orientation,
+    wire_labels: bool,
+    exact_swaps: bool,
 }
 
 impl<G> GraphUiInternal<G>
@@ -101,6 +432,9 @@ where
             ready: false,
             reset_requested: true,
             solver,
+            orientation: Orientation::default(),
+            wire_labels: false,
+            exact_swaps: false,
         }
     }
 
@@ -111,13 +445,20 @@ where
         Edge<G::Ctx>: Codeable,
         Operation<G::Ctx>: Codeable + Matchable,
         Thunk<G::Ctx>: Codeable + Matchable,
+        Weight<Thunk<G::Ctx>>: Display,
         // Needed for generate_shapes
         Edge<G::Ctx>: ExtensibleEdge,
         Operation<G::Ctx>: Shapeable,
         Weight<Operation<G::Ctx>>: Display,
-        Weight<Edge<G::Ctx>>: WithType,
+        Weight<Edge<G::Ctx>>: WithType + Display,
     {
-        let shapes = generate_shapes(&self.graph, self.solver);
+        let shapes = generate_shapes(
+            &self.graph,
+            self.solver,
+            self.orientation,
+            self.wire_labels,
+            self.exact_swaps,
+        );
         let guard = shapes.lock().unwrap();
         if let Some(shapes) = guard.ready() {
             let (response, painter) =
@@ -171,11 +512,12 @@ where
             painter.extend(sd_graphics::render::render(
                 &mut self.graph,
                 ui,
-                &shapes.shapes,
+                shapes,
                 &response,
                 to_screen,
                 search,
             ));
+            minimap(ui, response.rect, shapes, &mut self.panzoom, search);
             self.ready = true;
         } else {
             ui.centered_and_justified(eframe::egui::Ui::spinner);
@@ -191,23 +533,74 @@ where
         self.reset_requested = true;
     }
 
-    /// Searches through the shapes and pans to the one which matches the query
+    pub(crate) const fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Lays the graph out in another orientation, resetting the view if it changed.
+    pub(crate) fn set_orientation(&mut self, orientation: Orientation) {
+        if self.orientation != orientation {
+            self.orientation = orientation;
+            self.reset_requested = true;
+        }
+    }
+
+    pub(crate) const fn wire_labels(&self) -> bool {
+        self.wire_labels
+    }
+
+    /// Lays the graph out with or without wire labels, resetting the view if it changed.
+    pub(crate) fn set_wire_labels(&mut self, wire_labels: bool) {
+        if self.wire_labels != wire_labels {
+            self.wire_labels = wire_labels;
+            self.reset_requested = true;
+        }
+    }
+
+    pub(crate) const fn exact_swaps(&self) -> bool {
+        self.exact_swaps
+    }
+
+    /// Minimises swaps exactly (for small enough graphs) or heuristically,
+    /// resetting the view if it changed.
+    pub(crate) fn set_exact_swaps(&mut self, exact_swaps: bool) {
+        if self.exact_swaps != exact_swaps {
+            self.exact_swaps = exact_swaps;
+            self.reset_requested = true;
+        }
+    }
+
+    pub(crate) const fn panzoom(&self) -> Panzoom {
+        self.panzoom
+    }
+
+    /// Sets the view, overriding any pending reset
+    pub(crate) fn set_panzoom(&mut self, panzoom: Panzoom) {
+        self.panzoom = panzoom;
+        self.reset_requested = false;
+    }
+
+    /// Searches through the shapes and pans to the one which matches the query,
+    /// cycling through matches in reading order
     pub(crate) fn find(&mut self, query: &str, offset: usize)
     where
         Edge<G::Ctx>: ExtensibleEdge,
         Operation<G::Ctx>: Matchable + Shapeable,
         Thunk<G::Ctx>: Matchable,
         Weight<Operation<G::Ctx>>: Display,
+        Weight<Edge<G::Ctx>>: Display,
     {
-        let shapes = generate_shapes(&self.graph, self.solver);
+        let shapes = generate_shapes(
+            &self.graph,
+            self.solver,
+            self.orientation,
+            self.wire_labels,
+            self.exact_swaps,
+        );
         let guard = shapes.lock().unwrap();
 
         if let Some(shapes) = guard.ready() {
-            let matches = shapes
-                .shapes
-                .iter()
-                .filter(|shape| shape.is_match(query))
-                .collect::<Vec<_>>();
+            let matches = shapes.find(query).collect::<Vec<_>>();
             if !matches.is_empty() {
                 let shape = matches[offset % matches.len()];
                 self.panzoom.set_pan(shape.center());
@@ -227,10 +620,35 @@ where
         Edge<G::Ctx>: ExtensibleEdge,
         Operation<G::Ctx>: Shapeable,
         Weight<Operation<G::Ctx>>: Display,
+        Weight<Edge<G::Ctx>>: Display,
     {
-        let shapes = generate_shapes(&self.graph, self.solver);
+        let shapes = generate_shapes(
+            &self.graph,
+            self.solver,
+            self.orientation,
+            self.wire_labels,
+            self.exact_swaps,
+        );
         let guard = shapes.lock().unwrap(); // this would lock the UI, but by the time we get here
                                             // the shapes have already been computed
         guard.block_until_ready().to_svg().to_string()
     }
+
+    pub(crate) fn export_term(&self, format: TermFormat) -> String
+    where
+        Weight<Operation<G::Ctx>>: Display,
+    {
+        MonoidalGraph::from(&from_graph(&self.graph, self.solver, self.exact_swaps)).to_term(format)
+    }
+
+    pub(crate) fn export_discopy(&self) -> String
+    where
+        Weight<Edge<G::Ctx>>: Display,
+        Weight<Operation<G::Ctx>>: Display,
+        Weight<Thunk<G::Ctx>>: Display,
+    {
+        MonoidalGraph::from(&from_graph(&self.graph, self.solver, self.exact_swaps))
+            .to_discopy()
+            .to_json()
+    }
 }
@@ -15,6 +15,8 @@ use syntect::{
     util::LinesWithEndings,
 };
 
+use crate::languages::UiLanguage;
+
 impl egui::util::cache::ComputerMut<(&CodeTheme, &str, &str), LayoutJob> for Highlighter {
     fn compute(&mut self, (theme, code, language): (&CodeTheme, &str, &str)) -> LayoutJob {
         self.highlight(theme, code, language)
@@ -72,9 +74,6 @@ impl CodeTheme {
 
 // ----------------------------------------------------------------------------
 
-pub const CHIL_SYNTAX: &str = include_str!("chil.sublime-syntax");
-pub const SPARTAN_SYNTAX: &str = include_str!("spartan.sublime-syntax");
-
 struct Highlighter {
     themes: ThemeSet,
     syntaxes: SyntaxSet,
@@ -84,8 +83,9 @@ impl Default for Highlighter {
     fn default() -> Self {
         let syntaxes = {
             let mut builder = SyntaxSetBuilder::new();
-            builder.add(SyntaxDefinition::load_from_str(CHIL_SYNTAX, true, None).unwrap());
-            builder.add(SyntaxDefinition::load_from_str(SPARTAN_SYNTAX, true, None).unwrap());
+            for syntax in UiLanguage::all().filter_map(|language| language.syntax()) {
+                builder.add(SyntaxDefinition::load_from_str(syntax, true, None).unwrap());
+            }
             builder.build()
         };
 
@@ -0,0 +1,110 @@
+use std::fmt::Display;
+
+use eframe::egui;
+use sd_core::{
+    codeable::Codeable,
+    hypergraph::{
+        traits::{Graph, NodeLike, WithWeight},
+        Edge, Hypergraph, Node, Operation, Weight,
+    },
+    interactive::InteractiveGraph,
+    prettyprinter::PrettyPrint,
+};
+
+/// Show the details of the node last clicked in a graph,
+/// with the `details` of operations which depend on the language as pairs of field names and values.
+/// Returns the edge clicked on, if any.
+pub(crate) fn inspector<W: Weight>(
+    ui: &mut egui::Ui,
+    graph: &InteractiveGraph<Hypergraph<W>>,
+    details: &[(&'static str, String)],
+) -> Option<Edge<W>>
+where
+    W::EdgeWeight: Display,
+    W::OperationWeight: Display,
+    W::ThunkWeight: Display,
+    Operation<W>: Codeable,
+{
+    let Some(node) = graph.inspected() else {
+        ui.weak("Click on an operation or thunk to inspect it.");
+        return None;
+    };
+
+    let mut clicked = None;
+    match node {
+        Node::Operation(op) => {
+            ui.heading(op.weight().to_string());
+            egui::Grid::new("inspector_details")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Code");
+                    ui.monospace(op.code().to_pretty());
+                    ui.end_row();
+                    for (field, value) in details {
+                        ui.label(*field);
+                        ui.monospace(value);
+                        ui.end_row();
+                    }
+                });
+            edges(ui, "Inputs", op.inputs(), &mut clicked);
+            edges(ui, "Outputs", op.outputs(), &mut clicked);
+        }
+        Node::Thunk(thunk) => {
+            ui.heading(format!("Thunk {}", thunk.weight()));
+            edges(ui, "Inputs", thunk.inputs(), &mut clicked);
+            edges(ui, "Bound inputs", thunk.bound_graph_inputs(), &mut clicked);
+            edges(
+                ui,
+                "Bound outputs",
+                thunk.bound_graph_outputs(),
+                &mut clicked,
+            );
+            edges(ui, "Outputs", thunk.outputs(), &mut clicked);
+        }
+    }
+
+    egui::CollapsingHeader::new("Enclosing thunks")
+        .default_open(true)
+        .show(ui, |ui| {
+            let mut thunks = std::iter::successors(node.backlink(), NodeLike::backlink).peekable();
+            if thunks.peek().is_none() {
+                ui.weak("None");
+            }
+            for thunk in thunks {
+                ui.label(thunk.weight().to_string());
+            }
+        });
+
+    clicked
+}
+
+/// A list of edges, each of which can be clicked on.
+fn edges<W: Weight>(
+    ui: &mut egui::Ui,
+    heading: &str,
+    edges: impl Iterator<Item = Edge<W>>,
+    clicked: &mut Option<Edge<W>>,
+) where
+    W::EdgeWeight: Display,
+{
+    egui::CollapsingHeader::new(heading)
+        .default_open(true)
+        .show(ui, |ui| {
+            let mut edges = edges.peekable();
+            if edges.peek().is_none() {
+                ui.weak("None");
+            }
+            for (i, edge) in edges.enumerate() {
+                let name = edge.weight().to_string();
+                let label = if name.is_empty() {
+                    format!("#{i}")
+                } else {
+                    name
+                };
+                if ui.link(label).on_hover_text("Go to source").clicked() {
+                    *clicked = Some(edge);
+                }
+            }
+        });
+}
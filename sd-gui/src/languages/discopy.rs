@@ -0,0 +1,54 @@
+use std::any::Any;
+
+use sd_core::{discopy::Diagram, hypergraph::isomorphism::is_isomorphic, lp::Solver};
+
+use super::{same_language, LanguageDef, Settings};
+use crate::{
+    graph_ui::GraphUi,
+    parser::{ParseError, ParseOutput, Program},
+};
+
+pub struct DiscopyLanguage;
+
+impl LanguageDef for DiscopyLanguage {
+    fn name(&self) -> &'static str {
+        "discopy"
+    }
+
+    fn label(&self) -> &'static str {
+        "DisCoPy"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn optimisable(&self) -> bool {
+        false
+    }
+
+    fn parse(&self, source: &str) -> Result<ParseOutput, ParseError> {
+        let diagram = Diagram::from_json(source).map_err(|err| ParseError::Syntax {
+            language: self.label(),
+            message: err.to_string(),
+            line_col: None,
+        })?;
+        Ok(Box::new(diagram))
+    }
+}
+
+impl Program for Diagram {
+    fn compile(&self, _settings: &Settings, solver: Solver) -> anyhow::Result<GraphUi> {
+        tracing::debug!("Converting DisCoPy diagram to hypergraph...");
+        Ok(GraphUi::new(self.to_graph()?, solver))
+    }
+
+    fn is_isomorphic(&self, other: &dyn Program, _settings: &Settings) -> anyhow::Result<bool> {
+        let other = same_language::<Self>(other)?;
+        Ok(is_isomorphic(&self.to_graph()?, &other.to_graph()?))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,94 @@
+use std::any::Any;
+
+use dot_structures::Graph;
+use eframe::egui;
+use sd_core::{
+    dot::{dot_to_graph, DotSettings, DotWeight},
+    hypergraph::{isomorphism::is_isomorphic, Hypergraph},
+    interactive::InteractiveGraph,
+    lp::Solver,
+};
+
+use super::{same_language, LanguageDef, Settings};
+use crate::{
+    graph_ui::{GraphUi, GraphUiInternal, GraphView},
+    parser::{ParseError, ParseOutput, Program},
+};
+
+pub struct DotLanguage;
+
+/// The settings of this language, kept among the others in [`Settings`].
+fn dot_settings(settings: &Settings) -> DotSettings {
+    settings.language(DotLanguage.name())
+}
+
+impl LanguageDef for DotLanguage {
+    fn name(&self) -> &'static str {
+        "dot"
+    }
+
+    fn label(&self) -> &'static str {
+        "Dot"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["dot", "gv"]
+    }
+
+    fn optimisable(&self) -> bool {
+        false
+    }
+
+    fn parse(&self, source: &str) -> Result<ParseOutput, ParseError> {
+        let graph = graphviz_rust::parse(source).map_err(|message| ParseError::Syntax {
+            language: self.label(),
+            message,
+            line_col: None,
+        })?;
+        Ok(Box::new(graph))
+    }
+
+    fn has_settings(&self) -> bool {
+        true
+    }
+
+    fn settings_ui(&self, ui: &mut egui::Ui, settings: &mut Settings) -> bool {
+        let mut dot = dot_settings(settings);
+        let mut changed = false;
+        if ui.selectable_label(dot.invert, "Invert edges").clicked() {
+            dot.invert = !dot.invert;
+            settings.set_language(self.name(), &dot);
+            changed = true;
+        }
+        if ui.selectable_label(dot.collect, "Collect edges").clicked() {
+            dot.collect = !dot.collect;
+            settings.set_language(self.name(), &dot);
+            changed = true;
+        }
+        changed
+    }
+}
+
+impl Program for Graph {
+    fn compile(&self, settings: &Settings, solver: Solver) -> anyhow::Result<GraphUi> {
+        tracing::debug!("Converting dot to hypergraph...");
+        Ok(GraphUi::new(
+            dot_to_graph(self, dot_settings(settings))?,
+            solver,
+        ))
+    }
+
+    fn is_isomorphic(&self, other: &dyn Program, settings: &Settings) -> anyhow::Result<bool> {
+        let other = same_language::<Self>(other)?;
+        Ok(is_isomorphic(
+            &dot_to_graph(self, dot_settings(settings))?,
+            &dot_to_graph(other, dot_settings(settings))?,
+        ))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl GraphView for GraphUiInternal<InteractiveGraph<Hypergraph<DotWeight>>> {}
@@ -0,0 +1,138 @@
+use std::{
+    any::Any,
+    sync::{Mutex, RwLock},
+};
+
+use anyhow::anyhow;
+use sd_core::{
+    graph::SyntaxHypergraph,
+    hypergraph::isomorphism::is_isomorphic,
+    interactive::InteractiveGraph,
+    language::dynamic::{Dynamic, DynamicError, DynamicLanguage, Expr},
+    lp::Solver,
+};
+
+use super::{is_builtin, optimised, same_language, LanguageDef, Settings, UiLanguage};
+use crate::{
+    graph_ui::{GraphUi, GraphUiInternal, GraphView},
+    parser::{ParseError, ParseOutput, Program},
+    selection::Selection,
+};
+
+/// A language loaded at runtime from a pest grammar and a mapping of its rules.
+pub struct RuntimeLanguage {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    /// The grammar and mapping, which are replaced when the language is loaded again.
+    language: RwLock<DynamicLanguage>,
+}
+
+/// Every language loaded so far, which are reused when a language of the same name is loaded again.
+static LOADED: Mutex<Vec<&'static RuntimeLanguage>> = Mutex::new(Vec::new());
+
+/// Load a language from a pest grammar and a TOML mapping of its rules,
+/// and add it to the registry under `name`, which is also its file extension.
+///
+/// # Errors
+///
+/// This function will return an error if the grammar or mapping is invalid,
+/// or `name` is the name or file extension of a built-in language.
+pub fn load_language(name: &str, grammar: &str, mapping: &str) -> anyhow::Result<UiLanguage> {
+    if is_builtin(name) {
+        return Err(anyhow!("{name} is already the name of a built-in language"));
+    }
+    let dynamic = DynamicLanguage::new(grammar, mapping)?;
+
+    let mut loaded = LOADED.lock().unwrap();
+    if let Some(language) = loaded.iter().find(|language| language.name == name) {
+        *language.language.write().unwrap() = dynamic;
+        return Ok(UiLanguage::register(*language));
+    }
+
+    // Loaded languages live until the app exits.
+    let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+    let language: &'static RuntimeLanguage = Box::leak(Box::new(RuntimeLanguage {
+        name,
+        extensions: Box::leak(Box::new([name])),
+        language: RwLock::new(dynamic),
+    }));
+    loaded.push(language);
+    Ok(UiLanguage::register(language))
+}
+
+/// A program of a language loaded at runtime, which is only comparable to programs of the same language.
+struct RuntimeProgram {
+    language: &'static str,
+    expr: Expr,
+}
+
+impl LanguageDef for RuntimeLanguage {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn label(&self) -> &'static str {
+        self.name
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        self.extensions
+    }
+
+    fn parse(&self, source: &str) -> Result<ParseOutput, ParseError> {
+        let expr = self
+            .language
+            .read()
+            .unwrap()
+            .parse(source)
+            .map_err(|err| match err {
+                DynamicError::Parse { message, line_col } => ParseError::Syntax {
+                    language: self.label(),
+                    message,
+                    line_col: Some(line_col),
+                },
+                err => ParseError::Syntax {
+                    language: self.label(),
+                    message: err.to_string(),
+                    line_col: None,
+                },
+            })?;
+        Ok(Box::new(RuntimeProgram {
+            language: self.name,
+            expr,
+        }))
+    }
+}
+
+impl Program for RuntimeProgram {
+    fn compile(&self, settings: &Settings, solver: Solver) -> anyhow::Result<GraphUi> {
+        tracing::debug!("Converting runtime language to hypergraph...");
+        let graph = self.expr.to_graph(false)?;
+        Ok(GraphUi::new(optimised(graph, settings)?, solver))
+    }
+
+    fn is_isomorphic(&self, other: &dyn Program, _settings: &Settings) -> anyhow::Result<bool> {
+        let other = same_language::<Self>(other)?;
+        if other.language != self.language {
+            return Err(anyhow!("programs are in different languages"));
+        }
+        Ok(is_isomorphic(
+            &self.expr.to_graph(false)?,
+            &other.expr.to_graph(false)?,
+        ))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl GraphView for GraphUiInternal<InteractiveGraph<SyntaxHypergraph<Dynamic>>> {
+    fn selection(&self, name: String, solver: Solver) -> Option<Selection> {
+        Some(Selection::new(self, name, solver))
+    }
+
+    fn inspected_details(&self) -> Vec<(&'static str, String)> {
+        self.syntax_details()
+    }
+}
@@ -0,0 +1,105 @@
+use std::any::Any;
+
+use eframe::egui;
+use from_pest::{ConversionError, FromPest};
+use pest::Parser;
+use sd_core::{
+    graph::SyntaxHypergraph,
+    hypergraph::isomorphism::is_isomorphic,
+    interactive::InteractiveGraph,
+    language::llvm::{
+        internal::{LlvmParser, Rule, TopLevelItem},
+        Expr, Llvm, LlvmSettings,
+    },
+    lp::Solver,
+};
+
+use super::{optimised, same_language, LanguageDef, Settings};
+use crate::{
+    graph_ui::{GraphUi, GraphUiInternal, GraphView},
+    parser::{ParseError, ParseOutput, Program},
+    selection::Selection,
+};
+
+pub struct LlvmLanguage;
+
+/// The settings of this language, kept among the others in [`Settings`].
+fn llvm_settings(settings: &Settings) -> LlvmSettings {
+    settings.language(LlvmLanguage.name())
+}
+
+impl LanguageDef for LlvmLanguage {
+    fn name(&self) -> &'static str {
+        "llvm"
+    }
+
+    fn label(&self) -> &'static str {
+        "LLVM IR"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ll"]
+    }
+
+    fn parse(&self, source: &str) -> Result<ParseOutput, ParseError> {
+        let mut pairs = LlvmParser::parse(Rule::toplevel, source)
+            .map_err(|err| ParseError::pest(self.label(), err))?;
+
+        let items = Vec::<TopLevelItem>::from_pest(&mut pairs)?;
+        if pairs.next().is_some_and(|pair| pair.as_rule() != Rule::EOI) {
+            return Err(ConversionError::Extraneous {
+                current_node: "TopLevelItem",
+            }
+            .into());
+        }
+        Ok(Box::new(Expr::from(items)))
+    }
+
+    fn has_settings(&self) -> bool {
+        true
+    }
+
+    fn settings_ui(&self, ui: &mut egui::Ui, settings: &mut Settings) -> bool {
+        let mut llvm = llvm_settings(settings);
+        if ui
+            .selectable_label(llvm.sym_name_linking, "Link symbols")
+            .clicked()
+        {
+            llvm.sym_name_linking = !llvm.sym_name_linking;
+            settings.set_language(self.name(), &llvm);
+            return true;
+        }
+        false
+    }
+}
+
+impl Program for Expr {
+    fn compile(&self, settings: &Settings, solver: Solver) -> anyhow::Result<GraphUi> {
+        tracing::debug!("Converting llvm to hypergraph...");
+        let graph = self.to_graph(llvm_settings(settings).sym_name_linking)?;
+        Ok(GraphUi::new(optimised(graph, settings)?, solver))
+    }
+
+    fn is_isomorphic(&self, other: &dyn Program, settings: &Settings) -> anyhow::Result<bool> {
+        let other = same_language::<Self>(other)?;
+        let linking = llvm_settings(settings).sym_name_linking;
+        Ok(is_isomorphic(
+            &self.to_graph(linking)?,
+            &other.to_graph(linking)?,
+        ))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl GraphView for GraphUiInternal<InteractiveGraph<SyntaxHypergraph<Llvm>>> {
+    fn selection(&self, name: String, solver: Solver) -> Option<Selection> {
+        Some(Selection::new(self, name, solver))
+    }
+
+    fn inspected_details(&self) -> Vec<(&'static str, String)> {
+        self.syntax_details()
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    any::Any,
+    borrow::Cow,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use eframe::egui;
+use from_pest::FromPest;
+use pest::Parser;
+use sd_core::{
+    graph::SyntaxHypergraph,
+    hypergraph::{isomorphism::is_isomorphic, traits::WithWeight, Node},
+    interactive::InteractiveGraph,
+    language::mlir::{
+        dialect::DialectRegistry,
+        internal::{MlirParser, Rule, TopLevelItem},
+        Expr, Mlir, MlirSettings, Module,
+    },
+    lp::Solver,
+};
+
+use super::{optimised, same_language, LanguageDef, Settings};
+use crate::{
+    graph_ui::{GraphUi, GraphUiInternal, GraphView},
+    parser::{ParseError, ParseOutput, Program},
+    selection::Selection,
+};
+
+/// The dialects which mlir programs are parsed with.
+static DIALECTS: OnceLock<Mutex<Arc<DialectRegistry>>> = OnceLock::new();
+
+/// Parse mlir programs with the dialects of a dialect file as well as the built-in ones.
+pub fn set_dialects(dialects: DialectRegistry) {
+    *DIALECTS.get_or_init(Mutex::default).lock().unwrap() = Arc::new(dialects);
+}
+
+pub struct MlirLanguage;
+
+/// The settings of this language, kept among the others in [`Settings`].
+fn mlir_settings(settings: &Settings) -> MlirSettings {
+    settings.language(MlirLanguage.name())
+}
+
+impl LanguageDef for MlirLanguage {
+    fn name(&self) -> &'static str {
+        "mlir"
+    }
+
+    fn label(&self) -> &'static str {
+        "Mlir"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["mlir"]
+    }
+
+    fn parse(&self, source: &str) -> Result<ParseOutput, ParseError> {
+        let mut pairs = MlirParser::parse(Rule::toplevel, source)
+            .map_err(|err| ParseError::pest(self.label(), err))?;
+
+        let items = Vec::<TopLevelItem>::from_pest(&mut pairs)?;
+        let dialects = DIALECTS.get_or_init(Mutex::default).lock().unwrap().clone();
+        Ok(Box::new(Module::new(items, &dialects)))
+    }
+
+    fn has_settings(&self) -> bool {
+        true
+    }
+
+    fn settings_ui(&self, ui: &mut egui::Ui, settings: &mut Settings) -> bool {
+        let mut mlir = mlir_settings(settings);
+        if ui
+            .selectable_label(mlir.sym_name_linking, "Link symbols")
+            .clicked()
+        {
+            mlir.sym_name_linking = !mlir.sym_name_linking;
+            settings.set_language(self.name(), &mlir);
+            return true;
+        }
+        if ui
+            .selectable_label(mlir.expand_aliases, "Expand aliases")
+            .on_hover_text(
+                "Show attributes and types with their aliases replaced by what they stand for",
+            )
+            .clicked()
+        {
+            mlir.expand_aliases = !mlir.expand_aliases;
+            settings.set_language(self.name(), &mlir);
+            return true;
+        }
+        false
+    }
+}
+
+/// The operations of a module, with their aliases expanded if the settings ask for it.
+fn expr<'a>(module: &'a Module, settings: &Settings) -> Cow<'a, Expr> {
+    if mlir_settings(settings).expand_aliases {
+        Cow::Owned(module.expanded())
+    } else {
+        Cow::Borrowed(&module.expr)
+    }
+}
+
+impl Program for Module {
+    fn compile(&self, settings: &Settings, solver: Solver) -> anyhow::Result<GraphUi> {
+        tracing::debug!("Converting mlir to hypergraph...");
+        let graph = expr(self, settings).to_graph(mlir_settings(settings).sym_name_linking)?;
+        Ok(GraphUi::new(optimised(graph, settings)?, solver))
+    }
+
+    fn is_isomorphic(&self, other: &dyn Program, settings: &Settings) -> anyhow::Result<bool> {
+        let other = same_language::<Self>(other)?;
+        let linking = mlir_settings(settings).sym_name_linking;
+        Ok(is_isomorphic(
+            &expr(self, settings).to_graph(linking)?,
+            &expr(other, settings).to_graph(linking)?,
+        ))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl GraphView for GraphUiInternal<InteractiveGraph<SyntaxHypergraph<Mlir>>> {
+    fn selection(&self, name: String, solver: Solver) -> Option<Selection> {
+        Some(Selection::new(self, name, solver))
+    }
+
+    fn inspected_details(&self) -> Vec<(&'static str, String)> {
+        let mut details = self.syntax_details();
+        if let Some(Node::Operation(op)) = self.graph.inspected() {
+            let op = op.weight();
+            details.insert(0, ("Name", op.name.clone()));
+            for (field, attributes) in [
+                ("Properties", &op.properties),
+                ("Attributes", &op.attributes),
+            ] {
+                if !attributes.is_empty() {
+                    let lines: Vec<_> = attributes.iter().map(ToString::to_string).collect();
+                    details.push((field, lines.join("\n")));
+                }
+            }
+            if let Some(location) = &op.location {
+                let location = match location.file_line() {
+                    Some((file, line)) => format!("{file}:{line}"),
+                    None => location.to_string(),
+                };
+                details.push(("Location", location));
+            }
+        }
+        details
+    }
+
+    fn inspected_location(&self) -> Option<(String, usize)> {
+        let Some(Node::Operation(op)) = self.graph.inspected() else {
+            return None;
+        };
+        let weight = op.weight();
+        let (file, line) = weight.location.as_ref()?.file_line()?;
+        Some((file.to_owned(), line))
+    }
+}
@@ -0,0 +1,227 @@
+//! The registry of languages which can be visualised.
+//!
+//! Adding a language only requires implementing [`LanguageDef`] (and [`Program`] for its parse output)
+//! and listing it in [`LANGUAGES`]; the app, the file dialogs and the command line discover it from here.
+//! Languages defined by a pest grammar can also be registered while the app is running with [`load_language`].
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::Mutex,
+};
+
+use anyhow::anyhow;
+use eframe::egui;
+use sd_core::{graph::SyntaxHypergraph, language::Language};
+use serde::{de, de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::parser::{ParseError, ParseOutput, Program};
+
+mod chil;
+mod discopy;
+mod dot;
+mod dynamic;
+mod llvm;
+mod mlir;
+mod qasm;
+mod spartan;
+mod term;
+
+/// Everything the app needs to know about a language.
+pub trait LanguageDef: Send + Sync {
+    /// A unique lowercase name, used for syntax highlighting and saved sessions.
+    fn name(&self) -> &'static str;
+
+    /// The name shown in the user interface.
+    fn label(&self) -> &'static str;
+
+    /// File extensions (without the leading dot) of programs in this language.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// A sublime syntax definition used for highlighting, if there is one.
+    fn syntax(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Whether graphs in this language can be optimised.
+    fn optimisable(&self) -> bool {
+        true
+    }
+
+    /// Parse a program in this language.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the program is malformed.
+    fn parse(&self, source: &str) -> Result<ParseOutput, ParseError>;
+
+    /// Whether this language has settings of its own.
+    fn has_settings(&self) -> bool {
+        false
+    }
+
+    /// Show the settings of this language, returning whether they changed.
+    ///
+    /// Languages keep their own settings in [`Settings`] with [`Settings::language`] and [`Settings::set_language`].
+    fn settings_ui(&self, _ui: &mut egui::Ui, _settings: &mut Settings) -> bool {
+        false
+    }
+}
+
+/// All registered languages, in the order they are shown in the user interface.
+static LANGUAGES: [&dyn LanguageDef; 8] = [
+    &chil::ChilLanguage,
+    &spartan::SpartanLanguage,
+    &mlir::MlirLanguage,
+    &llvm::LlvmLanguage,
+    &qasm::QasmLanguage,
+    &term::TermLanguage,
+    &discopy::DiscopyLanguage,
+    &dot::DotLanguage,
+];
+
+/// Whether `name` is the name or a file extension of a built-in language.
+fn is_builtin(name: &str) -> bool {
+    LANGUAGES
+        .iter()
+        .any(|language| language.name() == name || language.extensions().contains(&name))
+}
+
+/// Languages loaded while the app is running, shown after the built-in ones.
+static RUNTIME_LANGUAGES: Mutex<Vec<&'static dyn LanguageDef>> = Mutex::new(Vec::new());
+
+pub use dynamic::load_language;
+pub use mlir::set_dialects;
+
+/// Settings which affect how programs are compiled to graphs.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, Serialize, Deserialize)]
+pub struct Settings {
+    /// Merge common subexpressions and remove unused operations.
+    pub optimise: bool,
+    /// The settings of each language with settings of its own, serialised under the name of the language.
+    #[serde(default)]
+    languages: BTreeMap<String, String>,
+}
+
+impl Settings {
+    /// The settings of the language called `name`, which are the default ones if they were never set.
+    pub(crate) fn language<S: Default + DeserializeOwned>(&self, name: &str) -> S {
+        self.languages
+            .get(name)
+            .and_then(|settings| ron::from_str(settings).ok())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn set_language<S: Serialize>(&mut self, name: &str, settings: &S) {
+        match ron::to_string(settings) {
+            Ok(settings) => {
+                self.languages.insert(name.to_owned(), settings);
+            }
+            Err(err) => tracing::warn!("could not save the settings of {name}: {err}"),
+        }
+    }
+}
+
+/// A handle to a registered language.
+#[derive(Copy, Clone)]
+pub struct UiLanguage(&'static dyn LanguageDef);
+
+pub(crate) const SPARTAN: UiLanguage = UiLanguage(&spartan::SpartanLanguage);
+
+impl UiLanguage {
+    /// All registered languages.
+    pub fn all() -> impl Iterator<Item = Self> {
+        let runtime = RUNTIME_LANGUAGES.lock().unwrap().clone();
+        LANGUAGES.iter().copied().chain(runtime).map(Self)
+    }
+
+    /// Add a language to the registry, replacing any loaded earlier with the same name.
+    pub(crate) fn register(language: &'static dyn LanguageDef) -> Self {
+        let mut runtime = RUNTIME_LANGUAGES.lock().unwrap();
+        runtime.retain(|other| other.name() != language.name());
+        runtime.push(language);
+        Self(language)
+    }
+
+    /// Find a language by its name.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().find(|language| language.name() == name)
+    }
+
+    /// Guess the language of a file from its extension.
+    #[must_use]
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Self::all().find(|language| language.extensions().contains(&extension))
+    }
+}
+
+impl Deref for UiLanguage {
+    type Target = dyn LanguageDef;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl Default for UiLanguage {
+    fn default() -> Self {
+        SPARTAN
+    }
+}
+
+impl PartialEq for UiLanguage {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for UiLanguage {}
+
+impl Hash for UiLanguage {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name().hash(state);
+    }
+}
+
+impl Debug for UiLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UiLanguage").field(&self.name()).finish()
+    }
+}
+
+impl Serialize for UiLanguage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for UiLanguage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::from_name(&name).ok_or_else(|| de::Error::custom(format!("unknown language {name}")))
+    }
+}
+
+/// Optimise the graph if requested.
+fn optimised<T: 'static + Language>(
+    graph: SyntaxHypergraph<T>,
+    settings: &Settings,
+) -> anyhow::Result<SyntaxHypergraph<T>> {
+    if settings.optimise {
+        tracing::debug!("Optimising hypergraph...");
+        Ok(sd_core::optimise::optimise(&graph)?)
+    } else {
+        Ok(graph)
+    }
+}
+
+/// The other program of a comparison, which must be in the same language.
+fn same_language<P: 'static>(other: &dyn Program) -> anyhow::Result<&P> {
+    other
+        .as_any()
+        .downcast_ref()
+        .ok_or_else(|| anyhow!("programs are in different languages"))
+}
@@ -0,0 +1,83 @@
+use std::any::Any;
+
+use from_pest::{ConversionError, FromPest};
+use pest::Parser;
+use sd_core::{
+    graph::SyntaxHypergraph,
+    hypergraph::isomorphism::is_isomorphic,
+    interactive::InteractiveGraph,
+    language::qasm::{Expr, Qasm, QasmParser, Rule, Statement},
+    lp::Solver,
+};
+
+use super::{optimised, same_language, LanguageDef, Settings};
+use crate::{
+    graph_ui::{GraphUi, GraphUiInternal, GraphView},
+    parser::{ParseError, ParseOutput, Program},
+    selection::Selection,
+};
+
+pub struct QasmLanguage;
+
+impl LanguageDef for QasmLanguage {
+    fn name(&self) -> &'static str {
+        "qasm"
+    }
+
+    fn label(&self) -> &'static str {
+        "OpenQASM"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["qasm"]
+    }
+
+    fn parse(&self, source: &str) -> Result<ParseOutput, ParseError> {
+        let mut pairs = QasmParser::parse(Rule::program, source)
+            .map_err(|err| ParseError::pest(self.label(), err))?;
+
+        let statements = Vec::<Statement>::from_pest(&mut pairs)?;
+        if pairs.next().is_some_and(|pair| pair.as_rule() != Rule::EOI) {
+            return Err(ConversionError::Extraneous {
+                current_node: "Statement",
+            }
+            .into());
+        }
+        let expr = Expr::try_from(statements).map_err(|err| ParseError::Syntax {
+            language: self.label(),
+            message: err.to_string(),
+            line_col: None,
+        })?;
+        Ok(Box::new(expr))
+    }
+}
+
+impl Program for Expr {
+    fn compile(&self, settings: &Settings, solver: Solver) -> anyhow::Result<GraphUi> {
+        tracing::debug!("Converting qasm to hypergraph...");
+        let graph = self.to_graph(false)?;
+        Ok(GraphUi::new(optimised(graph, settings)?, solver))
+    }
+
+    fn is_isomorphic(&self, other: &dyn Program, _settings: &Settings) -> anyhow::Result<bool> {
+        let other = same_language::<Self>(other)?;
+        Ok(is_isomorphic(
+            &self.to_graph(false)?,
+            &other.to_graph(false)?,
+        ))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl GraphView for GraphUiInternal<InteractiveGraph<SyntaxHypergraph<Qasm>>> {
+    fn selection(&self, name: String, solver: Solver) -> Option<Selection> {
+        Some(Selection::new(self, name, solver))
+    }
+
+    fn inspected_details(&self) -> Vec<(&'static str, String)> {
+        self.syntax_details()
+    }
+}
@@ -0,0 +1,86 @@
+use std::any::Any;
+
+use from_pest::FromPest;
+use pest::Parser;
+use sd_core::{
+    graph::SyntaxHypergraph,
+    hypergraph::isomorphism::is_isomorphic,
+    interactive::InteractiveGraph,
+    language::spartan::{Expr, Rule, Spartan, SpartanParser},
+    lp::Solver,
+};
+
+use super::{optimised, same_language, LanguageDef, Settings};
+use crate::{
+    graph_ui::{GraphUi, GraphUiInternal, GraphView},
+    parser::{ParseError, ParseOutput, Program},
+    selection::Selection,
+};
+
+pub struct SpartanLanguage;
+
+impl LanguageDef for SpartanLanguage {
+    fn name(&self) -> &'static str {
+        "spartan"
+    }
+
+    fn label(&self) -> &'static str {
+        "Spartan"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["sd", "spartan"]
+    }
+
+    fn syntax(&self) -> Option<&'static str> {
+        Some(include_str!("spartan.sublime-syntax"))
+    }
+
+    fn parse(&self, source: &str) -> Result<ParseOutput, ParseError> {
+        let mut pairs = SpartanParser::parse(Rule::program, source)
+            .map_err(|err| ParseError::pest(self.label(), err))?;
+        Ok(Box::new(Expr::from_pest(&mut pairs)?))
+    }
+}
+
+impl Program for Expr {
+    fn compile(&self, settings: &Settings, solver: Solver) -> anyhow::Result<GraphUi> {
+        tracing::debug!("Converting spartan to hypergraph...");
+        let graph = self.to_graph(false)?;
+        Ok(GraphUi::new_inlinable(optimised(graph, settings)?, solver))
+    }
+
+    fn is_isomorphic(&self, other: &dyn Program, _settings: &Settings) -> anyhow::Result<bool> {
+        let other = same_language::<Self>(other)?;
+        Ok(is_isomorphic(
+            &self.to_graph(false)?,
+            &other.to_graph(false)?,
+        ))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl GraphView for GraphUiInternal<InteractiveGraph<SyntaxHypergraph<Spartan>>> {
+    fn can_extract(&self) -> bool {
+        true
+    }
+
+    fn extract_selection(&self) -> anyhow::Result<String> {
+        self.extracted_code()
+    }
+
+    fn inline_requested(&mut self) -> Option<anyhow::Result<String>> {
+        self.inlined_code()
+    }
+
+    fn selection(&self, name: String, solver: Solver) -> Option<Selection> {
+        Some(Selection::new(self, name, solver))
+    }
+
+    fn inspected_details(&self) -> Vec<(&'static str, String)> {
+        self.syntax_details()
+    }
+}
@@ -0,0 +1,68 @@
+use std::any::Any;
+
+use sd_core::{
+    hypergraph::{isomorphism::is_isomorphic, Hypergraph},
+    interactive::InteractiveGraph,
+    lp::Solver,
+    term::{Term, TermError, TermWeight},
+};
+
+use super::{same_language, LanguageDef, Settings};
+use crate::{
+    graph_ui::{GraphUi, GraphUiInternal, GraphView},
+    parser::{ParseError, ParseOutput, Program},
+};
+
+pub struct TermLanguage;
+
+impl LanguageDef for TermLanguage {
+    fn name(&self) -> &'static str {
+        "term"
+    }
+
+    fn label(&self) -> &'static str {
+        "Monoidal term"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["term"]
+    }
+
+    fn optimisable(&self) -> bool {
+        false
+    }
+
+    fn parse(&self, source: &str) -> Result<ParseOutput, ParseError> {
+        let term = Term::parse(source).map_err(|err| match err {
+            TermError::Parse { message, line_col } => ParseError::Syntax {
+                language: self.label(),
+                message,
+                line_col: Some(line_col),
+            },
+            err => ParseError::Syntax {
+                language: self.label(),
+                line_col: err.line_col().cloned(),
+                message: err.to_string(),
+            },
+        })?;
+        Ok(Box::new(term))
+    }
+}
+
+impl Program for Term {
+    fn compile(&self, _settings: &Settings, solver: Solver) -> anyhow::Result<GraphUi> {
+        tracing::debug!("Converting monoidal term to hypergraph...");
+        Ok(GraphUi::new(self.to_graph()?, solver))
+    }
+
+    fn is_isomorphic(&self, other: &dyn Program, _settings: &Settings) -> anyhow::Result<bool> {
+        let other = same_language::<Self>(other)?;
+        Ok(is_isomorphic(&self.to_graph()?, &other.to_graph()?))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl GraphView for GraphUiInternal<InteractiveGraph<Hypergraph<TermWeight>>> {}
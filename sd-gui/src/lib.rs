@@ -2,16 +2,24 @@
 mod app;
 pub(crate) mod code_generator;
 pub(crate) mod code_ui;
+mod compare;
 pub(crate) mod graph_ui;
 pub(crate) mod highlighter;
+pub(crate) mod inspector;
+pub(crate) mod languages;
+pub(crate) mod minimap;
 pub(crate) mod panzoom;
 pub(crate) mod parser;
 pub(crate) mod selection;
+pub(crate) mod session;
 pub(crate) mod shape_generator;
+pub(crate) mod source_viewer;
 pub(crate) mod squiggly_line;
 
 pub use app::App;
-pub use parser::UiLanguage;
+pub use compare::compare;
+pub use languages::{load_language, set_dialects, UiLanguage};
+pub use shape_generator::set_style_sheet;
 
 #[cfg(not(target_arch = "wasm32"))]
 macro_rules! spawn {
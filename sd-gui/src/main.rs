@@ -3,8 +3,12 @@
 
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Parser,
+};
 use sd_core::lp::Solver;
+use sd_gui::UiLanguage;
 
 #[derive(Parser)]
 #[command(
@@ -26,25 +30,89 @@ use sd_core::lp::Solver;
 ///
 /// Please report bugs at <https://github.com/sd-visualiser/sd-visualiser/issues>.
 struct Args {
-    /// Read in a chil file
+    /// Read in a file, whose language is guessed from its extension
+    #[arg(value_name = "FILE")]
+    file: Option<PathBuf>,
+
+    /// Language of the files read in
+    #[arg(
+        long,
+        value_parser = PossibleValuesParser::new(UiLanguage::all().map(|language| language.name()))
+            .map(|name| UiLanguage::from_name(&name).unwrap())
+    )]
+    language: Option<UiLanguage>,
+
+    /// Pest grammar of a language to load, used for the files read in instead of `--language`
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "mapping",
+        conflicts_with = "language"
+    )]
+    grammar: Option<PathBuf>,
+
+    /// TOML mapping from rules of the grammar to parts of a program
+    #[arg(long, value_name = "FILE", requires = "grammar")]
+    mapping: Option<PathBuf>,
+
+    /// Compare two files for equivalence (up to renaming of bound variables) instead of opening the visualiser
+    #[arg(long, num_args = 2, value_names = ["FILE", "FILE"])]
+    compare: Option<Vec<PathBuf>>,
+
+    /// TOML file declaring the terminators and other traits of the operations of MLIR dialects
     #[arg(long, value_name = "FILE")]
-    chil: Option<PathBuf>,
+    dialects: Option<PathBuf>,
 
-    /// Read in a spartan file
+    /// TOML style sheet saying how to draw operations
     #[arg(long, value_name = "FILE")]
+    style: Option<PathBuf>,
+
+    /// Choose LP solver
+    #[arg(long, value_enum, default_value_t)]
+    solver: Solver,
+
+    /// Minimise swaps exactly with an integer program for small graphs, instead of heuristically
+    #[arg(long)]
+    exact_swaps: bool,
+
+    /// Read in a chil file, the same as `--language chil FILE`
+    #[arg(long, value_name = "FILE", hide = true, conflicts_with_all = LEGACY_CONFLICTS)]
+    chil: Option<PathBuf>,
+
+    /// Read in a spartan file, the same as `--language spartan FILE`
+    #[arg(long, value_name = "FILE", hide = true, conflicts_with_all = LEGACY_CONFLICTS)]
     spartan: Option<PathBuf>,
 
-    /// Read in an mlir file
-    #[arg(long, value_name = "FILE")]
+    /// Read in an mlir file, the same as `--language mlir FILE`
+    #[arg(long, value_name = "FILE", hide = true, conflicts_with_all = LEGACY_CONFLICTS)]
     mlir: Option<PathBuf>,
 
-    /// Read in an dot file
-    #[arg(long, value_name = "FILE")]
+    /// Read in a dot file, the same as `--language dot FILE`
+    #[arg(long, value_name = "FILE", hide = true, conflicts_with_all = LEGACY_CONFLICTS)]
     dot: Option<PathBuf>,
+}
 
-    /// Choose LP solver
-    #[arg(long, value_enum, default_value_t)]
-    solver: Solver,
+/// Arguments which cannot be combined with the flags reading in a file of a given language.
+const LEGACY_CONFLICTS: [&str; 3] = ["file", "language", "grammar"];
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Args {
+    /// Turn the flags of earlier versions, such as `--chil FILE`, into a file and its language.
+    fn apply_legacy_flags(&mut self) {
+        let legacy = [
+            ("chil", &mut self.chil),
+            ("spartan", &mut self.spartan),
+            ("mlir", &mut self.mlir),
+            ("dot", &mut self.dot),
+        ];
+        if let Some((name, path)) = legacy
+            .into_iter()
+            .find_map(|(name, path)| Some((name, path.take()?)))
+        {
+            self.file = Some(path);
+            self.language = UiLanguage::from_name(name);
+        }
+    }
 }
 
 // When compiling natively:
@@ -59,10 +127,59 @@ fn main() -> anyhow::Result<()> {
         .with_thread_names(true)
         .init();
 
-    let args = Args::parse();
+    let mut args = Args::parse();
+    args.apply_legacy_flags();
+
+    let loaded = match (&args.grammar, &args.mapping) {
+        (Some(grammar), Some(mapping)) => Some(sd_gui::load_language(
+            &grammar
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            &std::fs::read_to_string(grammar)?,
+            &std::fs::read_to_string(mapping)?,
+        )?),
+        _ => None,
+    };
+
+    let language_of = |path: &PathBuf| {
+        loaded
+            .or(args.language)
+            .or_else(|| {
+                path.extension()
+                    .and_then(|extension| UiLanguage::from_extension(extension.to_str()?))
+            })
+            .ok_or_else(|| anyhow!("unknown language for file {}", path.display()))
+    };
+
+    if let Some(path) = &args.dialects {
+        sd_gui::set_dialects(std::fs::read_to_string(path)?.parse()?);
+    }
+
+    if let Some(paths) = &args.compare {
+        let languages = paths
+            .iter()
+            .map(language_of)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if languages[0] != languages[1] {
+            return Err(anyhow!("files are in different languages"));
+        }
+        let left = std::fs::read_to_string(&paths[0])?;
+        let right = std::fs::read_to_string(&paths[1])?;
+        if sd_gui::compare(&left, &right, languages[0])? {
+            println!("equivalent");
+            return Ok(());
+        }
+        println!("not equivalent");
+        std::process::exit(1);
+    }
 
     tracing::info!("lp solver: {:?}", args.solver);
 
+    if let Some(path) = &args.style {
+        sd_gui::set_style_sheet(std::fs::read_to_string(path)?.parse()?);
+    }
+
     let native_options = eframe::NativeOptions {
         viewport: ViewportBuilder {
             maximized: Some(true),
@@ -71,20 +188,9 @@ fn main() -> anyhow::Result<()> {
         ..Default::default()
     };
 
-    let file = if let Some(path) = args.chil {
-        let code = std::fs::read_to_string(path)?;
-        Some((code, sd_gui::UiLanguage::Chil))
-    } else if let Some(path) = args.spartan {
-        let code = std::fs::read_to_string(path)?;
-        Some((code, sd_gui::UiLanguage::Spartan))
-    } else if let Some(path) = args.mlir {
-        let code = std::fs::read_to_string(path)?;
-        Some((code, sd_gui::UiLanguage::Mlir))
-    } else if let Some(path) = args.dot {
-        let code = std::fs::read_to_string(path)?;
-        Some((code, sd_gui::UiLanguage::Dot))
-    } else {
-        None
+    let file = match &args.file {
+        Some(path) => Some((std::fs::read_to_string(path)?, language_of(path).ok())),
+        None => None,
     };
     eframe::run_native(
         "SD Visualiser",
@@ -92,8 +198,12 @@ fn main() -> anyhow::Result<()> {
         Box::new(move |cc| {
             let mut app = sd_gui::App::new(cc, args.solver);
 
+            if args.exact_swaps {
+                app.set_exact_swaps(true);
+            }
+
             if let Some((code, language)) = file {
-                app.set_file(&code, Some(language));
+                app.set_file(&code, language);
             }
 
             Box::new(app)
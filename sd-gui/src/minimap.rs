@@ -0,0 +1,93 @@
+use eframe::{
+    egui,
+    epaint::{Color32, Rect, Rounding, Stroke},
+};
+use sd_core::{common::Matchable, hypergraph::generic::Ctx};
+use sd_graphics::shape::{Shape, Shapes};
+
+use crate::panzoom::Panzoom;
+
+/// The length of the longest side of the minimap on screen.
+const MINIMAP_SIZE: f32 = 160.0;
+/// The gap between the minimap and the corner of the graph view.
+const MARGIN: f32 = 8.0;
+
+/// Draw an overview of a diagram in the bottom right corner of the graph view `rect`,
+/// showing its thunks, how densely packed it is, the matches of the search and the visible region.
+/// Clicking or dragging on the overview pans the view.
+/// Nothing is drawn when the whole diagram is visible.
+pub(crate) fn minimap<T: Ctx>(
+    ui: &egui::Ui,
+    rect: Rect,
+    shapes: &Shapes<T>,
+    panzoom: &mut Panzoom,
+    search: Option<&str>,
+) where
+    T::Operation: Matchable,
+    T::Thunk: Matchable,
+{
+    let viewport = *panzoom.transform(rect).from();
+    let Some(to_map) = shapes.overview(viewport, rect, MINIMAP_SIZE, MARGIN) else {
+        return;
+    };
+    let map_rect = *to_map.to();
+
+    let response = ui.interact(map_rect, ui.id().with("minimap"), egui::Sense::drag());
+    if let Some(pos) = response.interact_pointer_pos() {
+        panzoom.set_pan(to_map.inverse().transform_pos(pos));
+    }
+
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect(
+        map_rect.expand(MARGIN / 2.0),
+        Rounding::same(MARGIN / 2.0),
+        visuals.extreme_bg_color,
+        visuals.window_stroke(),
+    );
+
+    // Shade the regions of the diagram by how many shapes are drawn there
+    let max = shapes
+        .index
+        .density()
+        .map(|(_, n)| n)
+        .max()
+        .unwrap_or_default();
+    for (cell, n) in shapes.index.density() {
+        if n > 0 {
+            #[allow(clippy::cast_precision_loss)]
+            let opacity = 0.6 * n as f32 / max as f32;
+            painter.rect_filled(
+                to_map.transform_rect(cell).intersect(map_rect),
+                Rounding::ZERO,
+                visuals.text_color().gamma_multiply(opacity),
+            );
+        }
+    }
+
+    for shape in &shapes.shapes {
+        if let Shape::Rectangle { rect, .. } = shape {
+            painter.rect_stroke(
+                to_map.transform_rect(*rect),
+                Rounding::ZERO,
+                Stroke::new(1.0_f32, visuals.strong_text_color()),
+            );
+        }
+    }
+
+    if let Some(query) = search {
+        for shape in shapes.find(query) {
+            painter.circle_filled(
+                to_map.transform_pos(shape.center()),
+                2.0,
+                Color32::LIGHT_RED,
+            );
+        }
+    }
+
+    painter.rect_stroke(
+        to_map.transform_rect(viewport).intersect(map_rect),
+        Rounding::ZERO,
+        visuals.selection.stroke,
+    );
+}
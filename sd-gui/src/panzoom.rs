@@ -2,11 +2,12 @@ use eframe::{
     emath::RectTransform,
     epaint::{vec2, Pos2, Rect, Vec2},
 };
+use serde::{Deserialize, Serialize};
 
 const PAN_FACTOR: f32 = 10.0;
 const ZOOM_FACTOR: f32 = 1.25;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Panzoom {
     translation: Pos2,
     zoom: f32,
@@ -1,92 +1,57 @@
-use from_pest::{ConversionError, FromPest, Void};
-use pest::{error, Parser as _};
-use sd_core::language::{
-    chil::{self, ChilParser},
-    mlir::{
-        self,
-        internal::{MlirParser, TopLevelItem},
-    },
-    spartan::{self, SpartanParser},
+use std::any::Any;
+
+use from_pest::{ConversionError, Void};
+use pest::{
+    error::{Error as PestError, LineColLocation},
+    RuleType,
 };
+use sd_core::lp::Solver;
 use thiserror::Error;
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
-pub enum UiLanguage {
-    Chil,
-    #[default]
-    Spartan,
-    Mlir,
-    Dot,
-}
-
-impl UiLanguage {
-    pub(crate) const fn name(&self) -> &str {
-        match self {
-            Self::Chil => "chil",
-            Self::Spartan => "spartan",
-            Self::Mlir => "mlir",
-            Self::Dot => "dot",
-        }
-    }
+use crate::{graph_ui::GraphUi, languages::Settings};
+
+/// A parsed program in one of the registered languages.
+pub trait Program: Send {
+    /// Convert the program to a graph which can be displayed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the program cannot be converted to a hypergraph.
+    fn compile(&self, settings: &Settings, solver: Solver) -> anyhow::Result<GraphUi>;
+
+    /// Whether this program and `other` have isomorphic hypergraphs.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the programs are in different languages
+    /// or either cannot be converted to a hypergraph.
+    fn is_isomorphic(&self, other: &dyn Program, settings: &Settings) -> anyhow::Result<bool>;
+
+    fn as_any(&self) -> &dyn Any;
 }
 
-#[derive(Clone, Debug)]
-pub enum ParseOutput {
-    Chil(chil::Expr),
-    Spartan(spartan::Expr),
-    Mlir(mlir::Expr),
-    Dot(dot_structures::Graph),
-}
+pub type ParseOutput = Box<dyn Program>;
 
 #[derive(Clone, Debug, Error)]
 pub enum ParseError {
-    #[error("Chil parsing error:\n{0}")]
-    Chil(#[from] Box<error::Error<chil::Rule>>),
-
-    #[error("Spartan parsing error:\n{0}")]
-    Spartan(#[from] Box<error::Error<spartan::Rule>>),
-
-    #[error("Mlir parsing error:\n{0}")]
-    Mlir(#[from] Box<error::Error<mlir::internal::Rule>>),
-
-    #[error("Dot parsing error:\n{0}")]
-    Dot(String),
+    #[error("{language} parsing error:\n{message}")]
+    Syntax {
+        language: &'static str,
+        message: String,
+        /// Where the error occurred, if known.
+        line_col: Option<LineColLocation>,
+    },
 
     #[error("Conversion error:\n{0}")]
     Conversion(#[from] ConversionError<Void>),
 }
 
-pub fn parse(source: &str, language: UiLanguage) -> Result<ParseOutput, ParseError> {
-    match language {
-        UiLanguage::Chil => {
-            let mut pairs = ChilParser::parse(chil::Rule::program, source).map_err(Box::new)?;
-            let expr = chil::Expr::from_pest(&mut pairs)?;
-            Ok(ParseOutput::Chil(expr))
-        }
-        UiLanguage::Spartan => {
-            let mut pairs =
-                SpartanParser::parse(spartan::Rule::program, source).map_err(Box::new)?;
-            let expr = spartan::Expr::from_pest(&mut pairs)?;
-            Ok(ParseOutput::Spartan(expr))
-        }
-        UiLanguage::Mlir => {
-            let mut pairs =
-                MlirParser::parse(mlir::internal::Rule::toplevel, source).map_err(Box::new)?;
-
-            let items = Vec::<TopLevelItem>::from_pest(&mut pairs)?;
-            let ops: Vec<mlir::internal::Operation> = items
-                .into_iter()
-                .filter_map(|x| match x {
-                    TopLevelItem::Operation(y) => Some(y),
-                    TopLevelItem::Other(_) => None,
-                })
-                .collect();
-            let expr = mlir::Expr::from(ops);
-            Ok(ParseOutput::Mlir(expr))
-        }
-        UiLanguage::Dot => {
-            let graph = graphviz_rust::parse(source).map_err(ParseError::Dot)?;
-            Ok(ParseOutput::Dot(graph))
+impl ParseError {
+    pub(crate) fn pest<R: RuleType>(language: &'static str, err: PestError<R>) -> Self {
+        Self::Syntax {
+            language,
+            message: err.to_string(),
+            line_col: Some(err.line_col),
         }
     }
 }
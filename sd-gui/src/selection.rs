@@ -4,70 +4,82 @@ use delegate::delegate;
 use eframe::egui;
 use sd_core::{
     graph::SyntaxHypergraph,
-    interactive::InteractiveSubgraph,
-    language::{chil::Chil, mlir::Mlir, spartan::Spartan, Expr, Language, Thunk},
+    hypergraph::path::NodePath,
+    interactive::{InteractiveGraph, InteractiveSubgraph},
+    language::{Expr, Language, Thunk},
     lp::Solver,
     prettyprinter::PrettyPrint,
 };
+use sd_graphics::common::Orientation;
 
 use crate::{
-    code_generator::generate_code,
-    code_ui::code_ui,
-    graph_ui::{GraphUi, GraphUiInternal},
-    parser::UiLanguage,
+    code_generator::generate_code, code_ui::code_ui, graph_ui::GraphUiInternal, languages::SPARTAN,
 };
 
-pub enum Selection {
-    Chil(SelectionInternal<Chil>),
-    Mlir(SelectionInternal<Mlir>),
-    Spartan(SelectionInternal<Spartan>),
-}
+/// A window showing a saved selection on its own.
+pub struct Selection(Box<dyn SelectionView>);
 
 impl Selection {
+    pub(crate) fn new<T>(
+        graph_ui: &GraphUiInternal<InteractiveGraph<SyntaxHypergraph<T>>>,
+        name: String,
+        solver: Solver,
+    ) -> Self
+    where
+        T: 'static + Language,
+        Expr<T>: PrettyPrint,
+        Thunk<T>: PrettyPrint,
+    {
+        let mut selection = SelectionInternal::new(
+            graph_ui.graph.to_subgraph(),
+            name,
+            graph_ui.graph.selection_paths(),
+            solver,
+        );
+        selection.set_orientation(graph_ui.orientation());
+        selection.set_wire_labels(graph_ui.wire_labels());
+        selection.set_exact_swaps(graph_ui.exact_swaps());
+        Self(Box::new(selection))
+    }
+
     delegate! {
-        to match self {
-            Self::Chil(selection) => selection,
-            Self::Mlir(selection) => selection,
-            Self::Spartan(selection) => selection,
-        } {
+        to self.0 {
             pub(crate) fn ui(&mut self, ctx: &egui::Context);
             pub(crate) fn name(&self) -> &str;
             pub(crate) fn displayed(&mut self) -> &mut bool;
+            pub(crate) fn is_displayed(&self) -> bool;
+            pub(crate) fn nodes(&self) -> &[NodePath];
+            pub(crate) fn set_orientation(&mut self, orientation: Orientation);
+            pub(crate) fn set_wire_labels(&mut self, wire_labels: bool);
+            pub(crate) fn set_exact_swaps(&mut self, exact_swaps: bool);
         }
     }
+}
 
-    pub fn from_graph(graph_ui: &GraphUi, name: String, solver: Solver) -> Option<Self> {
-        match graph_ui {
-            GraphUi::Chil(graph_ui) => Some(Self::Chil(SelectionInternal::new(
-                graph_ui.graph.to_subgraph(),
-                name,
-                solver,
-            ))),
-            GraphUi::Mlir(graph_ui) => Some(Self::Mlir(SelectionInternal::new(
-                graph_ui.graph.to_subgraph(),
-                name,
-                solver,
-            ))),
-            GraphUi::Spartan(graph_ui) => Some(Self::Spartan(SelectionInternal::new(
-                graph_ui.graph.to_subgraph(),
-                name,
-                solver,
-            ))),
-            GraphUi::Dot(_) => None,
-        }
-    }
+trait SelectionView {
+    fn ui(&mut self, ctx: &egui::Context);
+    fn name(&self) -> &str;
+    fn displayed(&mut self) -> &mut bool;
+    fn is_displayed(&self) -> bool;
+    fn nodes(&self) -> &[NodePath];
+    fn set_orientation(&mut self, orientation: Orientation);
+    fn set_wire_labels(&mut self, wire_labels: bool);
+    fn set_exact_swaps(&mut self, exact_swaps: bool);
 }
 
-pub struct SelectionInternal<T: Language> {
+struct SelectionInternal<T: Language> {
     name: String,
     displayed: bool,
+    /// Paths of the selected nodes in the original graph, so the selection can be saved
+    nodes: Vec<NodePath>,
     graph_ui: GraphUiInternal<InteractiveSubgraph<SyntaxHypergraph<T>>>,
 }
 
 impl<T: 'static + Language> SelectionInternal<T> {
-    pub(crate) fn new(
+    fn new(
         subgraph: InteractiveSubgraph<SyntaxHypergraph<T>>,
         name: String,
+        nodes: Vec<NodePath>,
         solver: Solver,
     ) -> Self {
         let graph_ui = GraphUiInternal::new(subgraph, solver);
@@ -75,23 +87,47 @@ impl<T: 'static + Language> SelectionInternal<T> {
         Self {
             name,
             displayed: true,
+            nodes,
             graph_ui,
         }
     }
+}
 
-    pub(crate) fn name(&self) -> &str {
+impl<T> SelectionView for SelectionInternal<T>
+where
+    T: 'static + Language,
+    Expr<T>: PrettyPrint,
+    Thunk<T>: PrettyPrint,
+{
+    fn name(&self) -> &str {
         &self.name
     }
 
-    pub(crate) fn displayed(&mut self) -> &mut bool {
+    fn displayed(&mut self) -> &mut bool {
         &mut self.displayed
     }
 
-    pub(crate) fn ui(&mut self, ctx: &egui::Context)
-    where
-        Expr<T>: PrettyPrint,
-        Thunk<T>: PrettyPrint,
-    {
+    fn is_displayed(&self) -> bool {
+        self.displayed
+    }
+
+    fn nodes(&self) -> &[NodePath] {
+        &self.nodes
+    }
+
+    fn set_orientation(&mut self, orientation: Orientation) {
+        self.graph_ui.set_orientation(orientation);
+    }
+
+    fn set_wire_labels(&mut self, wire_labels: bool) {
+        self.graph_ui.set_wire_labels(wire_labels);
+    }
+
+    fn set_exact_swaps(&mut self, exact_swaps: bool) {
+        self.graph_ui.set_exact_swaps(exact_swaps);
+    }
+
+    fn ui(&mut self, ctx: &egui::Context) {
         egui::Window::new(self.name.clone())
             .open(&mut self.displayed)
             .show(ctx, |ui| {
@@ -99,7 +135,7 @@ impl<T: 'static + Language> SelectionInternal<T> {
                     let code = generate_code(&self.graph_ui.graph);
                     let guard = code.lock().unwrap();
                     if let Some(code) = guard.ready() {
-                        code_ui(&mut columns[0], &mut code.as_str(), UiLanguage::Spartan);
+                        code_ui(&mut columns[0], &mut code.as_str(), SPARTAN);
                     }
                     self.graph_ui.ui(&mut columns[1], None);
                 });
@@ -0,0 +1,74 @@
+use sd_core::{hypergraph::path::NodePath, interactive::InteractiveState};
+use sd_graphics::common::Orientation;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    languages::{Settings, UiLanguage},
+    panzoom::Panzoom,
+};
+
+/// Everything needed to reopen the visualiser where it was left.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Session {
+    pub code: String,
+    /// The language of the code, which is the default language if it is no longer registered
+    /// (e.g. one loaded from a grammar in an earlier run).
+    #[serde(deserialize_with = "language_or_default")]
+    pub language: UiLanguage,
+    pub settings: Settings,
+    #[serde(default)]
+    pub orientation: Orientation,
+    #[serde(default)]
+    pub wire_labels: bool,
+    /// Whether swaps are minimised exactly rather than heuristically
+    #[serde(default)]
+    pub exact_swaps: bool,
+    /// State of the compiled graph, restored once the code has been recompiled.
+    pub graph: Option<GraphState>,
+}
+
+fn language_or_default<'de, D: Deserializer<'de>>(deserializer: D) -> Result<UiLanguage, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    Ok(UiLanguage::from_name(&name).unwrap_or_else(|| {
+        tracing::warn!("unknown language {name} in session, using the default language");
+        UiLanguage::default()
+    }))
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GraphState {
+    pub interactive: InteractiveState,
+    pub panzoom: Panzoom,
+    pub selections: Vec<SelectionState>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SelectionState {
+    pub name: String,
+    pub displayed: bool,
+    pub nodes: Vec<NodePath>,
+}
+
+impl Session {
+    /// Parse a session saved with [`Session::to_ron`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the session is malformed.
+    pub fn from_ron(session: &str) -> anyhow::Result<Self> {
+        Ok(ron::from_str(session)?)
+    }
+
+    /// Serialise the session to a string which can be saved to a file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the session cannot be serialised.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn to_ron(&self) -> anyhow::Result<String> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+}
@@ -15,11 +15,21 @@ use sd_core::{
     lp::Solver,
     monoidal::{graph::MonoidalGraph, wired_graph::from_graph},
 };
-use sd_graphics::{common::Shapeable, layout::layout, render, shape::Shapes};
+use sd_graphics::{
+    common::{Orientation, Shapeable},
+    layout::layout,
+    render,
+    shape::Shapes,
+    style::StyleSheet,
+};
 
 static CACHE: OnceLock<Mutex<IdTypeMap>> = OnceLock::new();
 
-type Cache<G> = LruCache<Key<G>, Arc<Mutex<Promise<Shapes<<G as Graph>::Ctx>>>>>;
+/// The style sheet which operations are drawn with.
+static STYLE_SHEET: OnceLock<Mutex<Arc<StyleSheet>>> = OnceLock::new();
+
+type Cache<G> =
+    LruCache<(Key<G>, Orientation, bool, bool), Arc<Mutex<Promise<Shapes<<G as Graph>::Ctx>>>>>;
 
 fn shape_cache<G>() -> Arc<Mutex<Cache<G>>>
 where
@@ -42,21 +52,39 @@ pub fn clear_shape_cache() {
     }
 }
 
-pub fn generate_shapes<G>(graph: &G, solver: Solver) -> Arc<Mutex<Promise<Shapes<G::Ctx>>>>
+/// Draw operations with a new style sheet, regenerating all the shapes.
+pub fn set_style_sheet(style_sheet: StyleSheet) {
+    *STYLE_SHEET.get_or_init(Mutex::default).lock().unwrap() = Arc::new(style_sheet);
+    clear_shape_cache();
+}
+
+pub fn generate_shapes<G>(
+    graph: &G,
+    solver: Solver,
+    orientation: Orientation,
+    wire_labels: bool,
+    exact_swaps: bool,
+) -> Arc<Mutex<Promise<Shapes<G::Ctx>>>>
 where
     G: Graph + 'static,
     Edge<G::Ctx>: ExtensibleEdge,
     Operation<G::Ctx>: Shapeable,
     Weight<Operation<G::Ctx>>: Display,
+    Weight<Edge<G::Ctx>>: Display,
 {
     let cache = shape_cache::<G>();
     let mut guard = cache.lock().unwrap();
     guard
-        .get_or_insert(graph.key(), || {
+        .get_or_insert((graph.key(), orientation, wire_labels, exact_swaps), || {
             let graph = graph.clone();
+            let style_sheet = STYLE_SHEET
+                .get_or_init(Mutex::default)
+                .lock()
+                .unwrap()
+                .clone();
             Arc::new(Mutex::new(crate::spawn!("shape", {
                 tracing::info!("Converting to monoidal term");
-                let monoidal_term = from_graph(&graph, solver);
+                let monoidal_term = from_graph(&graph, solver, exact_swaps);
                 tracing::debug!("Got term {:#?}", monoidal_term);
 
                 tracing::info!("Inserting swaps and copies");
@@ -64,15 +92,21 @@ where
                 tracing::debug!("Got graph {:#?}", monoidal_graph);
 
                 tracing::info!("Calculating layout...");
-                let layout = layout(&monoidal_graph, solver).unwrap();
+                let layout = layout(
+                    &monoidal_graph,
+                    solver,
+                    orientation,
+                    wire_labels,
+                    &style_sheet,
+                )
+                .unwrap();
                 tracing::info!("Calculating shapes...");
                 let mut shapes = Vec::new();
-                render::generate_shapes(&mut shapes, &layout, true);
+                render::generate_shapes(&mut shapes, &layout, true, &style_sheet);
                 tracing::debug!("Generated {} shapes...", shapes.len());
-                Shapes {
-                    shapes,
-                    size: layout.size(),
-                }
+                let mut shapes = Shapes::new(shapes, layout.size());
+                shapes.orient(orientation);
+                shapes
             })))
         })
         .clone()
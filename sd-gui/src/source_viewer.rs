@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+
+use eframe::egui::{self, Align, RichText};
+
+/// A view of the local source file which the inspected operation came from, highlighting its line.
+#[derive(Default)]
+pub(crate) struct SourceViewer {
+    /// The directory which relative paths are resolved against, such as that of the file last imported.
+    pub(crate) base: Option<PathBuf>,
+    /// The file shown, with its contents or why they could not be read.
+    file: Option<(String, Result<String, String>)>,
+    line: usize,
+    /// Whether to scroll to the highlighted line on the next frame.
+    scroll: bool,
+}
+
+impl SourceViewer {
+    /// Show the file and line of `location`, or those last shown if it is `None`.
+    pub(crate) fn ui(&mut self, ui: &mut egui::Ui, location: Option<(String, usize)>) {
+        if let Some((file, line)) = location {
+            if self.file.as_ref().map(|(shown, _)| shown) != Some(&file) {
+                let contents =
+                    std::fs::read_to_string(self.resolve(&file)).map_err(|err| err.to_string());
+                self.file = Some((file, contents));
+                self.scroll = true;
+            }
+            if line != self.line {
+                self.line = line;
+                self.scroll = true;
+            }
+        }
+
+        let Some((file, contents)) = &self.file else {
+            ui.weak("Click on an operation with a source location to show it here.");
+            return;
+        };
+        ui.label(RichText::new(format!("{file}:{}", self.line)).strong());
+        ui.separator();
+        match contents {
+            Err(err) => {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("Could not read {file}: {err}"),
+                );
+            }
+            Ok(contents) => {
+                egui::ScrollArea::both()
+                    .id_source("source")
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        ui.style_mut().wrap = Some(false);
+                        ui.spacing_mut().item_spacing.y = 0.0;
+                        let width = contents.lines().count().to_string().len();
+                        for (number, text) in (1..).zip(contents.lines()) {
+                            let text =
+                                RichText::new(format!("{number:>width$}  {text}")).monospace();
+                            if number == self.line {
+                                let visuals = ui.visuals();
+                                let text = text
+                                    .background_color(visuals.selection.bg_fill)
+                                    .color(visuals.selection.stroke.color);
+                                let response = ui.label(text);
+                                if self.scroll {
+                                    response.scroll_to_me(Some(Align::Center));
+                                    self.scroll = false;
+                                }
+                            } else {
+                                ui.label(text);
+                            }
+                        }
+                    });
+            }
+        }
+    }
+
+    fn resolve(&self, file: &str) -> PathBuf {
+        match &self.base {
+            // Absolute paths replace the base when joined
+            Some(base) => base.join(file),
+            None => PathBuf::from(file),
+        }
+    }
+}
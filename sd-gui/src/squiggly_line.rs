@@ -2,10 +2,7 @@ use eframe::{
     egui::{self, show_tooltip_at_pointer, text_edit::TextEditOutput, Id, RichText},
     epaint::{Color32, FontId, Pos2, QuadraticBezierShape, Stroke},
 };
-use pest::{
-    error::{Error, LineColLocation},
-    RuleType,
-};
+use pest::error::LineColLocation;
 
 fn is_in_line(cursor: usize, line_col: &LineColLocation) -> bool {
     // Pest lines are 1 indexed, egui are 0 ☹
@@ -22,9 +19,14 @@ fn lines_contained(line_col: &LineColLocation) -> Vec<usize> {
     }
 }
 
-pub fn show_parse_error(ui: &egui::Ui, err: &Error<impl RuleType>, text_edit_out: &TextEditOutput) {
+pub fn show_parse_error(
+    ui: &egui::Ui,
+    line_col: &LineColLocation,
+    message: &str,
+    text_edit_out: &TextEditOutput,
+) {
     let painter = ui.painter();
-    for l in lines_contained(&err.line_col) {
+    for l in lines_contained(line_col) {
         if let Some(row) = text_edit_out.galley.rows.get(l) {
             // Draw squiggly line under error line
             const SQUIGGLE_HEIGHT: f32 = 5.0;
@@ -63,9 +65,9 @@ pub fn show_parse_error(ui: &egui::Ui, err: &Error<impl RuleType>, text_edit_out
 
         if text_edit_out.galley.rect.contains((pos.x, pos.y).into()) {
             let cursor = text_edit_out.galley.cursor_from_pos(pos);
-            if is_in_line(cursor.rcursor.row, &err.line_col) {
+            if is_in_line(cursor.rcursor.row, line_col) {
                 show_tooltip_at_pointer(ui.ctx(), Id::new("hover_tooltip"), |ui| {
-                    ui.label(RichText::new(err.to_string()).font(FontId::monospace(13.5)))
+                    ui.label(RichText::new(message).font(FontId::monospace(13.5)))
                 });
             }
         }